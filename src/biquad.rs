@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Mikael Lund
+
+//! Second-order IIR low-pass filter applied to the mixed output.
+
+use std::f32::consts::PI;
+
+/// Lowest and highest cutoff as a fraction of the Nyquist frequency
+const MIN_CUTOFF: f32 = 0.01;
+const MAX_CUTOFF: f32 = 0.99;
+/// Q at zero resonance (Butterworth, no peak) and at full resonance
+const MIN_Q: f32 = std::f32::consts::FRAC_1_SQRT_2;
+const MAX_Q: f32 = 10.0;
+
+/// Cookbook biquad low-pass filter (Direct Form I).
+#[derive(Debug, Clone, Default)]
+pub struct BiquadFilter {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl BiquadFilter {
+    /// Creates a low-pass filter at `cutoff_pct` of the Nyquist frequency.
+    /// `resonance_pct` (0.0-1.0) raises Q from a flat response to a sharp peak.
+    pub fn low_pass(cutoff_pct: f32, resonance_pct: f32, sample_rate: u32) -> Self {
+        let mut filter = Self::default();
+        filter.set_low_pass(cutoff_pct, resonance_pct, sample_rate);
+        filter
+    }
+
+    /// Recalculates the coefficients using the bilinear transform, keeping the
+    /// filter state so changes don't click.
+    pub fn set_low_pass(&mut self, cutoff_pct: f32, resonance_pct: f32, sample_rate: u32) {
+        #[allow(clippy::cast_precision_loss)]
+        let nyquist = sample_rate as f32 / 2.0;
+        let cutoff = cutoff_pct.clamp(MIN_CUTOFF, MAX_CUTOFF) * nyquist;
+        let q = (MAX_Q - MIN_Q).mul_add(resonance_pct.clamp(0.0, 1.0), MIN_Q);
+
+        #[allow(clippy::cast_precision_loss)]
+        let w0 = 2.0 * PI * cutoff / sample_rate as f32;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let a0 = 1.0 + alpha;
+
+        self.b0 = (1.0 - cos) / 2.0 / a0;
+        self.b1 = (1.0 - cos) / a0;
+        self.b2 = self.b0;
+        self.a1 = -2.0 * cos / a0;
+        self.a2 = (1.0 - alpha) / a0;
+    }
+
+    /// Filters one sample.
+    pub fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2
+            - self.a1 * self.y1
+            - self.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gain in dB of a sine at `freq` Hz after the filter has settled.
+    fn gain_db(filter: &mut BiquadFilter, freq: f32, sample_rate: u32) -> f32 {
+        #[allow(clippy::cast_precision_loss)]
+        let rate = sample_rate as f32;
+        let (mut input, mut output) = (0.0f32, 0.0f32);
+        for n in 0..sample_rate {
+            #[allow(clippy::cast_precision_loss)]
+            let x = (2.0 * PI * freq * n as f32 / rate).sin();
+            let y = filter.process(x);
+            // Skip the first half while the filter settles
+            if n > sample_rate / 2 {
+                input += x * x;
+                output += y * y;
+            }
+        }
+        10.0 * (output / input).log10()
+    }
+
+    #[test]
+    fn low_pass_attenuates_above_cutoff() {
+        let rate = 44_100;
+        // Cutoff at 2205 Hz
+        let mut filter = BiquadFilter::low_pass(0.1, 0.0, rate);
+        let stop = gain_db(&mut filter, 10_000.0, rate);
+        assert!(stop <= -20.0, "{stop} dB at 10 kHz");
+
+        let mut filter = BiquadFilter::low_pass(0.1, 0.0, rate);
+        let pass = gain_db(&mut filter, 200.0, rate);
+        assert!(pass.abs() < 1.0, "{pass} dB at 200 Hz");
+    }
+}
@@ -3,38 +3,420 @@
 
 //! User configuration persistence.
 
+use crate::hvsc::DEFAULT_HVSC_URL;
+use crate::player::{MixMode, SamplingMethod};
+use crate::tui::theme::{ColorScheme, SCHEMES};
+use ratatui::symbols::Marker;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+/// Config schema version written by this build.
+const CURRENT_SCHEMA_VERSION: u32 = 1;
 
 /// Default color scheme index (Gruvbox Dark Hard).
 const fn default_color_scheme() -> usize {
     12
 }
 
+/// Sample rates the audio output accepts, in Hz.
+const SAMPLE_RATE_RANGE: RangeInclusive<u32> = 8_000..=192_000;
+
+/// Default audio output sample rate in Hz.
+const fn default_sample_rate() -> u32 {
+    crate::SAMPLE_RATE
+}
+
+/// Default audio buffer size in samples.
+const fn default_buffer_size() -> usize {
+    crate::BUFFER_SIZE
+}
+
+/// Default output gain (full volume).
+const fn default_master_volume() -> f32 {
+    1.0
+}
+
+/// Default output level below which a tune counts as silent.
+const fn default_silence_threshold() -> f32 {
+    0.001
+}
+
+/// Default seconds of silence before skipping to the next tune.
+const fn default_silence_timeout_secs() -> u64 {
+    3
+}
+
+/// Default retries for transient HVSC database download failures.
+const fn default_http_max_retries() -> u32 {
+    crate::hvsc::DEFAULT_HTTP_MAX_RETRIES
+}
+
+/// Default instruction limit for a tune's init routine.
+const fn default_init_max_steps() -> u32 {
+    crate::player::DEFAULT_INIT_MAX_STEPS
+}
+
+/// Default SID download timeout in seconds.
+const fn default_hvsc_fetch_timeout_secs() -> u64 {
+    crate::hvsc::DEFAULT_FETCH_TIMEOUT.as_secs()
+}
+
+/// Default HVSC mirror list (the primary mirror only).
+fn default_hvsc_mirrors() -> Vec<String> {
+    vec![DEFAULT_HVSC_URL.to_string()]
+}
+
+/// Returns the config key for a subsong chip override.
+pub fn subsong_key(md5: &str, song: u16) -> String {
+    format!("{md5}@{song}")
+}
+
+/// Sampling methods selectable in the TUI, by config name.
+pub const SAMPLING_METHODS: [(&str, SamplingMethod); 5] = [
+    ("Fast", SamplingMethod::Fast),
+    ("Interpolate", SamplingMethod::Interpolate),
+    ("ResampleFast", SamplingMethod::ResampleFast),
+    ("Resample", SamplingMethod::Resample),
+    ("ResampleTwoPass", SamplingMethod::ResampleTwoPass),
+];
+
+/// SID mixing strategies selectable in the TUI, by config name.
+pub const MIX_MODES: [(&str, MixMode); 3] = [
+    ("WeightedAverage", MixMode::WeightedAverage),
+    ("SumWithClip", MixMode::SumWithClip),
+    ("SumWithNormalize", MixMode::SumWithNormalize),
+];
+
+/// Default mix mode name.
+fn default_mix_mode() -> String {
+    "WeightedAverage".to_string()
+}
+
+/// Parses a config mix mode name, falling back to WeightedAverage.
+pub fn parse_mix_mode(s: &str) -> MixMode {
+    MIX_MODES
+        .iter()
+        .find(|(name, _)| *name == s)
+        .map_or(MixMode::WeightedAverage, |&(_, mode)| mode)
+}
+
+/// Default sampling method name.
+fn default_sampling_method() -> String {
+    "Interpolate".to_string()
+}
+
+/// Parses a config sampling method name, falling back to Interpolate.
+pub fn parse_sampling_method(s: &str) -> SamplingMethod {
+    SAMPLING_METHODS
+        .iter()
+        .find(|(name, _)| *name == s)
+        .map_or(SamplingMethod::Interpolate, |&(_, method)| method)
+}
+
+/// Returns the config name of a sampling method.
+pub const fn sampling_method_name(method: SamplingMethod) -> &'static str {
+    match method {
+        SamplingMethod::Fast => "Fast",
+        SamplingMethod::Interpolate => "Interpolate",
+        SamplingMethod::ResampleFast => "ResampleFast",
+        SamplingMethod::Resample => "Resample",
+        SamplingMethod::ResampleTwoPass => "ResampleTwoPass",
+    }
+}
+
+/// Oscilloscope canvas markers by config name.
+pub const SCOPE_MARKERS: [(&str, Marker); 5] = [
+    ("Braille", Marker::Braille),
+    ("Block", Marker::Block),
+    ("Dot", Marker::Dot),
+    ("Bar", Marker::Bar),
+    ("HalfBlock", Marker::HalfBlock),
+];
+
+/// Default oscilloscope marker name.
+fn default_scope_marker() -> String {
+    "Braille".to_string()
+}
+
+/// Parses an oscilloscope marker name (case-insensitive), defaulting to Braille.
+pub fn parse_scope_marker(s: &str) -> Marker {
+    SCOPE_MARKERS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        .map_or(Marker::Braille, |&(_, marker)| marker)
+}
+
+/// The tune playing when the app last exited.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerStateRef {
+    /// Path or URL the SID file was loaded from
+    #[serde(default)]
+    pub source: String,
+    /// MD5 of the SID file, checked against the file at `source`
+    pub md5: String,
+    /// Song number (1-indexed)
+    pub song: u16,
+}
+
+/// A setting replaced by a `CRABSID_*` variable.
+#[derive(Debug, Clone)]
+struct Overridden<T> {
+    /// Value from the config file
+    file: T,
+    /// Value from the environment
+    env: T,
+}
+
+impl<T: Clone + PartialEq> Overridden<T> {
+    /// Replaces `setting` with `env`, remembering the file value.
+    fn replace(setting: &mut T, env: T) -> Self {
+        let file = std::mem::replace(setting, env.clone());
+        Self { file, env }
+    }
+
+    /// Puts the file value back unless the setting changed since the override.
+    fn restore(&self, setting: &mut T) {
+        if *setting == self.env {
+            setting.clone_from(&self.file);
+        }
+    }
+}
+
+/// Settings taken from the environment, which `save` leaves out of the file.
+#[derive(Debug, Clone, Default)]
+struct EnvOverrides {
+    sample_rate: Option<Overridden<u32>>,
+    buffer_size: Option<Overridden<usize>>,
+    master_volume: Option<Overridden<f32>>,
+    color_scheme: Option<Overridden<usize>>,
+    custom_color_scheme: Option<Overridden<Option<String>>>,
+    hvsc_mirrors: Option<Overridden<Vec<String>>>,
+}
+
 /// User configuration stored in config file.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
-    /// Selected color scheme index
+    /// Config file schema version (missing means 0)
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Selected built-in color scheme index (see `custom_color_scheme`)
     #[serde(default = "default_color_scheme")]
     pub color_scheme: usize,
+    /// HVSC mirrors tried in order when one fails
+    #[serde(default = "default_hvsc_mirrors")]
+    pub hvsc_mirrors: Vec<String>,
+    /// Seconds allowed for downloading a SID file from HVSC
+    #[serde(default = "default_hvsc_fetch_timeout_secs")]
+    pub hvsc_fetch_timeout_secs: u64,
+    /// Retries after an HTTP 5xx or connection error when downloading databases
+    #[serde(default = "default_http_max_retries")]
+    pub http_max_retries: u32,
+    /// Instructions a tune's init routine may run before it counts as hung
+    #[serde(default = "default_init_max_steps")]
+    pub init_max_steps: u32,
+    /// SID resampling method name (see `SAMPLING_METHODS`)
+    #[serde(default = "default_sampling_method")]
+    pub sampling_method: String,
+    /// How multiple SIDs are mixed (see `MIX_MODES`)
+    #[serde(default = "default_mix_mode")]
+    pub mix_mode: String,
+    /// Per-subsong chip models (6581/8580), keyed by `<md5>@<song>`
+    #[serde(default)]
+    pub subsong_chip_overrides: BTreeMap<String, u16>,
+    /// Audio output sample rate in Hz
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: u32,
+    /// Audio buffer size in samples
+    #[serde(default = "default_buffer_size")]
+    pub buffer_size: usize,
+    /// Output gain (0.0-1.0)
+    #[serde(default = "default_master_volume")]
+    pub master_volume: f32,
+    /// Tune to resume on startup when no files are given
+    #[serde(default)]
+    pub last_session: Option<PlayerStateRef>,
+    /// RGB voice colors replacing a color scheme's, keyed by scheme name (set in the TUI with Ctrl+V)
+    #[serde(default)]
+    pub custom_voice_colors: BTreeMap<String, [[u8; 3]; 9]>,
+    /// Oscilloscope marker name (see `SCOPE_MARKERS`)
+    #[serde(default = "default_scope_marker")]
+    pub scope_marker: String,
+    /// Color schemes imported with `--import-theme`, listed after the built-in ones
+    #[serde(default)]
+    pub custom_schemes: Vec<ColorScheme>,
+    /// Name of the selected imported scheme, used instead of `color_scheme` when set
+    #[serde(default)]
+    pub custom_color_scheme: Option<String>,
+    /// Output level (0.0-1.0) below which playback counts as silent
+    #[serde(default = "default_silence_threshold")]
+    pub silence_threshold: f32,
+    /// Seconds a tune may stay silent from its start before auto-advancing (0 disables)
+    #[serde(default = "default_silence_timeout_secs")]
+    pub silence_timeout_secs: u64,
+    /// Sources of the last playlist entries played, oldest first (for `--smart-shuffle`)
+    #[serde(default)]
+    pub recently_played: Vec<String>,
+    /// Lines entered in command mode (`:`), oldest first
+    #[serde(default)]
+    pub command_history: Vec<String>,
+    /// File values of the settings `apply_env_overrides` replaced
+    #[serde(skip)]
+    env_overrides: EnvOverrides,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
             color_scheme: default_color_scheme(),
+            hvsc_mirrors: default_hvsc_mirrors(),
+            hvsc_fetch_timeout_secs: default_hvsc_fetch_timeout_secs(),
+            http_max_retries: default_http_max_retries(),
+            init_max_steps: default_init_max_steps(),
+            sampling_method: default_sampling_method(),
+            mix_mode: default_mix_mode(),
+            subsong_chip_overrides: BTreeMap::new(),
+            sample_rate: default_sample_rate(),
+            buffer_size: default_buffer_size(),
+            master_volume: default_master_volume(),
+            last_session: None,
+            custom_voice_colors: BTreeMap::new(),
+            scope_marker: default_scope_marker(),
+            custom_schemes: Vec::new(),
+            custom_color_scheme: None,
+            silence_threshold: default_silence_threshold(),
+            silence_timeout_secs: default_silence_timeout_secs(),
+            recently_played: Vec::new(),
+            command_history: Vec::new(),
+            env_overrides: EnvOverrides::default(),
         }
     }
 }
 
 impl Config {
     /// Loads config from file, returning defaults if not found or invalid.
+    /// `CRABSID_*` environment variables override the file values.
     pub fn load() -> Self {
-        config_path()
-            .and_then(|p| fs::read_to_string(p).ok())
-            .and_then(|s| toml::from_str(&s).ok())
-            .unwrap_or_default()
+        let mut config = config_path().map_or_else(Self::default, |p| Self::load_from(&p));
+        config.reject_invalid_values();
+        config.apply_env_overrides();
+        config
+    }
+
+    /// Replaces file values that would break playback with their defaults.
+    fn reject_invalid_values(&mut self) {
+        if !SAMPLE_RATE_RANGE.contains(&self.sample_rate) {
+            eprintln!(
+                "ignoring sample_rate = {}: must be {}-{} Hz",
+                self.sample_rate,
+                SAMPLE_RATE_RANGE.start(),
+                SAMPLE_RATE_RANGE.end()
+            );
+            self.sample_rate = default_sample_rate();
+        }
+        if self.init_max_steps == 0 {
+            eprintln!("ignoring init_max_steps = 0: must be at least 1");
+            self.init_max_steps = default_init_max_steps();
+        }
+    }
+
+    /// Applies `CRABSID_SAMPLE_RATE`, `CRABSID_BUFFER_SIZE`, `CRABSID_VOLUME`,
+    /// `CRABSID_COLOR_SCHEME` (scheme name) and `CRABSID_HVSC_URL` if set.
+    /// Invalid values are reported on stderr and ignored. Overrides last
+    /// for this run only: `save` writes the file values back.
+    pub fn apply_env_overrides(&mut self) {
+        let overrides = &mut self.env_overrides;
+        if let Some(rate) = env_override("CRABSID_SAMPLE_RATE", |v| {
+            v.parse().ok().filter(|r| SAMPLE_RATE_RANGE.contains(r))
+        }) {
+            overrides.sample_rate = Some(Overridden::replace(&mut self.sample_rate, rate));
+        }
+        if let Some(size) = env_override("CRABSID_BUFFER_SIZE", |v| {
+            v.parse().ok().filter(|&n: &usize| n > 0)
+        }) {
+            overrides.buffer_size = Some(Overridden::replace(&mut self.buffer_size, size));
+        }
+        if let Some(volume) = env_override("CRABSID_VOLUME", |v| {
+            v.parse().ok().filter(|n: &f32| (0.0..=1.0).contains(n))
+        }) {
+            overrides.master_volume = Some(Overridden::replace(&mut self.master_volume, volume));
+        }
+        if let Some((index, custom)) = env_override("CRABSID_COLOR_SCHEME", |v| {
+            let matches = |s: &&ColorScheme| s.name.eq_ignore_ascii_case(v);
+            SCHEMES
+                .iter()
+                .position(|s| matches(&s))
+                .map(|index| (index, None))
+                .or_else(|| {
+                    let custom = self.custom_schemes.iter().find(matches)?;
+                    Some((self.color_scheme, Some(custom.name.to_string())))
+                })
+        }) {
+            overrides.color_scheme = Some(Overridden::replace(&mut self.color_scheme, index));
+            overrides.custom_color_scheme =
+                Some(Overridden::replace(&mut self.custom_color_scheme, custom));
+        }
+        if let Some(url) = env_override("CRABSID_HVSC_URL", |v| {
+            Some(v.trim_end_matches('/').to_string()).filter(|u| !u.is_empty())
+        }) {
+            // The override becomes the first mirror tried
+            let mut mirrors = self.hvsc_mirrors.clone();
+            mirrors.retain(|m| *m != url);
+            mirrors.insert(0, url);
+            overrides.hvsc_mirrors = Some(Overridden::replace(&mut self.hvsc_mirrors, mirrors));
+        }
+    }
+
+    /// Returns the config as it should be saved, with environment overrides
+    /// that are still in effect replaced by the file values.
+    fn without_env_overrides(&self) -> Self {
+        let mut config = self.clone();
+        let overrides = std::mem::take(&mut config.env_overrides);
+        if let Some(o) = &overrides.sample_rate {
+            o.restore(&mut config.sample_rate);
+        }
+        if let Some(o) = &overrides.buffer_size {
+            o.restore(&mut config.buffer_size);
+        }
+        if let Some(o) = &overrides.master_volume {
+            o.restore(&mut config.master_volume);
+        }
+        if let Some(o) = &overrides.color_scheme {
+            o.restore(&mut config.color_scheme);
+        }
+        if let Some(o) = &overrides.custom_color_scheme {
+            o.restore(&mut config.custom_color_scheme);
+        }
+        if let Some(o) = &overrides.hvsc_mirrors {
+            o.restore(&mut config.hvsc_mirrors);
+        }
+        config
+    }
+
+    /// Loads config from `path`, migrating older schemas and writing them back.
+    /// Unparseable files are moved to `config.toml.bak` and defaults are used.
+    fn load_from(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        let Ok(mut value) = toml::from_str::<toml::Value>(&content) else {
+            backup_config(path);
+            return Self::default();
+        };
+        let version = schema_version(&value);
+        if version < CURRENT_SCHEMA_VERSION {
+            value = migrate_config(value, version);
+            if let Ok(migrated) = toml::to_string_pretty(&value) {
+                let _ = fs::write(path, migrated);
+            }
+        }
+        value.try_into().unwrap_or_else(|_| {
+            backup_config(path);
+            Self::default()
+        })
     }
 
     /// Saves config to file (best-effort, errors ignored).
@@ -42,13 +424,227 @@ impl Config {
         let Some(path) = config_path() else { return };
         let Some(parent) = path.parent() else { return };
         let _ = fs::create_dir_all(parent);
-        if let Ok(content) = toml::to_string_pretty(self) {
+        if let Ok(content) = toml::to_string_pretty(&self.without_env_overrides()) {
             let _ = fs::write(&path, content);
         }
     }
 }
 
+/// Reads and parses environment variable `name`, warning if it is set but invalid.
+fn env_override<T>(name: &str, parse: impl FnOnce(&str) -> Option<T>) -> Option<T> {
+    let raw = std::env::var(name).ok()?;
+    let parsed = parse(raw.trim());
+    if parsed.is_none() {
+        eprintln!("ignoring {name}={raw:?}: unrecognized value");
+    }
+    parsed
+}
+
+/// Reads the schema version from a parsed config (0 if absent).
+fn schema_version(value: &toml::Value) -> u32 {
+    value
+        .get("schema_version")
+        .and_then(toml::Value::as_integer)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(0)
+}
+
+/// Returns true if the config file at `path` uses an older schema version.
+#[cfg(test)]
+pub fn needs_migration(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| toml::from_str::<toml::Value>(&s).ok())
+        .is_some_and(|v| schema_version(&v) < CURRENT_SCHEMA_VERSION)
+}
+
+/// Upgrades a config document from schema version `from` to the current one.
+/// Running it on an already current document leaves it unchanged.
+pub fn migrate_config(mut value: toml::Value, from: u32) -> toml::Value {
+    let Some(table) = value.as_table_mut() else {
+        return value;
+    };
+    if from < 1 {
+        // v0 -> v1: color_scheme may be given as a scheme name
+        if let Some(name) = table.get("color_scheme").and_then(toml::Value::as_str) {
+            let index = SCHEMES
+                .iter()
+                .position(|s| s.name.eq_ignore_ascii_case(name))
+                .unwrap_or_else(default_color_scheme);
+            table.insert(
+                "color_scheme".to_string(),
+                toml::Value::Integer(i64::try_from(index).unwrap_or_default()),
+            );
+        }
+    }
+    table.insert(
+        "schema_version".to_string(),
+        toml::Value::Integer(i64::from(CURRENT_SCHEMA_VERSION.max(from))),
+    );
+    value
+}
+
+/// Moves an unreadable config file aside to `config.toml.bak`.
+fn backup_config(path: &Path) {
+    let _ = fs::rename(path, path.with_extension("toml.bak"));
+}
+
 /// Returns the config file path (~/.config/crabsid/config.toml).
 fn config_path() -> Option<PathBuf> {
     dirs::config_dir().map(|d| d.join("crabsid").join("config.toml"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampling_method_roundtrips_through_toml() {
+        let config = Config {
+            sampling_method: "ResampleTwoPass".to_string(),
+            ..Config::default()
+        };
+        let text = toml::to_string_pretty(&config).expect("serialize config");
+        let loaded: Config = toml::from_str(&text).expect("parse config");
+        assert_eq!(loaded.sampling_method, "ResampleTwoPass");
+        assert_eq!(loaded.color_scheme, config.color_scheme);
+        assert_eq!(loaded.hvsc_mirrors, config.hvsc_mirrors);
+    }
+
+    #[test]
+    fn custom_schemes_roundtrip_through_toml() {
+        let mut config = Config::default();
+        config.custom_schemes.push(SCHEMES[1].clone());
+        config.last_session = Some(PlayerStateRef {
+            source: "/music/tune.sid".to_string(),
+            md5: "abc123".to_string(),
+            song: 2,
+        });
+        let text = toml::to_string_pretty(&config).expect("serialize config");
+        assert!(text.contains("[[custom_schemes]]"));
+        let loaded: Config = toml::from_str(&text).expect("parse config");
+        assert_eq!(loaded.custom_schemes, config.custom_schemes);
+    }
+
+    #[test]
+    fn subsong_overrides_roundtrip_through_toml() {
+        let mut config = Config::default();
+        config
+            .subsong_chip_overrides
+            .insert(subsong_key("abc123", 2), 8580);
+        let text = toml::to_string_pretty(&config).expect("serialize config");
+        let loaded: Config = toml::from_str(&text).expect("parse config");
+        assert_eq!(loaded.subsong_chip_overrides.get("abc123@2"), Some(&8580));
+    }
+
+    #[test]
+    fn v0_config_migrates_to_current_schema() {
+        let v0: toml::Value =
+            toml::from_str("color_scheme = \"dracula\"\nsampling_method = \"Fast\"").unwrap();
+        let migrated = migrate_config(v0, 0);
+        assert_eq!(schema_version(&migrated), CURRENT_SCHEMA_VERSION);
+        let config: Config = migrated.clone().try_into().expect("migrated config");
+        assert_eq!(SCHEMES[config.color_scheme].name, "Dracula");
+        assert_eq!(config.sampling_method, "Fast");
+        assert_eq!(migrate_config(migrated.clone(), 1), migrated);
+    }
+
+    #[test]
+    fn load_migrates_and_backs_up() {
+        let dir = std::env::temp_dir().join(format!("crabsid-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+
+        fs::write(&path, "color_scheme = 3").unwrap();
+        assert!(needs_migration(&path));
+        assert_eq!(Config::load_from(&path).color_scheme, 3);
+        assert!(!needs_migration(&path));
+
+        fs::write(&path, "color_scheme = [unclosed").unwrap();
+        let config = Config::load_from(&path);
+        assert_eq!(config.color_scheme, default_color_scheme());
+        assert!(!path.exists());
+        assert!(dir.join("config.toml.bak").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// Runs `f` with environment variables set, removing them afterwards.
+    fn with_env(vars: &[(&str, &str)], f: impl FnOnce()) {
+        for (name, value) in vars {
+            // SAFETY: only this test touches CRABSID_* variables
+            unsafe { std::env::set_var(name, value) };
+        }
+        f();
+        for (name, _) in vars {
+            // SAFETY: as above
+            unsafe { std::env::remove_var(name) };
+        }
+    }
+
+    #[test]
+    fn env_overrides_apply_to_config() {
+        let vars = [
+            ("CRABSID_VOLUME", "0.5"),
+            ("CRABSID_COLOR_SCHEME", "monokai"),
+            ("CRABSID_SAMPLE_RATE", "not-a-number"),
+            ("CRABSID_HVSC_URL", "https://mirror.example/hvsc/"),
+        ];
+        with_env(&vars, || {
+            let mut config = Config::default();
+            config.apply_env_overrides();
+            assert_eq!(config.master_volume, 0.5);
+            assert_eq!(SCHEMES[config.color_scheme].name, "Monokai");
+            assert_eq!(config.sample_rate, default_sample_rate());
+            assert_eq!(config.hvsc_mirrors[0], "https://mirror.example/hvsc");
+
+            // Overrides are not saved, but changes made during the session are
+            config.color_scheme = 2;
+            let saved = config.without_env_overrides();
+            assert_eq!(saved.master_volume, default_master_volume());
+            assert_eq!(saved.hvsc_mirrors, default_hvsc_mirrors());
+            assert_eq!(saved.color_scheme, 2);
+        });
+    }
+
+    #[test]
+    fn out_of_range_sample_rate_falls_back_to_default() {
+        let mut config: Config = toml::from_str("sample_rate = 0").expect("parse config");
+        config.reject_invalid_values();
+        assert_eq!(config.sample_rate, default_sample_rate());
+    }
+
+    #[test]
+    fn zero_init_max_steps_falls_back_to_default() {
+        let mut config: Config = toml::from_str("init_max_steps = 0").expect("parse config");
+        config.reject_invalid_values();
+        assert_eq!(config.init_max_steps, default_init_max_steps());
+    }
+
+    #[test]
+    fn missing_sampling_method_uses_default() {
+        let loaded: Config = toml::from_str("color_scheme = 3").expect("parse config");
+        assert_eq!(loaded.sampling_method, "Interpolate");
+    }
+
+    #[test]
+    fn scope_marker_names_parse() {
+        for (name, marker) in SCOPE_MARKERS {
+            assert_eq!(parse_scope_marker(name), marker);
+            assert_eq!(parse_scope_marker(&name.to_lowercase()), marker);
+        }
+        assert_eq!(parse_scope_marker("bogus"), Marker::Braille);
+        assert_eq!(Config::default().scope_marker, "Braille");
+    }
+
+    #[test]
+    fn sampling_method_names_roundtrip() {
+        for (name, method) in SAMPLING_METHODS {
+            assert_eq!(sampling_method_name(parse_sampling_method(name)), name);
+            assert_eq!(sampling_method_name(method), name);
+        }
+        assert_eq!(
+            sampling_method_name(parse_sampling_method("bogus")),
+            "Interpolate"
+        );
+    }
+}
@@ -4,40 +4,145 @@
 //! HVSC (High Voltage SID Collection) browser with STIL metadata support.
 
 use crate::sid_file::SidFile;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
 use std::collections::HashMap;
+use std::fmt;
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Default number of retries for transient HTTP failures.
+pub const DEFAULT_HTTP_MAX_RETRIES: u32 = 3;
+
+/// Delays before each retry; later retries reuse the last one.
+const RETRY_DELAYS: [Duration; 3] = [
+    Duration::from_millis(100),
+    Duration::from_millis(500),
+    Duration::from_millis(2000),
+];
+
+/// Retries used by the database downloads, set from the config at startup.
+static HTTP_MAX_RETRIES: AtomicU32 = AtomicU32::new(DEFAULT_HTTP_MAX_RETRIES);
+
+/// Sets how often database downloads are retried after a transient failure.
+pub fn set_http_max_retries(max_retries: u32) {
+    HTTP_MAX_RETRIES.store(max_retries, Ordering::Relaxed);
+}
 
-/// Fetches bytes from a URL (http/https) or local path (file://).
-fn fetch_bytes(url: &str) -> io::Result<Vec<u8>> {
+/// Fetches bytes from a URL (http/https), local path (file://) or base64 `data:` URI.
+pub fn fetch_bytes(url: &str) -> io::Result<Vec<u8>> {
     if let Some(path) = url.strip_prefix("file://") {
         std::fs::read(Path::new(path))
+    } else if let Some(uri) = url.strip_prefix("data:") {
+        decode_data_uri(uri)
     } else {
-        let response = ureq::get(url)
-            .call()
-            .map_err(|e| io::Error::other(e.to_string()))?;
-        let mut bytes = Vec::new();
-        response.into_body().into_reader().read_to_end(&mut bytes)?;
-        Ok(bytes)
+        fetch_http(url).map_err(|e| io::Error::other(e.to_string()))
+    }
+}
+
+/// Decodes the part of a `data:` URI after the scheme, e.g.
+/// `application/octet-stream;base64,UFNJRA...` or `;base64,UFNJRA...`.
+fn decode_data_uri(uri: &str) -> io::Result<Vec<u8>> {
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+    let (media_type, payload) = uri
+        .split_once(',')
+        .ok_or_else(|| invalid("data URI without ','".to_string()))?;
+    if !matches!(media_type, ";base64" | "application/octet-stream;base64") {
+        return Err(invalid(format!("unsupported data URI type: {media_type}")));
+    }
+    BASE64
+        .decode(payload.trim())
+        .map_err(|e| invalid(format!("bad base64 in data URI: {e}")))
+}
+
+/// Fetches the body of an HTTP(S) URL.
+fn fetch_http(url: &str) -> Result<Vec<u8>, ureq::Error> {
+    let response = ureq::get(url).call()?;
+    let mut bytes = Vec::new();
+    response.into_body().into_reader().read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// True for failures worth retrying: server errors and dropped connections.
+const fn is_transient(error: &ureq::Error) -> bool {
+    match error {
+        ureq::Error::StatusCode(status) => *status >= 500,
+        ureq::Error::Io(_) | ureq::Error::ConnectionFailed | ureq::Error::Timeout(_) => true,
+        _ => false,
+    }
+}
+
+/// Like `fetch_bytes`, but retrying HTTP 5xx and connection errors up to
+/// `max_retries` times with exponential backoff. Client errors fail at once.
+fn fetch_bytes_with_retry(url: &str, max_retries: u32) -> io::Result<Vec<u8>> {
+    if url.starts_with("file://") || url.starts_with("data:") {
+        return fetch_bytes(url);
+    }
+    let mut attempt = 0;
+    loop {
+        match fetch_http(url) {
+            Ok(bytes) => return Ok(bytes),
+            Err(e) if attempt < max_retries && is_transient(&e) => {
+                let delay = RETRY_DELAYS[(attempt as usize).min(RETRY_DELAYS.len() - 1)];
+                thread::sleep(with_jitter(delay));
+                attempt += 1;
+            }
+            Err(e) => return Err(io::Error::other(e.to_string())),
+        }
+    }
+}
+
+/// Adds up to 25% random jitter so clients don't retry in lockstep.
+fn with_jitter(delay: Duration) -> Duration {
+    delay + delay.mul_f64(rand::random_range(0.0..0.25))
+}
+
+/// Fetches bytes on a worker thread, giving up after `timeout`.
+/// A timed-out fetch is left to finish in the background and its result discarded.
+pub fn fetch_bytes_with_timeout(url: &str, timeout: Duration) -> io::Result<Vec<u8>> {
+    let url = url.to_string();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(fetch_bytes(&url));
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            Err(io::Error::new(io::ErrorKind::TimedOut, "fetch timeout"))
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(io::Error::other("fetch thread panicked")),
     }
 }
 
 /// Fetches text from a URL or local path, treating bytes as Latin-1.
 fn fetch_latin1_text(url: &str) -> io::Result<String> {
-    let bytes = fetch_bytes(url)?;
+    let bytes = fetch_bytes_with_retry(url, HTTP_MAX_RETRIES.load(Ordering::Relaxed))?;
     Ok(bytes.iter().map(|&b| b as char).collect())
 }
 
 /// Fetches text from a URL or local path as UTF-8.
 fn fetch_text(url: &str) -> io::Result<String> {
-    let bytes = fetch_bytes(url)?;
+    let bytes = fetch_bytes_with_retry(url, HTTP_MAX_RETRIES.load(Ordering::Relaxed))?;
     String::from_utf8(bytes).map_err(|e| io::Error::other(e.to_string()))
 }
 
+/// Default time allowed for fetching a single SID file.
+pub const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Default HVSC mirror URL.
 pub const DEFAULT_HVSC_URL: &str = "https://hvsc.brona.dk/HVSC/C64Music";
 
+/// Cache size above which a warning is printed (50 MB).
+const CACHE_WARN_BYTES: u64 = 50 * 1024 * 1024;
+
 /// Returns the cache directory for crabsid, creating it if needed.
 fn cache_dir() -> Option<PathBuf> {
     let dir = dirs::cache_dir()?.join("crabsid");
@@ -45,14 +150,53 @@ fn cache_dir() -> Option<PathBuf> {
     Some(dir)
 }
 
+/// Returns a warning to show if the cache directory has grown beyond 50 MB.
+pub fn cache_size_warning() -> Option<String> {
+    let dir = cache_dir()?;
+    let size = dir_size_bytes(&dir);
+    (size > CACHE_WARN_BYTES).then(|| {
+        format!(
+            "Cache {} uses {} MB (Ctrl+F or --refresh-cache clears it)",
+            dir.display(),
+            size / (1024 * 1024)
+        )
+    })
+}
+
+/// Returns the total size of all files in the cache directory.
+pub fn cache_size_bytes() -> u64 {
+    cache_dir().map_or(0, |dir| dir_size_bytes(&dir))
+}
+
+/// Recursively sums file sizes below a directory.
+fn dir_size_bytes(dir: &Path) -> u64 {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return 0;
+    };
+    read_dir
+        .filter_map(|e| e.ok())
+        .map(|e| match e.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size_bytes(&e.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
 /// Clears the HVSC cache files (STIL.txt and Songlengths.md5).
 pub fn clear_cache() {
     if let Some(dir) = cache_dir() {
-        let _ = fs::remove_file(dir.join("STIL.txt"));
-        let _ = fs::remove_file(dir.join("Songlengths.md5"));
+        clear_cache_in(&dir);
     }
 }
 
+/// Removes the cached database files from the given directory.
+fn clear_cache_in(dir: &Path) {
+    let _ = fs::remove_file(dir.join("STIL.txt"));
+    let _ = fs::remove_file(dir.join("STIL.idx"));
+    let _ = fs::remove_file(dir.join("Songlengths.md5"));
+}
+
 /// Reads a file as Latin-1 or UTF-8.
 fn read_file(path: &Path, latin1: bool) -> io::Result<String> {
     if latin1 {
@@ -65,7 +209,17 @@ fn read_file(path: &Path, latin1: bool) -> io::Result<String> {
 
 /// Reads cached file if present, otherwise fetches from URL and caches result.
 fn fetch_with_cache(url: &str, cache_name: &str, latin1: bool) -> io::Result<String> {
-    let cache_path = cache_dir().map(|d| d.join(cache_name));
+    fetch_with_cache_in(cache_dir().as_deref(), url, cache_name, latin1)
+}
+
+/// Like `fetch_with_cache`, but caching in an explicit directory.
+fn fetch_with_cache_in(
+    dir: Option<&Path>,
+    url: &str,
+    cache_name: &str,
+    latin1: bool,
+) -> io::Result<String> {
+    let cache_path = dir.map(|d| d.join(cache_name));
 
     // Try cache first
     if let Some(ref path) = cache_path
@@ -89,32 +243,123 @@ fn fetch_with_cache(url: &str, cache_name: &str, latin1: bool) -> io::Result<Str
     Ok(content)
 }
 
+/// Format version of the binary STIL index (`STIL.idx`).
+const STIL_INDEX_VERSION: u32 = 1;
+
+/// Leading record of `STIL.idx`, followed by `count` (path, entry) pairs.
+#[derive(Serialize, Deserialize)]
+struct StilIndexHeader {
+    version: u32,
+    count: u64,
+}
+
+/// Returns true if the index exists and is at least as new as the STIL text it was built from.
+fn index_is_fresh(index: &Path, source: &Path) -> bool {
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    matches!((modified(index), modified(source)), (Some(i), Some(s)) if i >= s)
+}
+
+/// Maps a bincode error to an I/O error.
+fn index_error(e: bincode::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
 /// Metadata for a SID file from STIL.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct StilEntry {
     pub title: Option<String>,
     pub artist: Option<String>,
     pub comment: Option<String>,
+    /// Per-subsong metadata from `(#N)` blocks
+    pub subsongs: HashMap<u16, StilSubEntry>,
+}
+
+/// Metadata for one subsong of a multi-song STIL entry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StilSubEntry {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub comment: Option<String>,
 }
 
 /// Parsed STIL database mapping paths to metadata.
 #[derive(Debug, Default)]
 pub struct StilDatabase {
     entries: HashMap<String, StilEntry>,
+    /// Lowercased artist to paths, built on first use by `artist_index`
+    artist_index: OnceCell<HashMap<String, Vec<String>>>,
+    /// Uppercased comment tag like `GAME` to paths, built on first use by `genre_index`
+    genre_index: OnceCell<HashMap<String, Vec<String>>>,
 }
 
 impl StilDatabase {
     /// Fetches and parses the STIL file from HVSC, using cache if available.
     pub fn fetch(base_url: &str) -> io::Result<Self> {
+        Self::fetch_in(cache_dir().as_deref(), base_url)
+    }
+
+    /// Like `fetch`, but caching in an explicit directory. A `STIL.idx` newer than
+    /// the cached `STIL.txt` is loaded instead of re-parsing; otherwise it is rebuilt.
+    fn fetch_in(dir: Option<&Path>, base_url: &str) -> io::Result<Self> {
+        let index_path = dir.map(|d| d.join("STIL.idx"));
+        if let (Some(dir), Some(index)) = (dir, &index_path)
+            && index_is_fresh(index, &dir.join("STIL.txt"))
+            && let Ok(db) = Self::load_index(index)
+        {
+            return Ok(db);
+        }
+
         let url = format!("{base_url}/DOCUMENTS/STIL.txt");
-        let content = fetch_with_cache(&url, "STIL.txt", true)?;
-        Ok(Self::parse(&content))
+        let content = fetch_with_cache_in(dir, &url, "STIL.txt", true)?;
+        let db = Self::parse(&content);
+        if let Some(index) = index_path {
+            let _ = db.save_index(&index);
+        }
+        Ok(db)
+    }
+
+    /// Loads a database written by `save_index`.
+    pub fn load_index(path: &Path) -> io::Result<Self> {
+        let mut reader = BufReader::new(fs::File::open(path)?);
+        let header: StilIndexHeader =
+            bincode::deserialize_from(&mut reader).map_err(index_error)?;
+        if header.version != STIL_INDEX_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported STIL index version {}", header.version),
+            ));
+        }
+        let mut entries = HashMap::new();
+        for _ in 0..header.count {
+            let (path, entry): (String, StilEntry) =
+                bincode::deserialize_from(&mut reader).map_err(index_error)?;
+            entries.insert(path, entry);
+        }
+        Ok(Self {
+            entries,
+            ..Self::default()
+        })
+    }
+
+    /// Writes the parsed entries as a binary index for fast loading.
+    pub fn save_index(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(fs::File::create(path)?);
+        let header = StilIndexHeader {
+            version: STIL_INDEX_VERSION,
+            count: self.entries.len() as u64,
+        };
+        bincode::serialize_into(&mut writer, &header).map_err(index_error)?;
+        for pair in &self.entries {
+            bincode::serialize_into(&mut writer, &pair).map_err(index_error)?;
+        }
+        writer.flush()
     }
 
     fn parse(content: &str) -> Self {
         let mut entries = HashMap::new();
         let mut current_path: Option<String> = None;
         let mut current_entry = StilEntry::default();
+        let mut current_song: Option<u16> = None;
 
         for line in content.lines() {
             // STIL format: path line starts new entry, field lines are indented
@@ -125,17 +370,39 @@ impl StilDatabase {
                 }
                 current_path = Some(line.to_string());
                 current_entry = StilEntry::default();
+                current_song = None;
+                continue;
+            }
+
+            // "(#N)" starts a subsong block; following fields belong to subsong N
+            if let Some(song) = line
+                .strip_prefix("(#")
+                .and_then(|rest| rest.strip_suffix(')'))
+                .and_then(|n| n.parse().ok())
+            {
+                current_song = Some(song);
                 continue;
             }
 
             // Parse field lines
             let trimmed = line.trim_start();
+            let (title, artist, comment) = match current_song {
+                Some(song) => {
+                    let sub = current_entry.subsongs.entry(song).or_default();
+                    (&mut sub.title, &mut sub.artist, &mut sub.comment)
+                }
+                None => (
+                    &mut current_entry.title,
+                    &mut current_entry.artist,
+                    &mut current_entry.comment,
+                ),
+            };
             if let Some(rest) = trimmed.strip_prefix("TITLE:") {
-                current_entry.title = Some(rest.trim().to_string());
+                *title = Some(rest.trim().to_string());
             } else if let Some(rest) = trimmed.strip_prefix("ARTIST:") {
-                current_entry.artist = Some(rest.trim().to_string());
+                *artist = Some(rest.trim().to_string());
             } else if let Some(rest) = trimmed.strip_prefix("COMMENT:") {
-                current_entry.comment = Some(rest.trim().to_string());
+                *comment = Some(rest.trim().to_string());
             }
         }
 
@@ -144,7 +411,10 @@ impl StilDatabase {
             entries.insert(path, current_entry);
         }
 
-        Self { entries }
+        Self {
+            entries,
+            ..Self::default()
+        }
     }
 
     /// Returns the number of entries in the database.
@@ -163,6 +433,115 @@ impl StilDatabase {
         self.entries.get(path)
     }
 
+    /// Returns the STIL comment for a subsong, falling back to the file-level comment.
+    pub fn comment_for_subsong(&self, path: &str, song: u16) -> Option<&str> {
+        let entry = self.entries.get(path)?;
+        entry
+            .subsongs
+            .get(&song)
+            .and_then(|sub| sub.comment.as_deref())
+            .or(entry.comment.as_deref())
+    }
+
+    /// Returns all entries sorted by path, for reproducible output.
+    pub fn entries_sorted(&self) -> Vec<(&String, &StilEntry)> {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        entries
+    }
+
+    /// Writes all entries as CSV (`path,title,artist,comment`) for analysis tools.
+    pub fn export_to_csv(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(fs::File::create(path)?);
+        self.write_csv(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Writes the CSV rows with every field quoted per RFC 4180; missing fields are empty.
+    fn write_csv(&self, writer: &mut impl Write) -> io::Result<()> {
+        let quote = |field: &str| format!("\"{}\"", field.replace('"', "\"\""));
+        writeln!(writer, "\"path\",\"title\",\"artist\",\"comment\"")?;
+        for (path, entry) in self.entries_sorted() {
+            let fields = [
+                Some(path.as_str()),
+                entry.title.as_deref(),
+                entry.artist.as_deref(),
+                entry.comment.as_deref(),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| quote(f.unwrap_or(""))).collect();
+            writeln!(writer, "{}", row.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// Maps each lowercased artist to the paths of their tunes, built once on first use.
+    pub fn artist_index(&self) -> &HashMap<String, Vec<String>> {
+        self.artist_index.get_or_init(|| {
+            let mut index: HashMap<String, Vec<String>> = HashMap::new();
+            for (path, entry) in &self.entries {
+                if let Some(artist) = &entry.artist {
+                    index
+                        .entry(artist.to_lowercase())
+                        .or_default()
+                        .push(path.clone());
+                }
+            }
+            index
+        })
+    }
+
+    /// Returns the paths of all tunes by `artist` (case-insensitive, whole name).
+    pub fn files_by_artist(&self, artist: &str) -> Vec<&str> {
+        self.artist_index()
+            .get(&artist.trim().to_lowercase())
+            .map(|paths| paths.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the bracketed tags, like `GAME` in `[GAME]`, of the entry's comment.
+    pub fn genres_for_path(&self, path: &str) -> Vec<String> {
+        self.entries
+            .get(path)
+            .and_then(|entry| entry.comment.as_deref())
+            .map(extract_genres)
+            .unwrap_or_default()
+    }
+
+    /// Maps each uppercased comment tag to the paths carrying it, built once on first use.
+    pub fn genre_index(&self) -> &HashMap<String, Vec<String>> {
+        self.genre_index.get_or_init(|| {
+            let mut index: HashMap<String, Vec<String>> = HashMap::new();
+            for (path, entry) in &self.entries {
+                let Some(comment) = &entry.comment else {
+                    continue;
+                };
+                for genre in extract_genres(comment) {
+                    let paths = index.entry(genre.to_uppercase()).or_default();
+                    // A tag may appear more than once in the same comment
+                    if paths.last() != Some(path) {
+                        paths.push(path.clone());
+                    }
+                }
+            }
+            index
+        })
+    }
+
+    /// Returns all comment tags, sorted alphabetically.
+    pub fn genres(&self) -> Vec<&str> {
+        let mut genres: Vec<&str> = self.genre_index().keys().map(String::as_str).collect();
+        genres.sort_unstable();
+        genres
+    }
+
+    /// Returns the paths of all tunes tagged `genre` (case-insensitive).
+    pub fn paths_for_genre(&self, genre: &str) -> Vec<&str> {
+        self.genre_index()
+            .get(&genre.trim().to_uppercase())
+            .map(|paths| paths.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
     /// Searches paths, titles, and artists for entries containing the query (case-insensitive).
     pub fn search(&self, query: &str) -> Vec<&str> {
         let query_lower = query.to_lowercase();
@@ -184,10 +563,55 @@ impl StilDatabase {
     }
 }
 
+/// Extracts bracketed tags like `[GAME]` or `[8-bit]` from a STIL comment.
+fn extract_genres(comment: &str) -> Vec<String> {
+    comment
+        .split('[')
+        .skip(1)
+        .filter_map(|rest| rest.split_once(']'))
+        .map(|(tag, _)| tag)
+        .filter(|tag| !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+        .map(String::from)
+        .collect()
+}
+
+/// A Songlengths.md5 line that `SonglengthsDatabase::parse_verbose` skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// 1-based line number
+    pub line_num: usize,
+    pub content: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {} ({})",
+            self.line_num, self.reason, self.content
+        )
+    }
+}
+
+/// Durations of one tune's subsongs, with the HVSC path from the preceding comment.
+#[derive(Debug)]
+struct SongEntry {
+    durations: Vec<std::time::Duration>,
+    /// From the `; /MUSICIANS/...sid` line above the MD5, if present
+    path: Option<String>,
+}
+
 /// Song lengths database mapping MD5 hashes to per-subsong durations.
 #[derive(Debug, Default)]
 pub struct SonglengthsDatabase {
-    entries: HashMap<String, Vec<std::time::Duration>>,
+    entries: HashMap<String, SongEntry>,
+}
+
+/// Returns the HVSC path of a `; /path/to/tune.sid` comment line.
+fn comment_path(line: &str) -> Option<&str> {
+    let path = line.strip_prefix(';')?.trim();
+    path.starts_with('/').then_some(path)
 }
 
 impl SonglengthsDatabase {
@@ -198,60 +622,271 @@ impl SonglengthsDatabase {
         Ok(Self::parse(&content))
     }
 
+    /// Fetches the database like `fetch`, also returning the lines `parse_verbose` skipped.
+    pub fn fetch_verbose(base_url: &str) -> io::Result<(Self, Vec<ParseWarning>)> {
+        let url = format!("{base_url}/DOCUMENTS/Songlengths.md5");
+        let content = fetch_with_cache(&url, "Songlengths.md5", false)?;
+        Ok(Self::parse_verbose(&content))
+    }
+
     fn parse(content: &str) -> Self {
         let mut entries = HashMap::new();
+        let mut path = None;
         for line in content.lines() {
-            // Skip comments and empty lines
-            if line.starts_with(';') || line.starts_with('[') || line.trim().is_empty() {
+            // Comments name the file of the MD5 line that follows
+            if line.starts_with(';') {
+                path = comment_path(line).map(str::to_string);
+                continue;
+            }
+            // Skip section headers and empty lines
+            if line.starts_with('[') || line.trim().is_empty() {
                 continue;
             }
             // Format: <md5>=<time1> <time2> ...
             if let Some((hash, times)) = line.split_once('=') {
-                let durations: Vec<std::time::Duration> = times
-                    .split_whitespace()
-                    .filter_map(parse_duration)
-                    .collect();
+                let durations = parse_durations(times);
                 if !durations.is_empty() {
-                    entries.insert(hash.to_lowercase(), durations);
+                    let path = path.take();
+                    entries.insert(hash.to_lowercase(), SongEntry { durations, path });
                 }
             }
         }
         Self { entries }
     }
 
+    /// Like `parse`, but also requires 32 hex digit MD5s and reports every
+    /// line it skips.
+    pub fn parse_verbose(content: &str) -> (Self, Vec<ParseWarning>) {
+        let mut entries = HashMap::new();
+        let mut warnings = Vec::new();
+        let mut path = None;
+        for (i, line) in content.lines().enumerate() {
+            if line.starts_with(';') {
+                path = comment_path(line).map(str::to_string);
+                continue;
+            }
+            if line.starts_with('[') || line.trim().is_empty() {
+                continue;
+            }
+            let entry = match line.split_once('=') {
+                None => Err("missing '='"),
+                Some((hash, _))
+                    if hash.len() != 32 || !hash.chars().all(|c| c.is_ascii_hexdigit()) =>
+                {
+                    Err("invalid MD5 format")
+                }
+                Some((hash, times)) => {
+                    let durations = parse_durations(times);
+                    if durations.is_empty() {
+                        Err("no valid durations")
+                    } else {
+                        Ok((hash.to_lowercase(), durations))
+                    }
+                }
+            };
+            match entry {
+                Ok((hash, durations)) => {
+                    let path = path.take();
+                    entries.insert(hash, SongEntry { durations, path });
+                }
+                Err(reason) => warnings.push(ParseWarning {
+                    line_num: i + 1,
+                    content: line.to_string(),
+                    reason: reason.to_string(),
+                }),
+            }
+        }
+        (Self { entries }, warnings)
+    }
+
     /// Looks up song durations by MD5 hash.
     pub fn get(&self, md5: &str) -> Option<&[std::time::Duration]> {
-        self.entries.get(&md5.to_lowercase()).map(|v| v.as_slice())
+        self.entries
+            .get(&md5.to_lowercase())
+            .map(|entry| entry.durations.as_slice())
+    }
+
+    /// Looks up the HVSC path of a tune by MD5 hash.
+    pub fn path_for_md5(&self, md5: &str) -> Option<&str> {
+        self.entries.get(&md5.to_lowercase())?.path.as_deref()
     }
 
     /// Returns the number of entries in the database.
-    #[allow(dead_code)] // May be useful for status display
     pub fn len(&self) -> usize {
         self.entries.len()
     }
+
+    /// Returns the number of subsong durations (tunes with several songs count each one).
+    pub fn entry_count(&self) -> usize {
+        self.entries
+            .values()
+            .map(|entry| entry.durations.len())
+            .sum()
+    }
+
+    /// Returns the summed duration of every subsong in the database.
+    pub fn total_duration(&self) -> std::time::Duration {
+        self.entries
+            .values()
+            .flat_map(|entry| &entry.durations)
+            .sum()
+    }
+
+    /// Returns the number of distinct tunes (MD5 hashes) in the database.
+    pub fn total_unique_tunes(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the MD5 and duration of the longest individual subsong.
+    pub fn longest_tune(&self) -> Option<(&str, std::time::Duration)> {
+        self.subsongs().max_by_key(|&(_, duration)| duration)
+    }
+
+    /// Returns the MD5 and duration of the shortest subsong, ignoring zero lengths.
+    pub fn shortest_tune(&self) -> Option<(&str, std::time::Duration)> {
+        self.subsongs()
+            .filter(|(_, duration)| !duration.is_zero())
+            .min_by_key(|&(_, duration)| duration)
+    }
+
+    /// Every subsong duration paired with its tune's MD5.
+    fn subsongs(&self) -> impl Iterator<Item = (&str, std::time::Duration)> {
+        self.entries.iter().flat_map(|(md5, entry)| {
+            entry
+                .durations
+                .iter()
+                .map(move |&duration| (md5.as_str(), duration))
+        })
+    }
+
+    /// Formats the longest and shortest subsong as "Longest tune: <md5> (4:32)".
+    pub fn extremes_lines(&self) -> Vec<String> {
+        let line = |label: &str, (md5, duration): (&str, std::time::Duration)| {
+            let secs = duration.as_secs();
+            format!("{label} tune: {md5} ({}:{:02})", secs / 60, secs % 60)
+        };
+        self.longest_tune()
+            .map(|tune| line("Longest", tune))
+            .into_iter()
+            .chain(self.shortest_tune().map(|tune| line("Shortest", tune)))
+            .collect()
+    }
+
+    /// Formats as "Total tunes: 52,347 | Total duration: 42d 7h 18m | Average: 2:12".
+    pub fn stats_line(&self) -> String {
+        let total = self.total_duration();
+        let average = u32::try_from(self.entry_count())
+            .ok()
+            .filter(|&n| n > 0)
+            .map_or(std::time::Duration::ZERO, |n| total / n);
+        format!(
+            "Total tunes: {} | Total duration: {} | Average: {}:{:02}",
+            format_thousands(self.total_unique_tunes()),
+            format_long_duration(total),
+            average.as_secs() / 60,
+            average.as_secs() % 60
+        )
+    }
 }
 
-/// Parses duration string "mm:ss" or "mm:ss.mmm" into Duration.
-fn parse_duration(s: &str) -> Option<std::time::Duration> {
+/// Formats a long duration as "42d 7h 18m".
+fn format_long_duration(duration: std::time::Duration) -> String {
+    let mins = duration.as_secs() / 60;
+    format!("{}d {}h {}m", mins / 1440, mins / 60 % 24, mins % 60)
+}
+
+/// Parses the space separated durations after the `=` of a Songlengths line.
+fn parse_durations(times: &str) -> Vec<std::time::Duration> {
+    times
+        .split_whitespace()
+        .filter_map(parse_duration_strict)
+        .collect()
+}
+
+/// Parses a Songlengths time as `m:ss` or `m:ss.mmm`, rejecting malformed fields.
+///
+/// Seconds must have exactly two digits and milliseconds exactly three. Minutes
+/// take one or two digits, since HVSC writes them unpadded (`3:25`).
+fn parse_duration_strict(s: &str) -> Option<std::time::Duration> {
+    let all_digits = |field: &str, lengths: &[usize]| {
+        lengths.contains(&field.len()) && field.bytes().all(|b| b.is_ascii_digit())
+    };
+
     // Remove any trailing attributes like "(G)" or "(M)"
     let s = s.split('(').next()?.trim();
     let (mins, rest) = s.split_once(':')?;
-    let mins: u64 = mins.parse().ok()?;
-
-    // Handle "ss" or "ss.mmm"
-    let (secs, millis) = if let Some((s, ms)) = rest.split_once('.') {
-        let secs: u64 = s.parse().ok()?;
-        let millis: u64 = ms.parse().ok()?;
-        (secs, millis)
-    } else {
-        (rest.parse().ok()?, 0)
-    };
+    let (secs, millis) = rest.split_once('.').unwrap_or((rest, "000"));
+    if !all_digits(mins, &[1, 2]) || !all_digits(secs, &[2]) || !all_digits(millis, &[3]) {
+        return None;
+    }
 
+    let secs: u64 = secs.parse().ok()?;
+    if secs >= 60 {
+        return None;
+    }
+    let mins: u64 = mins.parse().ok()?;
+    let millis: u64 = millis.parse().ok()?;
     Some(std::time::Duration::from_millis(
         mins * 60_000 + secs * 1000 + millis,
     ))
 }
 
+/// Counts the files and directories in a listing, returned as `(files, dirs)`.
+fn count_entries(entries: &[HvscEntry]) -> (usize, usize) {
+    let dirs = entries.iter().filter(|e| e.is_dir).count();
+    (entries.len() - dirs, dirs)
+}
+
+/// Header line of an exported directory listing, followed by the listed path.
+const LISTING_HEADER: &str = "# HVSC listing for ";
+
+/// Formats a listing as one name per line (directories end in `/`) under a header.
+fn format_listing(dir: &str, entries: &[HvscEntry]) -> String {
+    let mut text = format!(
+        "{LISTING_HEADER}{dir} generated by crabsid {}\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    for entry in entries {
+        text.push_str(&entry.name);
+        if entry.is_dir {
+            text.push('/');
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Parses a listing written by `format_listing`. The directory comes from the
+/// header when present, else `default_dir`; other `#` lines are ignored.
+fn parse_listing(text: &str, default_dir: &str) -> (String, Vec<HvscEntry>) {
+    let dir = text
+        .lines()
+        .find_map(|line| line.strip_prefix(LISTING_HEADER))
+        .and_then(|rest| rest.split(" generated by ").next())
+        .unwrap_or(default_dir)
+        .to_string();
+    let entries = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let name = line.trim_end_matches('/');
+            let is_dir = name.len() != line.len();
+            let path = if is_dir {
+                format!("{dir}{name}/")
+            } else {
+                format!("{dir}{name}")
+            };
+            HvscEntry {
+                name: name.to_string(),
+                path,
+                is_dir,
+            }
+        })
+        .collect();
+    (dir, entries)
+}
+
 /// An entry in the HVSC browser (directory or file).
 #[derive(Debug, Clone)]
 pub struct HvscEntry {
@@ -264,31 +899,55 @@ pub struct HvscEntry {
 }
 
 impl HvscEntry {
+    /// Creates a file entry named `name` at the full HVSC `path`.
+    pub fn new_file(name: &str, path: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.to_string(),
+            is_dir: false,
+        }
+    }
+
     /// Returns the full URL for this entry.
     pub fn url(&self, base_url: &str) -> String {
         format!("{base_url}{}", self.path)
     }
 
-    /// Loads this entry as a SID file (only valid for files).
-    pub fn load(&self, base_url: &str) -> io::Result<SidFile> {
+    /// Loads this entry as a SID file (only valid for files), failing with
+    /// `TimedOut` if the download takes longer than `timeout`.
+    pub fn load(&self, base_url: &str, timeout: Duration) -> io::Result<SidFile> {
         if self.is_dir {
             return Err(io::Error::other("Cannot load directory as SID file"));
         }
-        let bytes = fetch_bytes(&self.url(base_url))?;
-        SidFile::parse(&bytes)
+        let bytes = fetch_bytes_with_timeout(&self.url(base_url), timeout)?;
+        SidFile::from_bytes(&bytes)
     }
 }
 
+/// Directory listing fetched in the background, with the index of the mirror that served it.
+type PendingListing = JoinHandle<io::Result<(usize, Vec<HvscEntry>)>>;
+
+/// STIL and Songlengths databases fetched in the background.
+type PendingDatabases = JoinHandle<(io::Result<StilDatabase>, Option<SonglengthsDatabase>)>;
+
 /// HVSC directory browser state.
 pub struct HvscBrowser {
     /// Base URL for HVSC mirror
     pub base_url: String,
+    /// Mirror URLs tried in order on failure
+    pub mirrors: Vec<String>,
+    /// Index into `mirrors` of the mirror currently in use
+    pub active_mirror: usize,
     /// Current directory path
     pub current_path: String,
     /// Entries in current directory
     pub entries: Vec<HvscEntry>,
     /// Selected index
     pub selected: usize,
+    /// Number of files in `entries`
+    pub entry_file_count: usize,
+    /// Number of directories in `entries`
+    pub entry_dir_count: usize,
     /// STIL database for metadata
     pub stil: Option<StilDatabase>,
     /// STIL loading error (persists across navigation)
@@ -297,56 +956,174 @@ pub struct HvscBrowser {
     pub songlengths: Option<SonglengthsDatabase>,
     /// Loading state
     pub loading: bool,
+    /// Directory navigation in flight: target path and fetch thread
+    pending_nav: Option<(String, PendingListing)>,
+    /// STIL/Songlengths fetch in flight
+    pending_databases: Option<PendingDatabases>,
     /// Error message if any
     pub error: Option<String>,
+    /// Time allowed for fetching a SID file before giving up
+    pub fetch_timeout: Duration,
+    /// Order of the files in `entries`
+    pub sort_mode: SortMode,
+}
+
+/// Order of the files in an HVSC listing; directories always come first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortMode {
+    /// Alphabetically by file name
+    #[default]
+    ByName,
+    /// By STIL title, falling back to the file name
+    ByStilTitle,
 }
 
 impl HvscBrowser {
     /// Creates a new browser at the root level.
     pub fn new(base_url: &str) -> Self {
-        let entries = vec![
-            HvscEntry {
-                name: "MUSICIANS".to_string(),
-                path: "/MUSICIANS/".to_string(),
-                is_dir: true,
-            },
-            HvscEntry {
-                name: "GAMES".to_string(),
-                path: "/GAMES/".to_string(),
-                is_dir: true,
-            },
-            HvscEntry {
-                name: "DEMOS".to_string(),
-                path: "/DEMOS/".to_string(),
-                is_dir: true,
-            },
-        ];
+        let entries = root_entries();
+        let (entry_file_count, entry_dir_count) = count_entries(&entries);
 
         Self {
             base_url: base_url.to_string(),
+            mirrors: vec![base_url.to_string()],
+            active_mirror: 0,
             current_path: "/".to_string(),
             entries,
             selected: 0,
+            entry_file_count,
+            entry_dir_count,
             stil: None,
             stil_error: None,
             songlengths: None,
             loading: false,
+            pending_nav: None,
+            pending_databases: None,
             error: None,
+            fetch_timeout: DEFAULT_FETCH_TIMEOUT,
+            sort_mode: SortMode::ByName,
         }
     }
 
-    /// Fetches the STIL and Songlengths databases (from cache if available).
+    /// Replaces the mirror list, keeping `base_url` as the active mirror.
+    pub fn set_mirrors(&mut self, mirrors: Vec<String>) {
+        let mut list = vec![self.base_url.clone()];
+        for url in mirrors {
+            if !list.contains(&url) {
+                list.push(url);
+            }
+        }
+        self.mirrors = list;
+        self.active_mirror = 0;
+    }
+
+    /// Runs `op` against each mirror, starting with the active one, until one succeeds.
+    /// On success the successful mirror becomes the active one.
+    pub fn try_mirrors<F, T>(&mut self, mut op: F) -> io::Result<T>
+    where
+        F: FnMut(&str) -> io::Result<T>,
+    {
+        if self.mirrors.is_empty() {
+            return op(&self.base_url);
+        }
+
+        let len = self.mirrors.len();
+        let mut last_err = None;
+        for offset in 0..len {
+            let idx = (self.active_mirror + offset) % len;
+            match op(&self.mirrors[idx]) {
+                Ok(value) => {
+                    self.active_mirror = idx;
+                    self.base_url = self.mirrors[idx].clone();
+                    return Ok(value);
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| io::Error::other("No HVSC mirror available")))
+    }
+
+    /// Moves a mirror one step in the list, keeping `active_mirror` pointing at the same URL.
+    pub fn move_mirror(&mut self, from: usize, to: usize) {
+        let len = self.mirrors.len();
+        if from >= len || to >= len || from == to {
+            return;
+        }
+        self.mirrors.swap(from, to);
+        if self.active_mirror == from {
+            self.active_mirror = to;
+        } else if self.active_mirror == to {
+            self.active_mirror = from;
+        }
+    }
+
+    /// Removes a mirror from the list (the last remaining mirror is kept).
+    pub fn remove_mirror(&mut self, idx: usize) {
+        if self.mirrors.len() <= 1 || idx >= self.mirrors.len() {
+            return;
+        }
+        self.mirrors.remove(idx);
+        if self.active_mirror > idx || self.active_mirror >= self.mirrors.len() {
+            self.active_mirror = self.active_mirror.saturating_sub(1);
+        }
+        self.base_url = self.mirrors[self.active_mirror].clone();
+    }
+
+    /// Starts fetching the STIL and Songlengths databases (from cache if available)
+    /// in the background. Call `poll_pending_databases` to pick them up.
     pub fn load_stil(&mut self) {
-        match StilDatabase::fetch(&self.base_url) {
-            Ok(db) => self.stil = Some(db),
-            Err(e) => self.stil_error = Some(e.to_string()),
+        let base_url = self.base_url.clone();
+        self.pending_databases = Some(thread::spawn(move || {
+            // Songlengths errors are silently ignored - we just fall back to playtime
+            let songlengths = SonglengthsDatabase::fetch(&base_url).ok();
+            (StilDatabase::fetch(&base_url), songlengths)
+        }));
+    }
+
+    /// Stores the databases once their background fetch has finished.
+    pub fn poll_pending_databases(&mut self) {
+        if !self
+            .pending_databases
+            .as_ref()
+            .is_some_and(JoinHandle::is_finished)
+        {
+            return;
         }
-        // Songlengths errors are silently ignored - we just fall back to playtime
-        if let Ok(db) = SonglengthsDatabase::fetch(&self.base_url) {
-            self.songlengths = Some(db);
+        let Some(handle) = self.pending_databases.take() else {
+            return;
+        };
+
+        match handle.join() {
+            Ok((stil, songlengths)) => {
+                match stil {
+                    Ok(db) => self.stil = Some(db),
+                    Err(e) => self.stil_error = Some(e.to_string()),
+                }
+                self.songlengths = songlengths;
+            }
+            Err(_) => self.stil_error = Some("Database fetch panicked".to_string()),
         }
     }
 
+    /// Returns Songlengths totals and the longest/shortest tunes, one per line,
+    /// or None if the database isn't loaded.
+    pub fn songlength_stats(&self) -> Option<Vec<String>> {
+        self.songlengths.as_ref().map(|db| {
+            let mut lines: Vec<String> = db.stats_line().split(" | ").map(String::from).collect();
+            lines.extend(db.extremes_lines());
+            lines
+        })
+    }
+
+    /// Returns a one-line summary of the loaded STIL and Songlengths databases.
+    pub fn status_line(&self) -> String {
+        format_status_line(
+            self.stil.as_ref().map(|s| s.len()),
+            self.songlengths.as_ref().map(|s| s.len()),
+            self.pending_databases.is_some(),
+        )
+    }
+
     /// Clears the HVSC cache and reloads STIL and Songlengths databases.
     pub fn refresh_cache(&mut self) {
         clear_cache();
@@ -366,6 +1143,30 @@ impl HvscBrowser {
         self.stil.as_ref()?.get(&entry.path)
     }
 
+    /// Matches the current directory's entries by path, for searching without STIL.
+    ///
+    /// `*` and `?` act as glob wildcards; a plain query matches any path containing
+    /// it. Matching ignores case.
+    pub fn search_by_filename(&self, query: &str) -> Vec<HvscEntry> {
+        let pattern = if query.contains(['*', '?']) {
+            Pattern::new(query)
+        } else {
+            Pattern::new(&format!("*{}*", Pattern::escape(query)))
+        };
+        let Ok(pattern) = pattern else {
+            return Vec::new();
+        };
+        let options = MatchOptions {
+            case_sensitive: false,
+            ..MatchOptions::new()
+        };
+        self.entries
+            .iter()
+            .filter(|entry| pattern.matches_with(&entry.path, options))
+            .cloned()
+            .collect()
+    }
+
     /// Returns song duration for given MD5 and subsong (1-indexed), if available.
     pub fn song_duration(&self, md5: &str, subsong: u16) -> Option<std::time::Duration> {
         let durations = self.songlengths.as_ref()?.get(md5)?;
@@ -403,27 +1204,78 @@ impl HvscBrowser {
         }
     }
 
-    /// Navigate to a specific path.
+    /// Starts fetching `path` in the background, replacing any navigation in flight.
+    /// Call `poll_pending_nav` to pick up the result.
     pub fn navigate_to(&mut self, path: &str) {
+        self.cancel_pending();
+        if path == "/" {
+            self.reset_to_root();
+            return;
+        }
+
+        self.loading = true;
+        self.error = None;
+
+        let mirrors = if self.mirrors.is_empty() {
+            vec![self.base_url.clone()]
+        } else {
+            self.mirrors.clone()
+        };
+        let start = self.active_mirror;
+        let target = path.to_string();
+        let handle = thread::spawn(move || fetch_from_mirrors(&mirrors, start, &target));
+        self.pending_nav = Some((path.to_string(), handle));
+    }
+
+    /// Abandons the navigation in flight; its result is discarded when the fetch finishes.
+    pub fn cancel_pending(&mut self) {
+        drop(self.pending_nav.take());
+        self.loading = false;
+    }
+
+    /// Applies the pending navigation once its fetch has finished.
+    pub fn poll_pending_nav(&mut self) {
+        if !self
+            .pending_nav
+            .as_ref()
+            .is_some_and(|(_, handle)| handle.is_finished())
+        {
+            return;
+        }
+        let Some((path, handle)) = self.pending_nav.take() else {
+            return;
+        };
+
+        match handle.join() {
+            Ok(Ok((mirror, entries))) => {
+                if let Some(url) = self.mirrors.get(mirror) {
+                    self.active_mirror = mirror;
+                    self.base_url = url.clone();
+                }
+                self.current_path = path;
+                self.set_entries(entries);
+            }
+            Ok(Err(e)) => self.error = Some(e.to_string()),
+            Err(_) => self.error = Some("Directory fetch panicked".to_string()),
+        }
+        self.loading = false;
+    }
+
+    /// Navigate to a specific path, blocking until the listing is fetched.
+    pub fn navigate_to_immediate(&mut self, path: &str) {
+        self.cancel_pending();
         if path == "/" {
-            // Preserve STIL and base_url across navigation
-            let stil = self.stil.take();
-            let stil_error = self.stil_error.take();
-            let base_url = self.base_url.clone();
-            *self = Self::new(&base_url);
-            self.stil = stil;
-            self.stil_error = stil_error;
+            self.reset_to_root();
             return;
         }
 
         self.loading = true;
         self.error = None;
 
-        match fetch_directory(&self.base_url, path) {
+        match self.try_mirrors(|base_url| fetch_directory(base_url, path)) {
             Ok(entries) => {
                 self.current_path = path.to_string();
-                self.entries = entries;
-                self.selected = 0;
+                self.set_entries(entries);
             }
             Err(e) => {
                 self.error = Some(e.to_string());
@@ -433,6 +1285,96 @@ impl HvscBrowser {
         self.loading = false;
     }
 
+    /// Lists the directory holding the file at `path`, blocking until fetched,
+    /// and selects the file. Returns false if the file is not in the listing.
+    pub fn reveal_file(&mut self, path: &str) -> bool {
+        let Some((dir, _)) = path.rsplit_once('/') else {
+            return false;
+        };
+        self.navigate_to_immediate(&format!("{dir}/"));
+        let Some(index) = self.entries.iter().position(|e| e.path == path) else {
+            return false;
+        };
+        self.selected = index;
+        true
+    }
+
+    /// Writes the current directory's entries to `path`, one per line with
+    /// directories ending in `/`, for use as a static manifest.
+    pub fn export_current_directory(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, format_listing(&self.current_path, &self.entries))
+    }
+
+    /// Replaces the listing with one saved by `export_current_directory`,
+    /// without any network access.
+    pub fn import_directory_from_file(&mut self, path: &Path) -> io::Result<()> {
+        let (dir, entries) = parse_listing(&fs::read_to_string(path)?, &self.current_path);
+        self.cancel_pending();
+        self.error = None;
+        self.current_path = dir;
+        self.set_entries(entries);
+        Ok(())
+    }
+
+    /// Replaces the listing, selecting the first entry and updating the counts.
+    fn set_entries(&mut self, entries: Vec<HvscEntry>) {
+        (self.entry_file_count, self.entry_dir_count) = count_entries(&entries);
+        self.entries = entries;
+        self.selected = 0;
+        if self.sort_mode == SortMode::ByStilTitle {
+            self.sort_entries_by_stil_title();
+        }
+    }
+
+    /// Sorts the files by STIL title, or by name where STIL has none.
+    /// Does nothing without STIL.
+    pub fn sort_entries_by_stil_title(&mut self) {
+        let Some(stil) = &self.stil else {
+            return;
+        };
+        self.entries.sort_by_cached_key(|entry| {
+            let title = stil
+                .get(&entry.path)
+                .and_then(|info| info.title.as_deref())
+                .unwrap_or(&entry.name);
+            (!entry.is_dir, title.to_lowercase())
+        });
+    }
+
+    /// Switches between name and STIL title order, keeping the selected entry selected.
+    /// Returns false, leaving the order as is, if STIL isn't loaded.
+    pub fn cycle_sort_mode(&mut self) -> bool {
+        if self.stil.is_none() {
+            return false;
+        }
+        let selected_path = self.selected_entry().map(|entry| entry.path.clone());
+        self.sort_mode = match self.sort_mode {
+            SortMode::ByName => {
+                self.sort_entries_by_stil_title();
+                SortMode::ByStilTitle
+            }
+            SortMode::ByStilTitle => {
+                sort_by_name(&mut self.entries);
+                SortMode::ByName
+            }
+        };
+        self.selected = selected_path
+            .and_then(|path| self.entries.iter().position(|entry| entry.path == path))
+            .unwrap_or(0);
+        true
+    }
+
+    /// Returns to the top-level listing. Only the listing is reset; databases,
+    /// mirrors and settings are kept.
+    fn reset_to_root(&mut self) {
+        self.current_path = "/".to_string();
+        self.entries = root_entries();
+        (self.entry_file_count, self.entry_dir_count) = count_entries(&self.entries);
+        self.selected = 0;
+        self.loading = false;
+        self.error = None;
+    }
+
     pub fn select_next(&mut self) {
         if !self.entries.is_empty() {
             self.selected = (self.selected + 1).min(self.entries.len() - 1);
@@ -444,12 +1386,68 @@ impl HvscBrowser {
     }
 
     /// Returns the currently selected entry.
-    #[allow(dead_code)]
     pub fn selected_entry(&self) -> Option<&HvscEntry> {
         self.entries.get(self.selected)
     }
 }
 
+/// The fixed top-level HVSC directories.
+fn root_entries() -> Vec<HvscEntry> {
+    ["MUSICIANS", "GAMES", "DEMOS"]
+        .into_iter()
+        .map(|name| HvscEntry {
+            name: name.to_string(),
+            path: format!("/{name}/"),
+            is_dir: true,
+        })
+        .collect()
+}
+
+/// Formats database sizes as "STIL: 58,621 entries | Songlengths: 52,347 tunes".
+fn format_status_line(stil: Option<usize>, songlengths: Option<usize>, loading: bool) -> String {
+    let describe = |count: Option<usize>, unit: &str| match count {
+        Some(n) => format!("{} {unit}", format_thousands(n)),
+        None if loading => "(loading…)".to_string(),
+        None => "(unavailable)".to_string(),
+    };
+    format!(
+        "STIL: {} | Songlengths: {}",
+        describe(stil, "entries"),
+        describe(songlengths, "tunes")
+    )
+}
+
+/// Formats a number with comma thousands separators (58621 -> "58,621").
+fn format_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Fetches a directory listing from each mirror in turn, starting at `start`.
+/// Returns the index of the mirror that succeeded along with the entries.
+fn fetch_from_mirrors(
+    mirrors: &[String],
+    start: usize,
+    path: &str,
+) -> io::Result<(usize, Vec<HvscEntry>)> {
+    let mut last_err = None;
+    for offset in 0..mirrors.len() {
+        let idx = (start + offset) % mirrors.len();
+        match fetch_directory(&mirrors[idx], path) {
+            Ok(entries) => return Ok((idx, entries)),
+            Err(e) => last_err = Some(e),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| io::Error::other("No HVSC mirror available")))
+}
+
 /// Fetches and parses a directory listing from HVSC.
 fn fetch_directory(base_url: &str, path: &str) -> io::Result<Vec<HvscEntry>> {
     if let Some(base_path) = base_url.strip_prefix("file://") {
@@ -487,12 +1485,7 @@ fn read_local_directory(base_path: &str, path: &str) -> io::Result<Vec<HvscEntry
         })
         .collect();
 
-    // Sort: directories first, then alphabetically
-    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
-        (true, false) => std::cmp::Ordering::Less,
-        (false, true) => std::cmp::Ordering::Greater,
-        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-    });
+    sort_by_name(&mut entries);
 
     Ok(entries)
 }
@@ -500,7 +1493,46 @@ fn read_local_directory(base_path: &str, path: &str) -> io::Result<Vec<HvscEntry
 /// Fetches and parses an HTTP directory listing.
 fn fetch_http_directory(base_url: &str, path: &str) -> io::Result<Vec<HvscEntry>> {
     let html = fetch_text(&format!("{base_url}{path}"))?;
-    Ok(parse_directory_listing(&html, path))
+    Ok(parse_directory_listing(
+        &html,
+        path,
+        detect_server_style(&html),
+    ))
+}
+
+/// Web server that generated a directory listing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ServerStyle {
+    /// `mod_autoindex`: one entry per line, often in table cells
+    Apache,
+    /// `autoindex`: bare `<a href>` lines inside a `<pre>` block
+    Nginx,
+    Unknown,
+}
+
+/// Guesses the server from the listing body. Nginx's autoindex rarely names
+/// itself, so an "Index of" page with a `<pre>` block and no table counts too.
+fn detect_server_style(html: &str) -> ServerStyle {
+    let lower = html.to_ascii_lowercase();
+    if lower.contains("nginx") {
+        ServerStyle::Nginx
+    } else if lower.contains("apache") || lower.contains("<td") {
+        ServerStyle::Apache
+    } else if lower.contains("index of") && lower.contains("<pre>") {
+        ServerStyle::Nginx
+    } else {
+        ServerStyle::Unknown
+    }
+}
+
+/// Sorts directories first, then alphabetically by name.
+fn sort_by_name(entries: &mut [HvscEntry]) {
+    entries.sort_by_cached_key(|entry| (!entry.is_dir, entry.name.to_lowercase()));
+}
+
+/// True for sort links, parent and absolute links that aren't directory entries.
+fn is_nav_href(href: &str) -> bool {
+    href.starts_with('?') || href.starts_with('/') || href.starts_with("http") || href == "../"
 }
 
 /// Extracts href value from an HTML line, filtering navigation/special links.
@@ -515,18 +1547,34 @@ fn extract_href(line: &str) -> Option<&str> {
     let href = &rest[..end];
 
     // Apache listings include sort links and parent refs we don't want
-    let dominated_by_nav =
-        href.starts_with('?') || href.starts_with('/') || href.starts_with("http") || href == "../";
+    if is_nav_href(href) { None } else { Some(href) }
+}
 
-    if dominated_by_nav { None } else { Some(href) }
+/// Extracts every non-navigation href in `html`, regardless of line breaks.
+fn anchor_hrefs(html: &str) -> Vec<&str> {
+    html.split("href=\"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .filter(|href| !is_nav_href(href))
+        .collect()
 }
 
-/// Parses an Apache-style directory listing HTML.
-fn parse_directory_listing(html: &str, base_path: &str) -> Vec<HvscEntry> {
-    let mut entries: Vec<HvscEntry> = html
-        .lines()
-        .filter_map(|line| {
-            let href = extract_href(line)?;
+/// Parses a directory listing HTML. Apache listings are read line by line,
+/// Nginx ones from the anchors in their `<pre>` block and others from all anchors.
+fn parse_directory_listing(html: &str, base_path: &str, style: ServerStyle) -> Vec<HvscEntry> {
+    let hrefs = match style {
+        ServerStyle::Apache => html.lines().filter_map(extract_href).collect(),
+        ServerStyle::Nginx => anchor_hrefs(
+            html.split_once("<pre>")
+                .map_or(html, |(_, listing)| listing),
+        ),
+        ServerStyle::Unknown => anchor_hrefs(html),
+    };
+    let mut entries: Vec<HvscEntry> = hrefs
+        .into_iter()
+        .filter_map(|href| {
+            // Some servers on Windows hosts list paths with backslashes
+            let href = href.replace('\\', "/");
             let is_dir = href.ends_with('/');
             let name = href.trim_end_matches('/').to_string();
 
@@ -543,12 +1591,7 @@ fn parse_directory_listing(html: &str, base_path: &str) -> Vec<HvscEntry> {
         })
         .collect();
 
-    // Directories first for easier navigation
-    entries.sort_by(|a, b| match (a.is_dir, b.is_dir) {
-        (true, false) => std::cmp::Ordering::Less,
-        (false, true) => std::cmp::Ordering::Greater,
-        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-    });
+    sort_by_name(&mut entries);
 
     entries
 }
@@ -580,11 +1623,127 @@ mod tests {
         };
     }
 
+    #[test]
+    fn files_by_artist_ignores_case() {
+        let db = StilDatabase::parse(
+            "/MUSICIANS/H/Hubbard_Rob/Commando.sid\n\
+             \x20ARTIST: Rob Hubbard\n\
+             \n\
+             /MUSICIANS/H/Hubbard_Rob/Monty.sid\n\
+             \x20ARTIST: rob hubbard\n\
+             \n\
+             /MUSICIANS/G/Galway_Martin/Wizball.sid\n\
+             \x20ARTIST: Martin Galway\n",
+        );
+        let mut paths = db.files_by_artist("Rob Hubbard");
+        paths.sort_unstable();
+        assert_eq!(
+            paths,
+            [
+                "/MUSICIANS/H/Hubbard_Rob/Commando.sid",
+                "/MUSICIANS/H/Hubbard_Rob/Monty.sid"
+            ]
+        );
+        assert!(db.files_by_artist("Ben Daglish").is_empty());
+    }
+
+    #[test]
+    fn genres_come_from_bracketed_comment_tags() {
+        assert_eq!(extract_genres("A classic [GAME] tune"), ["GAME"]);
+        assert_eq!(
+            extract_genres("[DEMO] with [8-bit] [not a tag] and [a[SID]]"),
+            ["DEMO", "8-bit", "SID"]
+        );
+
+        let db = StilDatabase::parse(
+            "/GAMES/A/Arkanoid.sid\n\
+             \x20COMMENT: A classic [GAME] tune, [game] again\n\
+             \n\
+             /DEMOS/A/Amplitude.sid\n\
+             \x20COMMENT: [DEMO] music\n",
+        );
+        assert_eq!(
+            db.genres_for_path("/GAMES/A/Arkanoid.sid"),
+            ["GAME", "game"]
+        );
+        assert_eq!(db.paths_for_genre("game"), ["/GAMES/A/Arkanoid.sid"]);
+        assert_eq!(db.genres(), ["DEMO", "GAME"]);
+        assert!(db.paths_for_genre("sid").is_empty());
+    }
+
     stil_tests! {
         stil_with_artist: "/MUSICIANS/H/Hubbard_Rob/Commando.sid" => (Some("Commando"), Some("Rob Hubbard")),
         stil_title_only: "/MUSICIANS/H/Hubbard_Rob/Delta.sid" => (Some("Delta"), None),
     }
 
+    macro_rules! subsong_comment_tests {
+        ($($name:ident: $song:expr => $expected:expr,)*) => {
+            const MULTI_SONG_STIL: &str = r#"
+/MUSICIANS/G/Galway_Martin/Game_Over.sid
+COMMENT: Music from the game.
+(#1)
+  TITLE: Title tune
+COMMENT: Loader music.
+(#2)
+ ARTIST: Martin Galway
+COMMENT: In-game music.
+"#;
+
+            $(
+                #[test]
+                fn $name() {
+                    let db = StilDatabase::parse(MULTI_SONG_STIL);
+                    let path = "/MUSICIANS/G/Galway_Martin/Game_Over.sid";
+                    assert_eq!(db.comment_for_subsong(path, $song), $expected);
+                }
+            )*
+        };
+    }
+
+    subsong_comment_tests! {
+        subsong_comment_first: 1 => Some("Loader music."),
+        subsong_comment_second: 2 => Some("In-game music."),
+        subsong_comment_falls_back_to_file: 3 => Some("Music from the game."),
+    }
+
+    #[test]
+    fn stil_index_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("crabsid-stil-idx-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("STIL.idx");
+
+        let mut db = StilDatabase::parse(STIL_CONTENT);
+        db.entries
+            .extend(StilDatabase::parse(MULTI_SONG_STIL).entries);
+        db.save_index(&path).unwrap();
+        let loaded = StilDatabase::load_index(&path).unwrap();
+        assert_eq!(loaded.entries, db.entries);
+
+        fs::write(&path, b"garbage").unwrap();
+        assert!(StilDatabase::load_index(&path).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn stil_csv_export_escapes_fields() {
+        let db = StilDatabase::parse(
+            "/B.sid\n  TITLE: Beta\n/A.sid\n  TITLE: Alpha\n ARTIST: Someone\n\
+             COMMENT: Fast, loud, and \"noisy\"\n/C.sid\n",
+        );
+        let dir = std::env::temp_dir().join(format!("crabsid-stil-csv-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("stil.csv");
+        db.export_to_csv(&path).unwrap();
+        let csv = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+
+        let expected = "\"path\",\"title\",\"artist\",\"comment\"\n\
+            \"/A.sid\",\"Alpha\",\"Someone\",\"Fast, loud, and \"\"noisy\"\"\"\n\
+            \"/B.sid\",\"Beta\",\"\",\"\"\n\
+            \"/C.sid\",\"\",\"\",\"\"\n";
+        assert_eq!(csv, expected);
+    }
+
     macro_rules! href_tests {
         ($($name:ident: $line:expr => $expected:expr,)*) => {
             $(
@@ -610,9 +1769,459 @@ mod tests {
 <a href="tune.sid">tune.sid</a>
 <a href="readme.txt">readme.txt</a>
 "#;
-        let entries = parse_directory_listing(html, "/TEST/");
+        let entries = parse_directory_listing(html, "/TEST/", ServerStyle::Apache);
         assert_eq!(entries.len(), 2);
         assert_eq!(entries[0].name, "0-9");
         assert_eq!(entries[1].name, "tune.sid");
     }
+
+    #[test]
+    fn nginx_listing_parses_like_apache() {
+        let apache = r#"<html><head><title>Index of /HVSC/</title></head><body>
+<table>
+<tr><th><a href="?C=N;O=D">Name</a></th></tr>
+<tr><td><a href="/HVSC/">Parent Directory</a></td></tr>
+<tr><td><a href="DEMOS/">DEMOS/</a></td><td>2020-01-01 00:00</td><td>-</td></tr>
+<tr><td><a href="Tune.sid">Tune.sid</a></td><td>2020-01-01 00:00</td><td>1.2K</td></tr>
+<tr><td><a href="readme.txt">readme.txt</a></td><td>2020-01-01 00:00</td><td>10</td></tr>
+</table><address>Apache/2.4 Server</address></body></html>"#;
+        let nginx = r#"<html>
+<head><title>Index of /HVSC/</title></head>
+<body>
+<h1>Index of /HVSC/</h1><hr><pre><a href="../">../</a>
+<a href="DEMOS/">DEMOS/</a>                                             01-Jan-2020 00:00       -
+<a href="Tune.sid">Tune.sid</a>                                          01-Jan-2020 00:00    1234
+<a href="readme.txt">readme.txt</a>                                      01-Jan-2020 00:00      10
+</pre><hr></body>
+</html>"#;
+        assert_eq!(detect_server_style(apache), ServerStyle::Apache);
+        assert_eq!(detect_server_style(nginx), ServerStyle::Nginx);
+        assert_eq!(
+            detect_server_style("<a href=\"a.sid\">"),
+            ServerStyle::Unknown
+        );
+
+        let parse = |html: &str| -> Vec<(String, String, bool)> {
+            parse_directory_listing(html, "/HVSC/", detect_server_style(html))
+                .into_iter()
+                .map(|e| (e.name, e.path, e.is_dir))
+                .collect()
+        };
+        let expected = vec![
+            ("DEMOS".to_string(), "/HVSC/DEMOS/".to_string(), true),
+            ("Tune.sid".to_string(), "/HVSC/Tune.sid".to_string(), false),
+        ];
+        assert_eq!(parse(apache), expected);
+        assert_eq!(parse(nginx), expected);
+        let generic = "<a href=\"DEMOS/\">x</a><a href=\"Tune.sid\">y</a>";
+        assert_eq!(parse(generic), expected);
+    }
+
+    macro_rules! status_line_tests {
+        ($($name:ident: ($stil:expr, $songlengths:expr, $loading:expr) => $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(format_status_line($stil, $songlengths, $loading), $expected);
+                }
+            )*
+        };
+    }
+
+    status_line_tests! {
+        status_both_loaded: (Some(58_621), Some(52_347), false) => "STIL: 58,621 entries | Songlengths: 52,347 tunes",
+        status_stil_only: (Some(12), None, false) => "STIL: 12 entries | Songlengths: (unavailable)",
+        status_songlengths_only: (None, Some(1_000), false) => "STIL: (unavailable) | Songlengths: 1,000 tunes",
+        status_neither: (None, None, false) => "STIL: (unavailable) | Songlengths: (unavailable)",
+        status_loading: (None, None, true) => "STIL: (loading…) | Songlengths: (loading…)",
+    }
+
+    macro_rules! strict_duration_tests {
+        ($($name:ident: $input:expr => $millis:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let expected: Option<u64> = $millis;
+                    assert_eq!(
+                        parse_duration_strict($input),
+                        expected.map(std::time::Duration::from_millis)
+                    );
+                }
+            )*
+        };
+    }
+
+    strict_duration_tests! {
+        strict_duration_millis: "02:45.123" => Some(165_123),
+        strict_duration_no_millis: "02:45" => Some(165_000),
+        strict_duration_unpadded_minutes: "3:25(G)" => Some(205_000),
+        strict_duration_short_millis: "02:45.1" => None,
+        strict_duration_long_millis: "02:45.1234" => None,
+        strict_duration_one_digit_seconds: "02:5" => None,
+        strict_duration_seconds_overflow: "02:75" => None,
+        strict_duration_long_minutes: "123:45" => None,
+    }
+
+    #[test]
+    fn songlengths_totals() {
+        let db = SonglengthsDatabase::parse(
+            "[Database]\n\
+             ; /MUSICIANS/A/a.sid\n\
+             0123=1:00 2:30\n\
+             ; /MUSICIANS/B/b.sid\n\
+             4567=0:30\n",
+        );
+        assert_eq!(db.len(), 2);
+        assert_eq!(db.entry_count(), 3);
+        assert_eq!(db.total_duration(), std::time::Duration::from_secs(240));
+        assert_eq!(
+            db.stats_line(),
+            "Total tunes: 2 | Total duration: 0d 0h 4m | Average: 1:20"
+        );
+    }
+
+    #[test]
+    fn songlengths_verbose_reports_skipped_lines() {
+        let (db, warnings) = SonglengthsDatabase::parse_verbose(
+            "[Database]\n\
+             ; /MUSICIANS/A/a.sid\n\
+             0123456789abcdef0123456789ABCDEF=1:00\n\
+             no equals sign here\n\
+             xyz=0:30\n",
+        );
+        assert_eq!(db.len(), 1);
+        assert!(db.get("0123456789abcdef0123456789abcdef").is_some());
+        let summary: Vec<_> = warnings
+            .iter()
+            .map(|w| (w.line_num, w.reason.as_str()))
+            .collect();
+        assert_eq!(summary, [(4, "missing '='"), (5, "invalid MD5 format")]);
+    }
+
+    #[test]
+    fn songlengths_paths_from_comment_lines() {
+        let db = SonglengthsDatabase::parse(
+            "; Songlengths for HVSC\n\
+             [Database]\n\
+             ; /MUSICIANS/H/Hubbard_Rob/Commando.sid\n\
+             0001=3:10\n\
+             0002=1:00\n",
+        );
+        assert_eq!(
+            db.path_for_md5("0001"),
+            Some("/MUSICIANS/H/Hubbard_Rob/Commando.sid")
+        );
+        // Each comment applies only to the line right after it
+        assert_eq!(db.path_for_md5("0002"), None);
+        assert_eq!(db.path_for_md5("ffff"), None);
+    }
+
+    #[test]
+    fn songlengths_longest_and_shortest() {
+        let db = SonglengthsDatabase::parse(
+            "0001=1:00 4:32\n\
+             0002=0:30\n\
+             0003=2:00 0:00\n\
+             0004=0:05\n\
+             0005=3:15\n",
+        );
+        assert_eq!(db.total_unique_tunes(), 5);
+        assert_eq!(
+            db.longest_tune(),
+            Some(("0001", std::time::Duration::from_secs(272)))
+        );
+        assert_eq!(
+            db.shortest_tune(),
+            Some(("0004", std::time::Duration::from_secs(5)))
+        );
+        assert_eq!(
+            db.extremes_lines(),
+            ["Longest tune: 0001 (4:32)", "Shortest tune: 0004 (0:05)"]
+        );
+        assert_eq!(SonglengthsDatabase::default().longest_tune(), None);
+    }
+
+    #[test]
+    fn long_duration_format() {
+        let secs = ((42 * 24 + 7) * 60 + 18) * 60 + 59;
+        assert_eq!(
+            format_long_duration(std::time::Duration::from_secs(secs)),
+            "42d 7h 18m"
+        );
+    }
+
+    #[test]
+    fn slow_server_times_out() {
+        // Accepts connections but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/tune.sid", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let _held: Vec<_> = listener.incoming().take(1).collect();
+            thread::sleep(Duration::from_secs(5));
+        });
+
+        let start = std::time::Instant::now();
+        let err = fetch_bytes_with_timeout(&url, Duration::from_millis(50)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    /// Serves `statuses` in order, one connection each, answering 200 with `body` last.
+    fn serve_statuses(statuses: &'static [u16], body: &'static str) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/STIL.txt", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for (stream, &status) in listener.incoming().zip(statuses) {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request);
+                let body = if status == 200 { body } else { "" };
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn transient_errors_are_retried() {
+        let url = serve_statuses(&[503, 502, 200], "content");
+        let bytes = fetch_bytes_with_retry(&url, 3).expect("third attempt succeeds");
+        assert_eq!(bytes, b"content");
+
+        let url = serve_statuses(&[404, 200], "content");
+        assert!(fetch_bytes_with_retry(&url, 3).is_err());
+    }
+
+    #[test]
+    fn failing_mirror_falls_back_to_next() {
+        let mut browser = HvscBrowser::new("https://bad.example");
+        browser.set_mirrors(vec!["https://good.example".to_string()]);
+
+        let mut tried = Vec::new();
+        let result = browser.try_mirrors(|url| {
+            tried.push(url.to_string());
+            if url == "https://bad.example" {
+                Err(io::Error::other("connection refused"))
+            } else {
+                Ok(42)
+            }
+        });
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(tried, ["https://bad.example", "https://good.example"]);
+        assert_eq!(browser.active_mirror, 1);
+        assert_eq!(browser.base_url, "https://good.example");
+    }
+
+    #[test]
+    fn exported_listing_imports_back() {
+        let mut browser = HvscBrowser::new("https://example.invalid");
+        browser.current_path = "/MUSICIANS/H/".to_string();
+        browser.set_entries(vec![
+            HvscEntry {
+                name: "Hubbard_Rob".to_string(),
+                path: "/MUSICIANS/H/Hubbard_Rob/".to_string(),
+                is_dir: true,
+            },
+            HvscEntry {
+                name: "Hit.sid".to_string(),
+                path: "/MUSICIANS/H/Hit.sid".to_string(),
+                is_dir: false,
+            },
+        ]);
+        let dir = std::env::temp_dir().join(format!("crabsid-listing-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("listing.txt");
+        browser.export_current_directory(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# HVSC listing for /MUSICIANS/H/ generated by crabsid "));
+        assert!(text.ends_with("\nHubbard_Rob/\nHit.sid\n"));
+
+        let mut imported = HvscBrowser::new("https://example.invalid");
+        imported.import_directory_from_file(&path).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(imported.current_path, "/MUSICIANS/H/");
+        let parsed: Vec<(&str, &str, bool)> = imported
+            .entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.path.as_str(), e.is_dir))
+            .collect();
+        assert_eq!(
+            parsed,
+            [
+                ("Hubbard_Rob", "/MUSICIANS/H/Hubbard_Rob/", true),
+                ("Hit.sid", "/MUSICIANS/H/Hit.sid", false),
+            ]
+        );
+        assert_eq!(
+            (imported.entry_file_count, imported.entry_dir_count),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn filename_search_matches_globs_in_current_directory() {
+        let mut browser = HvscBrowser::new("https://example.invalid");
+        browser.set_entries(vec![
+            HvscEntry::new_file("Commando.sid", "/MUSICIANS/H/Hubbard_Rob/Commando.sid"),
+            HvscEntry::new_file("Delta.sid", "/MUSICIANS/H/Hubbard_Rob/Delta.sid"),
+            HvscEntry::new_file("Cybernoid.sid", "/MUSICIANS/T/Tel_Jeroen/Cybernoid.sid"),
+            HvscEntry {
+                name: "Extras".to_string(),
+                path: "/MUSICIANS/H/Hubbard_Rob/Extras/".to_string(),
+                is_dir: true,
+            },
+        ]);
+        let names = |query| -> Vec<String> {
+            browser
+                .search_by_filename(query)
+                .into_iter()
+                .map(|entry| entry.name)
+                .collect()
+        };
+        assert_eq!(names("*Hubbard*"), ["Commando.sid", "Delta.sid", "Extras"]);
+        assert_eq!(
+            names("*.sid"),
+            ["Commando.sid", "Delta.sid", "Cybernoid.sid"]
+        );
+        assert_eq!(names("cyber"), ["Cybernoid.sid"]);
+        assert_eq!(names("D?lta"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn stil_title_sort_keeps_directories_first() {
+        let mut browser = HvscBrowser::new("https://example.invalid");
+        browser.stil = Some(StilDatabase::parse(
+            "/MUSICIANS/X/A.sid\n\x20\x20TITLE: Zounds\n\n/MUSICIANS/X/B.sid\n\x20\x20TITLE: Alpha\n",
+        ));
+        browser.set_entries(vec![
+            HvscEntry::new_file("A.sid", "/MUSICIANS/X/A.sid"),
+            HvscEntry::new_file("B.sid", "/MUSICIANS/X/B.sid"),
+            HvscEntry::new_file("Untitled.sid", "/MUSICIANS/X/Untitled.sid"),
+            HvscEntry {
+                name: "Zz".to_string(),
+                path: "/MUSICIANS/X/Zz/".to_string(),
+                is_dir: true,
+            },
+        ]);
+        browser.sort_entries_by_stil_title();
+        let names: Vec<&str> = browser.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Zz", "B.sid", "Untitled.sid", "A.sid"]);
+
+        browser.selected = 1;
+        browser.sort_mode = SortMode::ByStilTitle;
+        assert!(browser.cycle_sort_mode());
+        assert_eq!(browser.sort_mode, SortMode::ByName);
+        assert_eq!(browser.entries[1].name, "A.sid");
+        assert_eq!(
+            browser.selected_entry().map(|e| e.name.as_str()),
+            Some("B.sid")
+        );
+    }
+
+    #[test]
+    fn root_listing_counts_entries() {
+        let mut browser = HvscBrowser::new("https://example.invalid");
+        assert_eq!((browser.entry_file_count, browser.entry_dir_count), (0, 3));
+        browser.set_entries(vec![HvscEntry {
+            name: "tune.sid".to_string(),
+            path: "/tune.sid".to_string(),
+            is_dir: false,
+        }]);
+        assert_eq!((browser.entry_file_count, browser.entry_dir_count), (1, 0));
+        browser.navigate_to("/");
+        assert_eq!((browser.entry_file_count, browser.entry_dir_count), (0, 3));
+    }
+
+    #[test]
+    fn returning_to_root_keeps_databases_and_settings() {
+        let mut browser = HvscBrowser::new("https://example.invalid");
+        browser.fetch_timeout = Duration::from_secs(3);
+        browser.songlengths = Some(SonglengthsDatabase::parse("0001=1:00\n"));
+        browser.navigate_to("/");
+        assert_eq!(browser.fetch_timeout, Duration::from_secs(3));
+        assert!(browser.songlengths.is_some());
+        assert_eq!(browser.current_path, "/");
+    }
+
+    #[test]
+    fn navigate_retries_second_mirror() {
+        let root = std::env::temp_dir().join(format!("crabsid-mirror-{}", std::process::id()));
+        fs::create_dir_all(root.join("MUSICIANS")).unwrap();
+        fs::write(root.join("MUSICIANS").join("tune.sid"), b"").unwrap();
+
+        let mut browser = HvscBrowser::new("file:///nonexistent/crabsid/mirror");
+        browser.set_mirrors(vec![format!("file://{}", root.display())]);
+        browser.navigate_to_immediate("/MUSICIANS/");
+
+        assert!(browser.error.is_none());
+        assert_eq!(browser.active_mirror, 1);
+        assert_eq!(browser.entries.len(), 1);
+        assert_eq!(browser.entries[0].name, "tune.sid");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn second_navigation_replaces_pending_one() {
+        let root = std::env::temp_dir().join(format!("crabsid-nav-{}", std::process::id()));
+        fs::create_dir_all(root.join("GAMES")).unwrap();
+        fs::create_dir_all(root.join("DEMOS")).unwrap();
+        fs::write(root.join("GAMES").join("game.sid"), b"").unwrap();
+        fs::write(root.join("DEMOS").join("demo.sid"), b"").unwrap();
+
+        let mut browser = HvscBrowser::new(&format!("file://{}", root.display()));
+        browser.navigate_to("/GAMES/");
+        browser.navigate_to("/DEMOS/");
+        assert!(browser.loading);
+
+        while browser.loading {
+            browser.poll_pending_nav();
+            thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        assert!(browser.error.is_none());
+        assert_eq!(browser.current_path, "/DEMOS/");
+        assert_eq!(browser.entries.len(), 1);
+        assert_eq!(browser.entries[0].name, "demo.sid");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn new_file_entry_is_not_a_directory() {
+        let entry = HvscEntry::new_file("Delta.sid", "/MUSICIANS/H/Hubbard_Rob/Delta.sid");
+        assert!(!entry.is_dir);
+        assert_eq!(entry.name, "Delta.sid");
+        assert_eq!(
+            entry.url("https://example.org"),
+            "https://example.org/MUSICIANS/H/Hubbard_Rob/Delta.sid"
+        );
+    }
+
+    #[test]
+    fn clear_cache_removes_fetched_files() {
+        let dir = std::env::temp_dir().join(format!("crabsid-cache-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("source.txt");
+        fs::write(&source, "cached content").unwrap();
+        let url = format!("file://{}", source.display());
+
+        for name in ["STIL.txt", "Songlengths.md5"] {
+            let content = fetch_with_cache_in(Some(&dir), &url, name, false).unwrap();
+            assert_eq!(content, "cached content");
+            assert!(dir.join(name).exists());
+        }
+        assert!(dir_size_bytes(&dir) > 0);
+
+        clear_cache_in(&dir);
+        assert!(!dir.join("STIL.txt").exists());
+        assert!(!dir.join("Songlengths.md5").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
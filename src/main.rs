@@ -5,20 +5,29 @@
 
 #![deny(missing_docs)]
 
+mod biquad;
 mod config;
 mod hvsc;
 mod memory;
+#[cfg(feature = "mpris")]
+mod mpris;
+mod pidfile;
 mod player;
 mod playlist;
+mod remote;
 mod sid_file;
 mod tui;
+mod wav;
 
 use clap::Parser;
 use config::Config;
-use player::{SamplingMethod, create_shared_player};
-use playlist::Playlist;
+use player::{SamplingMethod, StepLimits, VOICES_PER_SID, create_shared_player};
+use playlist::{Playlist, PlaylistEntry};
+use residfp::ChipModel;
 use sid_file::SidFile;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tinyaudio::prelude::*;
 
 const SAMPLE_RATE: u32 = 44100;
@@ -31,10 +40,14 @@ struct Args {
     #[arg(name = "FILE")]
     files: Vec<PathBuf>,
 
-    /// Path to .m3u playlist file
+    /// Path to .m3u or .xspf playlist file
     #[arg(short = 'l', long)]
     playlist: Option<PathBuf>,
 
+    /// Merge entries of another playlist into the loaded one, skipping duplicates
+    #[arg(long, value_name = "PATH")]
+    merge_playlist: Option<PathBuf>,
+
     /// Song number to play (default: start song from file)
     #[arg(short, long)]
     song: Option<u16>,
@@ -43,25 +56,246 @@ struct Args {
     #[arg(short, long)]
     chip: Option<u16>,
 
+    /// Save the playlist with file paths relative to its directory
+    #[arg(long)]
+    portable: bool,
+
     /// Disable TUI and use simple text output
     #[arg(long)]
     no_tui: bool,
 
-    /// HVSC mirror base URL
-    #[arg(long, default_value = hvsc::DEFAULT_HVSC_URL)]
-    hvsc_url: String,
+    /// HVSC mirror base URL (default: first configured mirror)
+    #[arg(long)]
+    hvsc_url: Option<String>,
 
     /// Maximum song playtime in seconds before advancing
     #[arg(long, default_value = "180")]
     playtime: u64,
 
     /// Audio resampling method: fast, interpolate, resample, resample-fast, two-pass
-    #[arg(long, default_value = "two-pass", value_parser = parse_sampling_method)]
-    sampling: SamplingMethod,
+    /// (default: from config)
+    #[arg(long, visible_alias = "sampling-method", value_parser = parse_sampling_method)]
+    sampling: Option<SamplingMethod>,
+
+    /// Fast-forward N play calls (frames) into the song before playing
+    #[arg(long, value_name = "N")]
+    seek_frames: Option<u64>,
+
+    /// Continue the playlist in random order, skipping the last 10 entries played
+    #[arg(long)]
+    smart_shuffle: bool,
+
+    /// Write the last 300 frames of VU meter peaks to PATH as CSV on quit (TUI only)
+    #[arg(long, value_name = "PATH", conflicts_with = "no_tui")]
+    export_vu_history: Option<PathBuf>,
+
+    /// Save the first TUI frame to PATH as ANSI text (.ans) and exit
+    #[arg(long, value_name = "PATH", conflicts_with = "no_tui")]
+    screenshot: Option<PathBuf>,
+
+    /// Stop playback and exit after SECS seconds
+    #[arg(long, value_name = "SECS")]
+    exit_after: Option<u64>,
+
+    /// Output one audio channel per SID voice (3, 6 or 9 channels), approximated by
+    /// splitting the mixed output by voice envelope level
+    #[arg(long)]
+    multichannel: bool,
+
+    /// Add triangular dither to the output to mask quantization at low volumes
+    #[arg(long)]
+    dither: bool,
 
     /// Use EKV transistor model filter for more accurate 6581 emulation
     #[arg(long)]
     ekv: bool,
+
+    /// Clear the cached HVSC databases (STIL, Songlengths) and exit
+    #[arg(long)]
+    refresh_cache: bool,
+
+    /// Print HVSC Songlengths statistics and exit
+    #[arg(long)]
+    hvsc_stats: bool,
+
+    /// Print malformed lines in the HVSC Songlengths database and exit
+    #[arg(long)]
+    validate_songlengths: bool,
+
+    /// Write all STIL entries as CSV (path, title, artist, comment) and exit
+    #[arg(long, value_name = "PATH")]
+    export_stil_csv: Option<PathBuf>,
+
+    /// Write the listing of the --hvsc-path directory to PATH and exit
+    #[arg(long, value_name = "PATH")]
+    export_hvsc_dir: Option<PathBuf>,
+
+    /// Open the HVSC browser on a listing saved by --export-hvsc-dir (TUI only)
+    #[arg(long, value_name = "PATH", conflicts_with = "no_tui")]
+    import_hvsc_dir: Option<PathBuf>,
+
+    /// HVSC directory for --export-hvsc-dir, e.g. /MUSICIANS/H/
+    #[arg(long, value_name = "DIR", default_value = "/")]
+    hvsc_path: String,
+
+    /// Append an HVSC mirror URL to the saved fallback list
+    #[arg(long, value_name = "URL")]
+    add_hvsc_mirror: Vec<String>,
+
+    /// Check input files for header problems and exit (status 1 on warnings)
+    #[arg(long)]
+    validate: bool,
+
+    /// Load and check every entry of the --playlist file and exit (status 1 on failures)
+    #[arg(long)]
+    validate_playlist: bool,
+
+    /// Per-entry download limit for --validate-playlist URL entries
+    #[arg(long, value_name = "MS")]
+    timeout: Option<u64>,
+
+    /// Number of entries --validate-playlist loads concurrently
+    #[arg(long, value_name = "N", default_value = "1")]
+    parallel: usize,
+
+    /// Print the PAL/NTSC guess from each input file's CIA timer setup and exit
+    #[arg(long)]
+    show_clock_hint: bool,
+
+    /// Run for SECS seconds of audio, print the SID registers and exit
+    #[arg(long, value_name = "SECS")]
+    dump_registers: Option<u32>,
+
+    /// Run FRAMES play calls, print every SID write as CSV (cycle, address, value) and exit
+    #[arg(long, value_name = "FRAMES")]
+    trace_writes: Option<u64>,
+
+    /// Print machine-readable JSON (with --dump-registers or --list-songs)
+    #[arg(long)]
+    json: bool,
+
+    /// Check the data section CRC32 of each FILE and exit
+    #[arg(long)]
+    verify_integrity: bool,
+
+    /// Print duration, chip and STIL comment of every subsong in FILE and exit
+    #[arg(long)]
+    list_songs: bool,
+
+    /// Oscilloscope marker: braille, block, dot, bar, half-block (default: from config, `M` in the TUI)
+    #[arg(long, value_parser = parse_scope_marker)]
+    scope_marker: Option<String>,
+
+    /// Write the named color scheme to a JSON theme file and exit
+    #[arg(long, num_args = 2, value_names = ["NAME", "PATH"])]
+    export_theme: Option<Vec<String>>,
+
+    /// Add the color scheme in a JSON theme file to the config and exit
+    #[arg(long, value_name = "PATH")]
+    import_theme: Option<PathBuf>,
+
+    /// Print the effective configuration (file, environment and CLI) as TOML and exit
+    #[arg(long)]
+    list_config: bool,
+
+    /// Start even if another instance holds the lock file
+    #[arg(long)]
+    force: bool,
+
+    /// Override the play address of the tune, e.g. 0x1003 (0 = no play routine)
+    #[arg(long = "play-address", value_name = "HEX", value_parser = parse_hex_address)]
+    play_address_override: Option<u16>,
+
+    /// Override the init address of the tune, e.g. 0x1000
+    #[arg(long = "init-address", value_name = "HEX", value_parser = parse_hex_address)]
+    init_address_override: Option<u16>,
+
+    /// Write the tune, with any address overrides, to PATH as a SID file and exit
+    #[arg(long, value_name = "PATH")]
+    save_sid: Option<PathBuf>,
+
+    /// Add a SID chip beyond the file's own, e.g. D600:6581 (repeatable, 8 SIDs in total)
+    #[arg(long, value_name = "ADDR:MODEL", value_parser = parse_extra_sid)]
+    extra_sid: Vec<(u16, ChipModel)>,
+
+    /// Instructions a tune's init routine may run before giving up (default: from config)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    init_max_steps: Option<u32>,
+
+    /// Load an 8 KB Kernal ROM image for tunes that read $E000-$FFFF
+    #[arg(long, value_name = "PATH")]
+    kernal_rom: Option<PathBuf>,
+
+    /// Load an 8 KB BASIC ROM image for tunes that read $A000-$BFFF
+    #[arg(long, value_name = "PATH")]
+    basic_rom: Option<PathBuf>,
+
+    /// Load a 4 KB character ROM image, read at $D000-$DFFF while I/O is banked out
+    #[arg(long, value_name = "PATH")]
+    char_rom: Option<PathBuf>,
+
+    /// Accept WebSocket remote control on localhost at this port (TUI only)
+    #[arg(long, value_name = "PORT", conflicts_with = "no_tui")]
+    ws_port: Option<u16>,
+}
+
+/// Parse oscilloscope marker from CLI string into its config name.
+fn parse_scope_marker(s: &str) -> Result<String, String> {
+    let key = s.replace('-', "");
+    config::SCOPE_MARKERS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(&key))
+        .map(|(name, _)| (*name).to_string())
+        .ok_or_else(|| {
+            format!("unknown scope marker '{s}', expected: braille, block, dot, bar, half-block")
+        })
+}
+
+/// Parse a 16-bit address given as hex, with or without a `0x` or `$` prefix.
+fn parse_hex_address(s: &str) -> Result<u16, String> {
+    let digits = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .or_else(|| s.strip_prefix('$'))
+        .unwrap_or(s);
+    u16::from_str_radix(digits, 16)
+        .map_err(|_| format!("invalid address '{s}', expected hex like 0x1003"))
+}
+
+/// Parse an extra SID chip given as `ADDR:MODEL`, e.g. `D600:6581`.
+fn parse_extra_sid(s: &str) -> Result<(u16, ChipModel), String> {
+    let (address, model) = s
+        .split_once(':')
+        .ok_or_else(|| format!("invalid extra SID '{s}', expected ADDR:MODEL like D600:6581"))?;
+    let model = match model {
+        "6581" => ChipModel::Mos6581,
+        "8580" => ChipModel::Mos8580,
+        _ => {
+            return Err(format!(
+                "unknown chip model '{model}', expected 6581 or 8580"
+            ));
+        }
+    };
+    let address = parse_hex_address(address)?;
+    if address % memory::SID_REGISTER_COUNT != 0 {
+        return Err(format!(
+            "extra SID address {address:04X} is not a multiple of {:X}",
+            memory::SID_REGISTER_COUNT
+        ));
+    }
+    match address {
+        0xD400 => Err("D400 is always the tune's first SID".to_string()),
+        0xD420..=0xD7FF | 0xDE00..=0xDFFF => Ok((address, model)),
+        0xD800..=0xDBFF => Err(format!(
+            "extra SID address {address:04X} overlaps colour RAM D800-DBFF"
+        )),
+        0xDC00..=0xDDFF => Err(format!(
+            "extra SID address {address:04X} overlaps the CIAs at DC00-DDFF"
+        )),
+        _ => Err(format!(
+            "extra SID address {address:04X} outside the I/O area D400-DFFF"
+        )),
+    }
 }
 
 /// Parse sampling method from CLI string.
@@ -79,6 +313,25 @@ fn parse_sampling_method(s: &str) -> Result<SamplingMethod, String> {
     }
 }
 
+/// Size of a C64 Kernal or BASIC ROM image in bytes
+const KERNAL_ROM_SIZE: usize = 8192;
+/// Size of a C64 character ROM image in bytes
+const CHAR_ROM_SIZE: usize = 4096;
+
+/// Reads a ROM image, rejecting files that are not exactly `size` bytes.
+fn read_rom(path: &Path, kind: &str, size: usize) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let rom = std::fs::read(path)?;
+    if rom.len() != size {
+        return Err(format!(
+            "{}: {kind} ROM must be {size} bytes, got {}",
+            path.display(),
+            rom.len()
+        )
+        .into());
+    }
+    Ok(rom)
+}
+
 fn default_playlist_path() -> PathBuf {
     dirs::config_dir()
         .unwrap_or_else(|| PathBuf::from("."))
@@ -89,6 +342,204 @@ fn default_playlist_path() -> PathBuf {
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
 
+    if args.refresh_cache {
+        let freed = hvsc::cache_size_bytes();
+        hvsc::clear_cache();
+        println!("HVSC cache cleared ({} KB freed)", freed / 1024);
+        return Ok(());
+    }
+
+    if args.validate {
+        if !validate_files(&args.files) {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if args.show_clock_hint {
+        for path in &args.files {
+            let sid = SidFile::load(path)?;
+            match SidFile::detect_clock_from_data(&sid.data) {
+                Some(hint) => println!(
+                    "{}: {} ({:.0}% of CIA timer values agree)",
+                    path.display(),
+                    hint.clock,
+                    hint.confidence * 100.0
+                ),
+                None => println!("{}: no CIA timer setup found", path.display()),
+            }
+        }
+        return Ok(());
+    }
+
+    if args.validate_playlist {
+        let Some(path) = &args.playlist else {
+            return Err("--validate-playlist needs --playlist <FILE>".into());
+        };
+        let playlist = Playlist::load(path)?;
+        let timeout = args.timeout.map(Duration::from_millis);
+        let results = check_playlist_entries(&playlist.entries, timeout, args.parallel);
+        let (report, all_valid) = playlist_report(&playlist.entries, &results);
+        print!("{report}");
+        if !all_valid {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let mut user_config = Config::load();
+    hvsc::set_http_max_retries(user_config.http_max_retries);
+    let hvsc_url = args.hvsc_url.clone().unwrap_or_else(|| {
+        user_config
+            .hvsc_mirrors
+            .first()
+            .cloned()
+            .unwrap_or_else(|| hvsc::DEFAULT_HVSC_URL.to_string())
+    });
+
+    if args.validate_songlengths {
+        let (db, warnings) = hvsc::SonglengthsDatabase::fetch_verbose(&hvsc_url)?;
+        for warning in &warnings {
+            println!("{warning}");
+        }
+        println!("{} tunes, {} lines skipped", db.len(), warnings.len());
+        if !warnings.is_empty() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if args.hvsc_stats {
+        let db = hvsc::SonglengthsDatabase::fetch(&hvsc_url)?;
+        println!("{}", db.stats_line());
+        for line in db.extremes_lines() {
+            println!("{line}");
+        }
+        return Ok(());
+    }
+
+    if let Some(path) = &args.export_hvsc_dir {
+        let mut browser = hvsc::HvscBrowser::new(&hvsc_url);
+        browser.navigate_to_immediate(&hvsc_dir_path(&args.hvsc_path));
+        if let Some(err) = browser.error {
+            return Err(err.into());
+        }
+        browser.export_current_directory(path)?;
+        println!(
+            "Exported {} entries of {} to {}",
+            browser.entries.len(),
+            browser.current_path,
+            path.display()
+        );
+        return Ok(());
+    }
+
+    if let Some(path) = &args.export_stil_csv {
+        let db = hvsc::StilDatabase::fetch(&hvsc_url)?;
+        db.export_to_csv(path)?;
+        println!("Exported {} STIL entries to {}", db.len(), path.display());
+        return Ok(());
+    }
+
+    if !args.add_hvsc_mirror.is_empty() {
+        for url in &args.add_hvsc_mirror {
+            if !user_config.hvsc_mirrors.contains(url) {
+                user_config.hvsc_mirrors.push(url.clone());
+            }
+        }
+        user_config.save();
+    }
+
+    if let Some(marker) = &args.scope_marker {
+        user_config.scope_marker.clone_from(marker);
+    }
+
+    if let Some([name, path]) = args.export_theme.as_deref() {
+        tui::theme::export_theme(name, &user_config.custom_schemes, Path::new(path))?;
+        println!("Exported color scheme '{name}' to {path}");
+        return Ok(());
+    }
+
+    if let Some(path) = &args.import_theme {
+        let scheme = tui::theme::import_theme(path)?;
+        println!("Imported color scheme '{}'", scheme.name);
+        user_config.custom_schemes.retain(|s| s.name != scheme.name);
+        user_config.custom_schemes.push(scheme);
+        user_config.save();
+        return Ok(());
+    }
+
+    if args.list_config {
+        if let Some(method) = args.sampling {
+            user_config.sampling_method = config::sampling_method_name(method).to_string();
+        }
+        user_config.hvsc_mirrors.retain(|m| *m != hvsc_url);
+        user_config.hvsc_mirrors.insert(0, hvsc_url);
+        print!("{}", toml::to_string_pretty(&user_config)?);
+        return Ok(());
+    }
+
+    if args.verify_integrity {
+        if args.files.is_empty() {
+            return Err("--verify-integrity needs a FILE".into());
+        }
+        let mut failed = false;
+        for file in &args.files {
+            match SidFile::verify_integrity(file) {
+                Ok(crc) => println!("{}: OK (CRC32 {crc:08X})", file.display()),
+                Err(e) => {
+                    println!("{}: {e}", file.display());
+                    failed = true;
+                }
+            }
+        }
+        if failed {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if args.list_songs {
+        let Some(file) = args.files.first() else {
+            return Err("--list-songs needs a FILE".into());
+        };
+        let sid = SidFile::load(file)?;
+        let songlengths = hvsc::SonglengthsDatabase::fetch(&hvsc_url)
+            .inspect_err(|e| eprintln!("Songlengths unavailable: {e}"))
+            .ok();
+        let stil = hvsc::StilDatabase::fetch(&hvsc_url)
+            .inspect_err(|e| eprintln!("STIL unavailable: {e}"))
+            .ok();
+        let hvsc_path = hvsc_relative_path(file);
+        let songs: Vec<SongInfo> = (1..=sid.songs)
+            .map(|song| SongInfo {
+                song,
+                duration_secs: songlengths
+                    .as_ref()
+                    .and_then(|db| db.get(&sid.md5)?.get(usize::from(song) - 1).copied())
+                    .map(|d| d.as_secs()),
+                chip: user_config
+                    .subsong_chip_overrides
+                    .get(&config::subsong_key(&sid.md5, song))
+                    .map_or_else(
+                        || sid.chip_model_name_for_sid(0).to_string(),
+                        u16::to_string,
+                    ),
+                comment: stil
+                    .as_ref()
+                    .zip(hvsc_path.as_deref())
+                    .and_then(|(db, path)| db.comment_for_subsong(path, song))
+                    .map(str::to_string),
+            })
+            .collect();
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&songs)?);
+        } else {
+            print!("{}", song_table(&songs));
+        }
+        return Ok(());
+    }
+
     // Load existing playlist or create new one, then append CLI files as absolute paths
     let playlist_path = args.playlist.clone().unwrap_or_else(default_playlist_path);
     let mut playlist = Playlist::load_or_create(&playlist_path)?;
@@ -97,52 +548,167 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let absolute = file.canonicalize().unwrap_or_else(|_| file.clone());
         playlist_modified |= playlist.add(&absolute.to_string_lossy(), None);
     }
+    if let Some(path) = &args.merge_playlist {
+        playlist_modified |= playlist.merge(&Playlist::load(path)?) > 0;
+    }
 
     // Determine initial SID file to play
-    let (sid_file, initial_song) = if !args.files.is_empty() {
+    let (mut sid_file, initial_song, initial_source) = if !args.files.is_empty() {
         // Play first file from CLI
         let sid = SidFile::load(&args.files[0])?;
         let song = args.song.unwrap_or(sid.start_song);
-        (sid, song)
+        let absolute = args.files[0]
+            .canonicalize()
+            .unwrap_or_else(|_| args.files[0].clone());
+        (sid, song, Some(absolute.to_string_lossy().into_owned()))
+    } else if let Some((sid, song, source)) = user_config
+        .last_session
+        .as_ref()
+        .and_then(|session| find_session_tune(&playlist, session))
+    {
+        // Resume the tune that was playing on last exit
+        (sid, args.song.unwrap_or(song), Some(source))
     } else if !playlist.is_empty() {
         // Play first from playlist
         let entry = &playlist.entries[0];
         let sid = entry.load()?;
         let song = args.song.or(entry.subsong).unwrap_or(sid.start_song);
-        (sid, song)
+        (sid, song, Some(entry.source.clone()))
     } else {
         // Empty playlist, no files - need a dummy SID for player init
         // TUI will start with HVSC browser focused
         let dummy = create_silent_sid();
-        (dummy, 1)
+        (dummy, 1, None)
     };
 
     if sid_file.requires_full_emulation() {
         return Err("Unsupported RSID-like format (requires CIA/interrupt emulation)".into());
     }
+    override_addresses(
+        &mut sid_file,
+        args.init_address_override,
+        args.play_address_override,
+    );
+    if let Some(path) = &args.save_sid {
+        sid_file.write_to_path(path)?;
+        println!("Saved {} to {}", sid_file.name, path.display());
+        return Ok(());
+    }
+
+    // CLI flag overrides the saved sampling method
+    let sampling = args
+        .sampling
+        .unwrap_or_else(|| config::parse_sampling_method(&user_config.sampling_method));
 
+    let limits = StepLimits {
+        init_max_steps: args.init_max_steps.unwrap_or(user_config.init_max_steps),
+        ..StepLimits::default()
+    };
     let player = create_shared_player(
         &sid_file,
         initial_song,
-        SAMPLE_RATE,
+        user_config.sample_rate,
         args.chip,
-        args.sampling,
+        sampling,
+        limits,
     )
     .map_err(|e| format!("{e}"))?;
 
-    // Enable EKV filter if requested
-    if args.ekv
-        && let Ok(mut p) = player.lock()
-    {
-        for i in 0..p.sid_count() {
-            p.toggle_ekv_filter(Some(i));
+    if let Ok(mut p) = player.lock() {
+        p.set_master_volume(user_config.master_volume);
+        p.set_mix_mode(config::parse_mix_mode(&user_config.mix_mode));
+        p.apply_dithering(args.dither);
+        if !args.extra_sid.is_empty() {
+            p.set_extra_sids(args.extra_sid.clone())?;
+        }
+        if let Some(path) = &args.kernal_rom {
+            p.install_kernal_rom(&read_rom(path, "Kernal", KERNAL_ROM_SIZE)?);
+        }
+        if let Some(path) = &args.basic_rom {
+            p.install_basic_rom(&read_rom(path, "BASIC", KERNAL_ROM_SIZE)?);
+        }
+        if let Some(path) = &args.char_rom {
+            p.install_char_rom(&read_rom(path, "Character", CHAR_ROM_SIZE)?);
+        }
+        if args.kernal_rom.is_some() || args.basic_rom.is_some() || args.char_rom.is_some() {
+            // Rerun init so it sees the ROMs too
+            p.load_song(initial_song)?;
+        }
+        if let Some(frames) = args.seek_frames {
+            p.seek_to_frame(frames)?;
+        }
+        // Enable EKV filter if requested
+        if args.ekv {
+            for i in 0..p.sid_count() {
+                p.toggle_ekv_filter(Some(i));
+            }
+        }
+    }
+
+    if let Some(secs) = args.dump_registers {
+        if let Ok(mut p) = player.lock() {
+            p.render_seconds(secs);
+            let snapshot = p.register_snapshot();
+            for (i, (base, regs)) in p.sid_addresses().iter().zip(&snapshot).enumerate() {
+                if args.json {
+                    println!("{}", register_json(i + 1, *base, regs));
+                } else {
+                    println!("{}", register_row(i + 1, *base, regs));
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(frames) = args.trace_writes {
+        if let Ok(mut p) = player.lock() {
+            p.enable_write_trace();
+            p.seek_to_frame(frames)?;
+            println!("cycle,address,value");
+            for event in p.take_write_trace() {
+                println!(
+                    "{},${:04X},${:02X}",
+                    event.cpu_cycle, event.address, event.value
+                );
+            }
         }
+        return Ok(());
     }
 
+    // Only one instance plays at a time; the lock is released when main returns
+    let _lock = match pidfile::default_lock_path() {
+        Some(path) => {
+            if args.force {
+                let _ = std::fs::remove_file(&path);
+            }
+            match pidfile::PidFile::new(&path) {
+                Ok(lock) => Some(lock),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let pid = pidfile::PidFile::holder(&path).unwrap_or_default();
+                    eprintln!("crabsid is already running (PID {pid}). Use --force to override.");
+                    std::process::exit(2);
+                }
+                Err(e) => {
+                    eprintln!("cannot create lock file {}: {e}", path.display());
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
+    // One channel per voice of the initial tune when --multichannel is given
+    let channels = if args.multichannel {
+        player
+            .lock()
+            .map_or(VOICES_PER_SID, |p| p.total_voice_count())
+    } else {
+        1
+    };
     let params = OutputDeviceParameters {
-        channels_count: 1,
-        sample_rate: SAMPLE_RATE as usize,
-        channel_sample_count: BUFFER_SIZE,
+        channels_count: channels,
+        sample_rate: user_config.sample_rate as usize,
+        channel_sample_count: user_config.buffer_size,
     };
 
     // Audio callback runs in separate thread
@@ -150,38 +716,240 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let player = player.clone();
         move |data| {
             if let Ok(mut p) = player.lock() {
-                p.fill_buffer(data);
+                p.fill_buffer_crossfade(data, channels);
             }
         }
     })?;
 
+    let exit_after = args.exit_after.map(Duration::from_secs);
     if args.no_tui {
-        run_simple(&sid_file, initial_song)?;
+        let chips = player
+            .lock()
+            .map(|p| p.chip_model_descriptions().join("|"))
+            .unwrap_or_default();
+        run_simple(&sid_file, initial_song, &chips, &player, exit_after)?;
     } else {
-        let mut user_config = Config::load();
         let focus_hvsc = args.files.is_empty() && playlist.is_empty();
+        #[cfg(feature = "mpris")]
+        let mpris = mpris::spawn(player.clone()).ok();
         let tui_config = tui::TuiConfig {
             player,
             sid_file: &sid_file,
+            source: initial_source,
             song: initial_song,
             playlist,
             playlist_path,
+            portable: args.portable,
             focus_hvsc,
             playlist_modified,
-            hvsc_url: &args.hvsc_url,
+            hvsc_url: &hvsc_url,
             playtime_secs: args.playtime,
-            color_scheme: user_config.color_scheme,
+            user_config,
+            remote: args.ws_port.map(remote::spawn).transpose()?,
+            #[cfg(feature = "mpris")]
+            mpris,
+            exit_after,
+            smart_shuffle: args.smart_shuffle,
+            export_vu_history: args.export_vu_history,
+            screenshot: args.screenshot,
+            hvsc_listing: args.import_hvsc_dir,
         };
-        let final_color_scheme = tui::run_tui(tui_config)?;
-        user_config.color_scheme = final_color_scheme;
-        user_config.save();
+        let final_config = tui::run_tui(tui_config)?;
+        final_config.save();
     }
 
     Ok(())
 }
 
+/// Loads the last session's tune if its source is still a local playlist entry
+/// with the same MD5. Returns the tune, song and source.
+fn find_session_tune(
+    playlist: &Playlist,
+    session: &config::PlayerStateRef,
+) -> Option<(SidFile, u16, String)> {
+    let entry = playlist
+        .entries
+        .iter()
+        .find(|entry| !entry.is_url() && entry.source == session.source)?;
+    let sid = entry.load().ok()?;
+    sid.md5
+        .eq_ignore_ascii_case(&session.md5)
+        .then(|| (sid, session.song, entry.source.clone()))
+}
+
+/// Prints validation warnings for each file to stderr. Returns true if all files are clean.
+fn validate_files(files: &[PathBuf]) -> bool {
+    let mut clean = true;
+    for path in files {
+        match SidFile::load(path) {
+            Ok(sid) => {
+                for warning in sid.validate() {
+                    eprintln!("{}: {warning}", path.display());
+                    clean = false;
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                clean = false;
+            }
+        }
+    }
+    clean
+}
+
+/// Replaces the init and play addresses from `--init-address`/`--play-address`,
+/// noting each change on stderr.
+fn override_addresses(sid: &mut SidFile, init: Option<u16>, play: Option<u16>) {
+    if let Some(init) = init {
+        eprintln!(
+            "Init address overridden: ${:04X} -> ${init:04X}",
+            sid.init_address
+        );
+        sid.init_address = init;
+    }
+    if let Some(play) = play {
+        eprintln!(
+            "Play address overridden: ${:04X} -> ${play:04X}",
+            sid.play_address
+        );
+        sid.play_address = play;
+    }
+}
+
+/// Normalizes an HVSC directory to the browser's `/DIR/` form.
+fn hvsc_dir_path(dir: &str) -> String {
+    match dir.trim_matches('/') {
+        "" => "/".to_string(),
+        dir => format!("/{dir}/"),
+    }
+}
+
+/// Loads an entry and checks its header; the error is the load failure or the warnings.
+fn check_playlist_entry(entry: &PlaylistEntry, timeout: Option<Duration>) -> Result<(), String> {
+    let sid = match timeout {
+        Some(timeout) => entry.load_with_timeout(timeout),
+        None => entry.load(),
+    }
+    .map_err(|e| e.to_string())?;
+    let warnings: Vec<String> = sid.validate().iter().map(ToString::to_string).collect();
+    if warnings.is_empty() {
+        Ok(())
+    } else {
+        Err(warnings.join("; "))
+    }
+}
+
+/// Checks entries on up to `parallel` threads at a time, returning results in entry order.
+fn check_playlist_entries(
+    entries: &[PlaylistEntry],
+    timeout: Option<Duration>,
+    parallel: usize,
+) -> Vec<Result<(), String>> {
+    entries
+        .chunks(parallel.max(1))
+        .flat_map(|batch| {
+            let handles: Vec<_> = batch
+                .iter()
+                .cloned()
+                .map(|entry| std::thread::spawn(move || check_playlist_entry(&entry, timeout)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|_| Err("validation thread panicked".to_string()))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Formats `[OK]`/`[FAIL: reason]` per entry and an `N/M entries valid` summary.
+/// Returns the report and whether every entry passed.
+fn playlist_report(entries: &[PlaylistEntry], results: &[Result<(), String>]) -> (String, bool) {
+    let mut report = String::new();
+    for (entry, result) in entries.iter().zip(results) {
+        let status = match result {
+            Ok(()) => "[OK]".to_string(),
+            Err(reason) => format!("[FAIL: {reason}]"),
+        };
+        report.push_str(&format!("{status} {}\n", entry.source));
+    }
+    let valid = results.iter().filter(|r| r.is_ok()).count();
+    report.push_str(&format!("{valid}/{} entries valid\n", results.len()));
+    (report, valid == results.len())
+}
+
+/// Formats one chip's registers as `SID1: D400=12 D401=A0 ...`.
+fn register_row(sid: usize, base: u16, regs: &[u8]) -> String {
+    let cells: Vec<String> = (base..)
+        .zip(regs)
+        .map(|(addr, val)| format!("{addr:04X}={val:02X}"))
+        .collect();
+    format!("SID{sid}: {}", cells.join(" "))
+}
+
+/// One chip's register dump for `--dump-registers --json`.
+#[derive(serde::Serialize)]
+struct RegisterDump {
+    sid: usize,
+    registers: BTreeMap<String, u8>,
+}
+
+/// Formats one chip's registers as `{"sid":1,"registers":{"D400":18,...}}`.
+fn register_json(sid: usize, base: u16, regs: &[u8]) -> String {
+    let registers = (base..)
+        .zip(regs)
+        .map(|(addr, &val)| (format!("{addr:04X}"), val))
+        .collect();
+    serde_json::to_string(&RegisterDump { sid, registers }).unwrap_or_default()
+}
+
+/// One subsong for `--list-songs`.
+#[derive(serde::Serialize)]
+struct SongInfo {
+    song: u16,
+    /// Songlengths duration, if the tune is in the database
+    duration_secs: Option<u64>,
+    chip: String,
+    /// STIL comment for the subsong (or the whole file)
+    comment: Option<String>,
+}
+
+/// Formats subsongs as a fixed-width `Song | Duration | Chip | Comment` table.
+fn song_table(songs: &[SongInfo]) -> String {
+    let mut table = format!(
+        "{:>4} | {:>8} | {:<9} | Comment\n",
+        "Song", "Duration", "Chip"
+    );
+    for info in songs {
+        let duration = info.duration_secs.map_or_else(
+            || "-".to_string(),
+            |secs| format!("{}:{:02}", secs / 60, secs % 60),
+        );
+        let comment = info.comment.as_deref().unwrap_or("").replace('\n', " ");
+        table.push_str(&format!(
+            "{:>4} | {duration:>8} | {:<9} | {comment}\n",
+            info.song, info.chip
+        ));
+    }
+    table
+}
+
+/// HVSC path (`/MUSICIANS/...`) of a file inside a local copy of the collection.
+fn hvsc_relative_path(file: &Path) -> Option<String> {
+    let path = file.to_string_lossy().replace('\\', "/");
+    ["/MUSICIANS/", "/GAMES/", "/DEMOS/"]
+        .iter()
+        .filter_map(|top| path.find(top))
+        .min()
+        .map(|start| path[start..].to_string())
+}
+
 /// Creates a minimal silent SID for when no file is loaded.
 fn create_silent_sid() -> SidFile {
+    let data = vec![0x60, 0x60, 0x60]; // RTS instructions
     SidFile {
         magic: "PSID".to_string(),
         version: 2,
@@ -196,22 +964,219 @@ fn create_silent_sid() -> SidFile {
         author: String::new(),
         released: String::new(),
         flags: 0,
-        data: vec![0x60, 0x60, 0x60], // RTS instructions
+        data_crc32: crc32fast::hash(&data),
+        data,
         md5: String::new(),
         second_sid_address: None,
         third_sid_address: None,
     }
 }
 
-fn run_simple(sid_file: &SidFile, song: u16) -> Result<(), Box<dyn std::error::Error>> {
+fn run_simple(
+    sid_file: &SidFile,
+    song: u16,
+    chips: &str,
+    player: &player::SharedPlayer,
+    exit_after: Option<Duration>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file_chips: Vec<&str> = (0..sid_file.sid_count())
+        .map(|i| sid_file.chip_model_name_for_sid(i))
+        .collect();
     println!("Title:    {}", sid_file.name);
     println!("Author:   {}", sid_file.author);
     println!("Released: {}", sid_file.released);
     println!("Songs:    {}", sid_file.songs);
+    println!("Chip:     {chips} (file: {})", file_chips.join("|"));
     println!("Playing song {} of {}", song, sid_file.songs);
     println!("Press Ctrl+C to stop");
 
+    let started_at = Instant::now();
     loop {
-        std::thread::sleep(std::time::Duration::from_secs(1));
+        std::thread::sleep(Duration::from_millis(100));
+        // Playback stops on errors such as a CIA-timed subsong
+        if let Some(err) = player.lock().ok().and_then(|mut p| p.take_error()) {
+            return Err(format!("Playback error: {err}").into());
+        }
+        if exit_after.is_some_and(|limit| started_at.elapsed() >= limit) {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn three_songs() -> Vec<SongInfo> {
+        vec![
+            SongInfo {
+                song: 1,
+                duration_secs: Some(205),
+                chip: "6581".to_string(),
+                comment: Some("Loader music.".to_string()),
+            },
+            SongInfo {
+                song: 2,
+                duration_secs: Some(61),
+                chip: "8580".to_string(),
+                comment: None,
+            },
+            SongInfo {
+                song: 3,
+                duration_secs: None,
+                chip: "6581".to_string(),
+                comment: Some("Two\nlines".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn song_table_has_fixed_columns() {
+        let table = song_table(&three_songs());
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "Song | Duration | Chip      | Comment");
+        assert_eq!(lines[1], "   1 |     3:25 | 6581      | Loader music.");
+        assert_eq!(lines[2], "   2 |     1:01 | 8580      | ");
+        assert_eq!(lines[3], "   3 |        - | 6581      | Two lines");
+    }
+
+    #[test]
+    fn song_list_json_is_valid() {
+        let json = serde_json::to_string_pretty(&three_songs()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value.as_array().map(Vec::len), Some(3));
+        assert_eq!(value[0]["duration_secs"], 205);
+        assert!(value[2]["duration_secs"].is_null());
+    }
+
+    #[test]
+    fn playlist_validation_reports_each_entry() {
+        let garbage = std::env::temp_dir().join(format!("crabsid-bad-{}.sid", std::process::id()));
+        std::fs::write(&garbage, b"not a sid file").unwrap();
+        let entries: Vec<PlaylistEntry> = [
+            "tests/Hexadecimal_2SID.sid",
+            "tests/missing.sid",
+            &garbage.to_string_lossy(),
+        ]
+        .iter()
+        .filter_map(|source| PlaylistEntry::new(source))
+        .collect();
+
+        let results = check_playlist_entries(&entries, None, 2);
+        let (report, all_valid) = playlist_report(&entries, &results);
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(!all_valid);
+        assert_eq!(lines[0], "[OK] tests/Hexadecimal_2SID.sid");
+        assert!(lines[1].starts_with("[FAIL: ") && lines[1].ends_with("] tests/missing.sid"));
+        assert!(lines[2].starts_with("[FAIL: "));
+        assert_eq!(lines[3], "1/3 entries valid");
+
+        let (_, all_valid) = playlist_report(&entries[..1], &results[..1]);
+        assert!(all_valid);
+        std::fs::remove_file(garbage).unwrap();
+    }
+
+    #[test]
+    fn vu_history_export_needs_the_tui() {
+        let args = ["crabsid", "--no-tui", "--export-vu-history", "vu.csv"];
+        assert!(Args::try_parse_from(args).is_err());
+        assert!(Args::try_parse_from(args.iter().filter(|&&arg| arg != "--no-tui")).is_ok());
+    }
+
+    #[test]
+    fn rom_of_wrong_size_is_rejected() {
+        let path = std::env::temp_dir().join(format!("crabsid-rom-{}.bin", std::process::id()));
+        std::fs::write(&path, [0xEA; CHAR_ROM_SIZE]).unwrap();
+        assert!(read_rom(&path, "Character", CHAR_ROM_SIZE).is_ok());
+        let err = read_rom(&path, "BASIC", KERNAL_ROM_SIZE).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            err.to_string()
+                .contains("BASIC ROM must be 8192 bytes, got 4096")
+        );
+    }
+
+    #[test]
+    fn play_address_override_reaches_player() {
+        let args = Args::try_parse_from([
+            "crabsid",
+            "--play-address",
+            "0x0000",
+            "--init-address",
+            "$1000",
+        ])
+        .unwrap();
+        assert_eq!(args.play_address_override, Some(0));
+        assert_eq!(args.init_address_override, Some(0x1000));
+
+        let mut sid = create_silent_sid();
+        override_addresses(
+            &mut sid,
+            args.init_address_override,
+            args.play_address_override,
+        );
+        assert_eq!(sid.play_address, 0);
+        assert_eq!(sid.init_address, 0x1000);
+        assert!(parse_hex_address("zz").is_err());
+    }
+
+    #[test]
+    fn init_max_steps_must_be_positive() {
+        let parse = |n| Args::try_parse_from(["crabsid", "--init-max-steps", n]);
+        assert_eq!(parse("1").unwrap().init_max_steps, Some(1));
+        assert!(parse("0").is_err());
+    }
+
+    #[test]
+    fn saved_sid_keeps_address_overrides() {
+        let args = Args::try_parse_from([
+            "crabsid",
+            "--init-address",
+            "0x1000",
+            "--save-sid",
+            "out.sid",
+        ])
+        .unwrap();
+        assert_eq!(args.save_sid, Some(PathBuf::from("out.sid")));
+
+        let mut sid = create_silent_sid();
+        override_addresses(&mut sid, args.init_address_override, None);
+        let path = std::env::temp_dir().join(format!("crabsid-save-{}.sid", std::process::id()));
+        sid.write_to_path(&path).unwrap();
+        let saved = SidFile::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(saved.init_address, 0x1000);
+    }
+
+    #[test]
+    fn extra_sid_parses_address_and_model() {
+        assert_eq!(
+            parse_extra_sid("D600:8580"),
+            Ok((0xD600, ChipModel::Mos8580))
+        );
+        assert!(parse_extra_sid("D600").is_err());
+        assert!(parse_extra_sid("D600:6582").is_err());
+        assert!(parse_extra_sid("D400:6581").is_err());
+        assert!(parse_extra_sid("D410:6581").is_err());
+        assert!(parse_extra_sid("DC00:6581").is_err());
+        assert!(parse_extra_sid("D800:6581").is_err());
+        assert_eq!(
+            parse_extra_sid("DE00:6581"),
+            Ok((0xDE00, ChipModel::Mos6581))
+        );
+        assert!(parse_extra_sid("E000:6581").is_err());
+        assert!(parse_extra_sid("1000:6581").is_err());
+    }
+
+    #[test]
+    fn hvsc_path_from_local_collection() {
+        assert_eq!(
+            hvsc_relative_path(Path::new(
+                "/data/C64Music/MUSICIANS/H/Hubbard_Rob/Delta.sid"
+            ))
+            .as_deref(),
+            Some("/MUSICIANS/H/Hubbard_Rob/Delta.sid")
+        );
+        assert_eq!(hvsc_relative_path(Path::new("tunes/Delta.sid")), None);
     }
 }
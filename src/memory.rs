@@ -5,12 +5,39 @@ use mos6502::memory::Bus;
 use residfp::{ChipModel, Sid};
 
 const RAM_SIZE: usize = 65536;
-const SID_REGISTER_COUNT: u16 = 0x20;
+/// Size of the address window each SID chip answers to
+pub const SID_REGISTER_COUNT: u16 = 0x20;
+/// First filter register: cutoff low ($15), followed by cutoff high and resonance/routing
+const FILTER_REGISTERS_START: u8 = 0x15;
+/// Sizes of the BASIC/Kernal ROMs and the character ROM
+const ROM_SIZE: usize = 8192;
+const CHAR_ROM_SIZE: usize = 4096;
+/// Processor port bits at $01 selecting which ROMs are banked in
+const LORAM: u8 = 0x01;
+const HIRAM: u8 = 0x02;
+const CHAREN: u8 = 0x04;
+/// Cassette sense and motor bits, high at power-on
+const CASSETTE: u8 = 0x30;
+/// Traced writes kept before further ones are dropped (about ten minutes of a busy tune)
+const MAX_WRITE_TRACE: usize = 1_000_000;
+
+/// A CPU write to a SID register, recorded while write tracing is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteEvent {
+    /// Absolute address written, e.g. $D418
+    pub address: u16,
+    /// Byte written
+    pub value: u8,
+    /// Bus accesses since power-on, roughly CPU cycles
+    pub cpu_cycle: u64,
+}
 
 /// A SID chip with its base memory address.
 pub struct SidChip {
     pub sid: Sid,
     pub base_address: u16,
+    /// Values the tune last wrote to the filter registers ($15-$17)
+    filter_registers: [u8; 3],
 }
 
 impl SidChip {
@@ -18,6 +45,25 @@ impl SidChip {
         Self {
             sid: Sid::new(chip_model),
             base_address,
+            filter_registers: [0; 3],
+        }
+    }
+
+    /// Writes a register for the tune, remembering filter settings for `restore_filter_registers`.
+    fn write(&mut self, reg: u8, val: u8) {
+        self.sid.write(reg, val);
+        if let Some(slot) = reg
+            .checked_sub(FILTER_REGISTERS_START)
+            .and_then(|i| self.filter_registers.get_mut(usize::from(i)))
+        {
+            *slot = val;
+        }
+    }
+
+    /// Writes back the filter registers as the tune last set them, undoing a filter bypass.
+    pub fn restore_filter_registers(&mut self) {
+        for (reg, val) in (FILTER_REGISTERS_START..).zip(self.filter_registers) {
+            self.sid.write(reg, val);
         }
     }
 
@@ -27,26 +73,59 @@ impl SidChip {
     }
 }
 
-/// Emulated C64 memory map with 1-3 SID chips.
+/// Emulated C64 memory map with 1-8 SID chips.
 ///
 /// Provides 64KB RAM with memory-mapped I/O for SID sound chips.
 /// Primary SID at $D400, optional second/third at configurable addresses.
 /// All other I/O areas (VIC, CIA, etc.) are treated as plain RAM since
 /// SID playback only requires the sound chips.
+///
+/// ROM images are optional. An installed Kernal or BASIC ROM shadows RAM
+/// reads in its area; the character ROM only shows when the processor port
+/// at $01 banks out I/O. Writes always go to RAM, as on the real machine.
 pub struct C64Memory {
     /// 64KB RAM, heap-allocated to avoid stack overflow
     ram: Box<[u8]>,
-    /// SID sound chips (1-3), each at their configured address
+    /// SID sound chips (1-8), each at their configured address
     pub sids: Vec<SidChip>,
+    /// Reads and writes so far, roughly one per CPU cycle
+    bus_accesses: u64,
+    /// SID writes recorded since the last `take_write_trace`, if tracing
+    write_trace: Option<Vec<WriteEvent>>,
+    /// Kernal ROM at $E000-$FFFF, if installed
+    kernal_rom: Option<Box<[u8; ROM_SIZE]>>,
+    /// BASIC ROM at $A000-$BFFF, if installed
+    basic_rom: Option<Box<[u8; ROM_SIZE]>>,
+    /// Character ROM at $D000-$DFFF, if installed
+    char_rom: Option<Box<[u8; CHAR_ROM_SIZE]>>,
 }
 
 impl C64Memory {
-    /// Creates memory with zeroed RAM and a single SID chip at $D400.
+    /// Creates memory with zeroed RAM, all ROMs banked in at $01, and a single
+    /// SID chip at $D400.
     pub fn new(chip_model: ChipModel) -> Self {
-        Self {
+        let mut memory = Self {
             ram: vec![0; RAM_SIZE].into_boxed_slice(),
             sids: vec![SidChip::new(chip_model, 0xD400)],
-        }
+            bus_accesses: 0,
+            write_trace: None,
+            kernal_rom: None,
+            basic_rom: None,
+            char_rom: None,
+        };
+        memory.reset_processor_port(0);
+        memory
+    }
+
+    /// Sets $01 as a PSID player does before calling code at `address`: ROMs
+    /// that would hide the code are banked out, the rest stay in.
+    pub fn reset_processor_port(&mut self, address: u16) {
+        self.ram[1] = match address {
+            0x0000..=0x9FFF => LORAM | HIRAM | CHAREN,
+            0xA000..=0xCFFF => HIRAM | CHAREN,
+            0xD000..=0xDFFF => CHAREN,
+            0xE000..=0xFFFF => LORAM | CHAREN,
+        } | CASSETTE;
     }
 
     /// Configures SID chips from (base_address, chip_model) pairs.
@@ -58,6 +137,77 @@ impl C64Memory {
             .collect();
     }
 
+    /// Returns the count of bus reads and writes; the difference between
+    /// two calls approximates the CPU cycles spent in between.
+    pub const fn bus_accesses(&self) -> u64 {
+        self.bus_accesses
+    }
+
+    /// Starts or stops recording SID writes. Stopping discards the trace.
+    pub fn trace_writes(&mut self, enabled: bool) {
+        self.write_trace = enabled.then(Vec::new);
+    }
+
+    /// Takes over `other`'s ROM images and whether SID writes are traced.
+    pub fn copy_settings_from(&mut self, other: &Self) {
+        self.trace_writes(other.write_trace.is_some());
+        self.kernal_rom.clone_from(&other.kernal_rom);
+        self.basic_rom.clone_from(&other.basic_rom);
+        self.char_rom.clone_from(&other.char_rom);
+    }
+
+    /// Returns and clears the SID writes recorded so far.
+    pub fn take_write_trace(&mut self) -> Vec<WriteEvent> {
+        self.write_trace
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Installs a Kernal ROM image read at $E000-$FFFF. Short images are zero padded.
+    pub fn install_kernal_rom(&mut self, data: &[u8]) {
+        self.kernal_rom = Some(rom_image(data));
+    }
+
+    /// Installs a BASIC ROM image read at $A000-$BFFF. Short images are zero padded.
+    pub fn install_basic_rom(&mut self, data: &[u8]) {
+        self.basic_rom = Some(rom_image(data));
+    }
+
+    /// Installs a character ROM image read at $D000-$DFFF while I/O is banked out.
+    pub fn install_char_rom(&mut self, data: &[u8]) {
+        self.char_rom = Some(rom_image(data));
+    }
+
+    /// Returns the installed ROM byte visible at `addr`, if any, following the
+    /// banking selected by the processor port at $01.
+    fn rom_byte(&self, addr: u16) -> Option<u8> {
+        let offset = |base: u16| usize::from(addr - base);
+        let port = self.ram[1];
+        match addr {
+            0xA000..=0xBFFF => {
+                let basic_in = port & (LORAM | HIRAM) == LORAM | HIRAM;
+                self.basic_rom
+                    .as_ref()
+                    .filter(|_| basic_in)
+                    .map(|rom| rom[offset(0xA000)])
+            }
+            0xD000..=0xDFFF => {
+                let io_off = port & CHAREN == 0 && port & (LORAM | HIRAM) != 0;
+                self.char_rom
+                    .as_ref()
+                    .filter(|_| io_off)
+                    .map(|rom| rom[offset(0xD000)])
+            }
+            0xE000..=0xFFFF => self
+                .kernal_rom
+                .as_ref()
+                .filter(|_| port & HIRAM != 0)
+                .map(|rom| rom[offset(0xE000)]),
+            _ => None,
+        }
+    }
+
     /// Loads binary data into RAM at the specified address.
     pub fn load(&mut self, address: u16, data: &[u8]) {
         let start = address as usize;
@@ -70,17 +220,43 @@ impl C64Memory {
         self.ram[0x0000..0x0200].fill(0);
     }
 
+    /// Reads `len` bytes from `start` through the memory map (wrapping at $FFFF).
+    /// SID register addresses return the chip's `read()` value.
+    pub fn read_range(&mut self, start: u16, len: u16) -> Vec<u8> {
+        (0..len)
+            .map(|offset| self.get_byte(start.wrapping_add(offset)))
+            .collect()
+    }
+
+    /// Writes `data` from `start` through the memory map (wrapping at $FFFF).
+    #[cfg(test)]
+    pub fn write_range(&mut self, start: u16, data: &[u8]) {
+        let mut addr = start;
+        for &byte in data {
+            self.set_byte(addr, byte);
+            addr = addr.wrapping_add(1);
+        }
+    }
+
     /// Replace the chip model for a specific SID (by index).
     pub fn set_chip_model(&mut self, index: usize, chip_model: ChipModel) {
         if let Some(sid_chip) = self.sids.get_mut(index) {
             let base = sid_chip.base_address;
-            *sid_chip = SidChip::new(chip_model, base);
+            let filter_registers = sid_chip.filter_registers;
+            *sid_chip = SidChip {
+                filter_registers,
+                ..SidChip::new(chip_model, base)
+            };
         }
     }
 }
 
 impl Bus for C64Memory {
     fn get_byte(&mut self, addr: u16) -> u8 {
+        self.bus_accesses += 1;
+        if let Some(byte) = self.rom_byte(addr) {
+            return byte;
+        }
         for sid_chip in &mut self.sids {
             if sid_chip.contains(addr) {
                 #[allow(clippy::cast_possible_truncation)]
@@ -91,15 +267,116 @@ impl Bus for C64Memory {
     }
 
     fn set_byte(&mut self, addr: u16, val: u8) {
+        self.bus_accesses += 1;
         for sid_chip in &mut self.sids {
             if sid_chip.contains(addr) {
                 #[allow(clippy::cast_possible_truncation)]
-                sid_chip
-                    .sid
-                    .write((addr - sid_chip.base_address) as u8, val);
+                sid_chip.write((addr - sid_chip.base_address) as u8, val);
+                if let Some(trace) = &mut self.write_trace
+                    && trace.len() < MAX_WRITE_TRACE
+                {
+                    trace.push(WriteEvent {
+                        address: addr,
+                        value: val,
+                        cpu_cycle: self.bus_accesses,
+                    });
+                }
                 return;
             }
         }
         self.ram[addr as usize] = val;
     }
 }
+
+/// Copies a ROM image into a fixed-size buffer, zero padding or truncating it.
+fn rom_image<const N: usize>(data: &[u8]) -> Box<[u8; N]> {
+    let mut rom = Box::new([0; N]);
+    let len = data.len().min(N);
+    rom[..len].copy_from_slice(&data[..len]);
+    rom
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_range_then_read_range_roundtrips() {
+        let mut memory = C64Memory::new(ChipModel::Mos6581);
+        let data: Vec<u8> = (0..=255).collect();
+        memory.write_range(0x1000, &data);
+        assert_eq!(memory.read_range(0x1000, 256), data);
+    }
+
+    #[test]
+    fn read_range_wraps_at_end_of_memory() {
+        let mut memory = C64Memory::new(ChipModel::Mos6581);
+        memory.write_range(0xFFFE, &[1, 2, 3, 4]);
+        assert_eq!(memory.read_range(0xFFFE, 4), [1, 2, 3, 4]);
+        assert_eq!(memory.read_range(0x0000, 2), [3, 4]);
+    }
+
+    #[test]
+    fn read_range_reads_sid_registers() {
+        let mut memory = C64Memory::new(ChipModel::Mos6581);
+        memory.write_range(0xD400, &[0x11; 0x19]);
+        let expected: Vec<u8> = (0..SID_REGISTER_COUNT)
+            .map(|reg| {
+                #[allow(clippy::cast_possible_truncation)]
+                memory.sids[0].sid.read(reg as u8)
+            })
+            .collect();
+        assert_eq!(memory.read_range(0xD400, SID_REGISTER_COUNT), expected);
+    }
+
+    #[test]
+    fn installed_kernal_rom_shadows_ram_reads() {
+        let mut memory = C64Memory::new(ChipModel::Mos6581);
+        let rom: Vec<u8> = (0..ROM_SIZE).map(|i| (i % 251) as u8 + 1).collect();
+        memory.install_kernal_rom(&rom);
+        assert_eq!(memory.get_byte(0xE000), 1);
+        assert_eq!(memory.get_byte(0xFFFF), rom[ROM_SIZE - 1]);
+
+        // Writes land in the RAM underneath
+        memory.set_byte(0xE000, 0x42);
+        assert_eq!(memory.get_byte(0xE000), 1);
+        assert_eq!(memory.ram[0xE000], 0x42);
+    }
+
+    #[test]
+    fn roms_follow_processor_port_banking() {
+        let mut memory = C64Memory::new(ChipModel::Mos6581);
+        memory.install_kernal_rom(&[0xEE; ROM_SIZE]);
+        memory.install_basic_rom(&[0xBB; ROM_SIZE]);
+        memory.ram[0xA000] = 0x11;
+        memory.ram[0xE000] = 0x22;
+        assert_eq!(memory.get_byte(0xA000), 0xBB);
+        assert_eq!(memory.get_byte(0xE000), 0xEE);
+
+        // HIRAM cleared: both ROMs give way to RAM
+        memory.set_byte(0x0001, 0x35);
+        assert_eq!(memory.get_byte(0xA000), 0x11);
+        assert_eq!(memory.get_byte(0xE000), 0x22);
+
+        // LORAM cleared: only BASIC is banked out
+        memory.set_byte(0x0001, 0x36);
+        assert_eq!(memory.get_byte(0xA000), 0x11);
+        assert_eq!(memory.get_byte(0xE000), 0xEE);
+
+        // Code under the Kernal starts with it banked out
+        memory.reset_processor_port(0xE000);
+        assert_eq!(memory.get_byte(0xE000), 0x22);
+        memory.reset_processor_port(0x1000);
+        assert_eq!(memory.get_byte(0xE000), 0xEE);
+    }
+
+    #[test]
+    fn char_rom_only_visible_with_io_banked_out() {
+        let mut memory = C64Memory::new(ChipModel::Mos6581);
+        memory.install_char_rom(&[0xAA; CHAR_ROM_SIZE]);
+        memory.set_byte(0x0001, 0x37);
+        assert_ne!(memory.get_byte(0xD000), 0xAA);
+        memory.set_byte(0x0001, 0x33);
+        assert_eq!(memory.get_byte(0xD000), 0xAA);
+    }
+}
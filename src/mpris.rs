@@ -0,0 +1,370 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Mikael Lund
+
+//! MPRIS2 D-Bus interface for desktop media keys and sound applets.
+//!
+//! A background thread owns the session bus connection. Remote control
+//! requests are forwarded to the TUI over a channel, while the TUI publishes
+//! the current track back through a shared [`TrackStatus`] that is turned
+//! into `PropertiesChanged` signals.
+
+use crate::player::SharedPlayer;
+use dbus::arg::{PropMap, RefArg, Variant};
+use dbus::blocking::Connection;
+use dbus::blocking::stdintf::org_freedesktop_dbus::PropertiesPropertiesChanged;
+use dbus::channel::MatchingReceiver;
+use dbus::message::{MatchRule, SignalArgs};
+use dbus_crossroads::{Crossroads, IfaceBuilder};
+use std::io;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const BUS_NAME: &str = "org.mpris.MediaPlayer2.crabsid";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const ROOT_IFACE: &str = "org.mpris.MediaPlayer2";
+const PLAYER_IFACE: &str = "org.mpris.MediaPlayer2.Player";
+/// How often the bus thread checks for state changes to signal
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Remote control request received over D-Bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MprisEvent {
+    PlayPause,
+    Next,
+    Previous,
+    Stop,
+}
+
+/// Track information published by the TUI for the `Metadata` property.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrackStatus {
+    pub title: String,
+    pub artist: String,
+    /// Song length from the Songlengths database, if known
+    pub length: Option<Duration>,
+    pub current_song: u16,
+    pub total_songs: u16,
+}
+
+impl TrackStatus {
+    const fn can_go_next(&self) -> bool {
+        self.current_song < self.total_songs
+    }
+
+    const fn can_go_previous(&self) -> bool {
+        self.current_song > 1
+    }
+}
+
+/// Player state compared between polls to detect property changes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Snapshot {
+    track: TrackStatus,
+    paused: bool,
+}
+
+/// TUI side of the bridge: receives requests and publishes track status.
+pub struct MprisHandle {
+    events: Receiver<MprisEvent>,
+    status: Arc<Mutex<TrackStatus>>,
+}
+
+impl MprisHandle {
+    /// Returns the next pending request, if any.
+    pub fn try_recv(&self) -> Option<MprisEvent> {
+        self.events.try_recv().ok()
+    }
+
+    /// Publishes the current track for the bus thread to report.
+    pub fn publish(&self, status: TrackStatus) {
+        if let Ok(mut current) = self.status.lock() {
+            *current = status;
+        }
+    }
+}
+
+/// State owned by the D-Bus object, shared with method and property callbacks.
+struct MprisData {
+    events: Sender<MprisEvent>,
+    status: Arc<Mutex<TrackStatus>>,
+    player: SharedPlayer,
+}
+
+impl MprisData {
+    fn send(&self, event: MprisEvent) {
+        let _ = self.events.send(event);
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        take_snapshot(&self.status, &self.player)
+    }
+}
+
+/// Connects to the session bus and spawns the MPRIS service thread.
+pub fn spawn(player: SharedPlayer) -> io::Result<MprisHandle> {
+    let conn = Connection::new_session().map_err(io::Error::other)?;
+    conn.request_name(BUS_NAME, false, true, false)
+        .map_err(io::Error::other)?;
+
+    let (tx, rx) = mpsc::channel();
+    let status = Arc::new(Mutex::new(TrackStatus::default()));
+    let data = MprisData {
+        events: tx,
+        status: Arc::clone(&status),
+        player,
+    };
+
+    thread::Builder::new()
+        .name("mpris".to_string())
+        .spawn(move || {
+            let _ = serve(&conn, data);
+        })?;
+
+    Ok(MprisHandle { events: rx, status })
+}
+
+/// Serves MPRIS requests and emits change signals until the connection fails.
+fn serve(conn: &Connection, data: MprisData) -> Result<(), dbus::Error> {
+    let status = Arc::clone(&data.status);
+    let player = data.player.clone();
+
+    let mut cr = Crossroads::new();
+    let root = cr.register(ROOT_IFACE, |b: &mut IfaceBuilder<MprisData>| {
+        b.method("Raise", (), (), |_, _, _: ()| Ok(()));
+        b.method("Quit", (), (), |_, _, _: ()| Ok(()));
+        b.property("Identity").get(|_, _| Ok("CrabSid".to_string()));
+        b.property("CanQuit").get(|_, _| Ok(false));
+        b.property("CanRaise").get(|_, _| Ok(false));
+        b.property("HasTrackList").get(|_, _| Ok(false));
+        b.property("SupportedUriSchemes")
+            .get(|_, _| Ok(vec!["file".to_string(), "https".to_string()]));
+        b.property("SupportedMimeTypes")
+            .get(|_, _| Ok(Vec::<String>::new()));
+    });
+    let player_iface = cr.register(PLAYER_IFACE, |b: &mut IfaceBuilder<MprisData>| {
+        b.method("PlayPause", (), (), |_, data, _: ()| {
+            data.send(MprisEvent::PlayPause);
+            Ok(())
+        });
+        b.method("Play", (), (), |_, data, _: ()| {
+            if data.snapshot().paused {
+                data.send(MprisEvent::PlayPause);
+            }
+            Ok(())
+        });
+        b.method("Pause", (), (), |_, data, _: ()| {
+            if !data.snapshot().paused {
+                data.send(MprisEvent::PlayPause);
+            }
+            Ok(())
+        });
+        b.method("Next", (), (), |_, data, _: ()| {
+            data.send(MprisEvent::Next);
+            Ok(())
+        });
+        b.method("Previous", (), (), |_, data, _: ()| {
+            data.send(MprisEvent::Previous);
+            Ok(())
+        });
+        b.method("Stop", (), (), |_, data, _: ()| {
+            data.send(MprisEvent::Stop);
+            Ok(())
+        });
+        b.property("PlaybackStatus")
+            .get(|_, data| Ok(playback_status(data.snapshot().paused).to_string()));
+        b.property("Metadata")
+            .get(|_, data| Ok(metadata(&data.snapshot().track)));
+        b.property("CanGoNext")
+            .get(|_, data| Ok(data.snapshot().track.can_go_next()));
+        b.property("CanGoPrevious")
+            .get(|_, data| Ok(data.snapshot().track.can_go_previous()));
+        b.property("CanPlay").get(|_, _| Ok(true));
+        b.property("CanPause").get(|_, _| Ok(true));
+        b.property("CanSeek").get(|_, _| Ok(false));
+        b.property("CanControl").get(|_, _| Ok(true));
+    });
+    cr.insert(OBJECT_PATH, &[root, player_iface], data);
+
+    conn.start_receive(
+        MatchRule::new_method_call(),
+        Box::new(move |msg, conn| {
+            let _ = cr.handle_message(msg, conn);
+            true
+        }),
+    );
+
+    let mut last = None;
+    loop {
+        conn.process(POLL_INTERVAL)?;
+        emit_changes(conn, &mut last, take_snapshot(&status, &player));
+    }
+}
+
+fn take_snapshot(status: &Mutex<TrackStatus>, player: &SharedPlayer) -> Snapshot {
+    Snapshot {
+        track: status.lock().map(|s| s.clone()).unwrap_or_default(),
+        paused: player.lock().map(|p| p.is_paused()).unwrap_or(true),
+    }
+}
+
+const fn playback_status(paused: bool) -> &'static str {
+    if paused { "Paused" } else { "Playing" }
+}
+
+fn variant<T: RefArg + 'static>(value: T) -> Variant<Box<dyn RefArg>> {
+    Variant(Box::new(value))
+}
+
+/// Builds the `Metadata` property (xesam/mpris keys).
+fn metadata(track: &TrackStatus) -> PropMap {
+    let mut map = PropMap::new();
+    let track_id = dbus::Path::from(format!("/org/crabsid/track/{}", track.current_song));
+    map.insert("mpris:trackid".to_string(), variant(track_id));
+    map.insert("xesam:title".to_string(), variant(track.title.clone()));
+    map.insert(
+        "xesam:artist".to_string(),
+        variant(vec![track.artist.clone()]),
+    );
+    if let Some(length) = track.length {
+        let micros = i64::try_from(length.as_micros()).unwrap_or(i64::MAX);
+        map.insert("mpris:length".to_string(), variant(micros));
+    }
+    map
+}
+
+/// Destination for `PropertiesChanged` signals (the session bus, or a mock in tests).
+trait SignalSink {
+    fn properties_changed(&self, changed: PropMap);
+}
+
+impl SignalSink for Connection {
+    fn properties_changed(&self, changed: PropMap) {
+        let signal = PropertiesPropertiesChanged {
+            interface_name: PLAYER_IFACE.to_string(),
+            changed_properties: changed,
+            invalidated_properties: Vec::new(),
+        };
+        let msg = signal.to_emit_message(&dbus::Path::from(OBJECT_PATH));
+        let _ = dbus::channel::Sender::send(self, msg);
+    }
+}
+
+/// Emits a signal for properties that differ from the previous snapshot.
+fn emit_changes(sink: &impl SignalSink, last: &mut Option<Snapshot>, current: Snapshot) {
+    if let Some(prev) = last.as_ref() {
+        let changed = changed_properties(prev, &current);
+        if !changed.is_empty() {
+            sink.properties_changed(changed);
+        }
+    }
+    *last = Some(current);
+}
+
+fn changed_properties(prev: &Snapshot, next: &Snapshot) -> PropMap {
+    let mut changed = PropMap::new();
+    if prev.paused != next.paused {
+        changed.insert(
+            "PlaybackStatus".to_string(),
+            variant(playback_status(next.paused).to_string()),
+        );
+    }
+    if prev.track != next.track {
+        changed.insert("Metadata".to_string(), variant(metadata(&next.track)));
+        changed.insert("CanGoNext".to_string(), variant(next.track.can_go_next()));
+        changed.insert(
+            "CanGoPrevious".to_string(),
+            variant(next.track.can_go_previous()),
+        );
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Records emitted signals instead of talking to a real bus.
+    #[derive(Default)]
+    struct MockBus(RefCell<Vec<PropMap>>);
+
+    impl SignalSink for MockBus {
+        fn properties_changed(&self, changed: PropMap) {
+            self.0.borrow_mut().push(changed);
+        }
+    }
+
+    fn track(song: u16) -> TrackStatus {
+        TrackStatus {
+            title: "Commando".to_string(),
+            artist: "Rob Hubbard".to_string(),
+            length: Some(Duration::from_secs(90)),
+            current_song: song,
+            total_songs: 3,
+        }
+    }
+
+    #[test]
+    fn metadata_reports_length_in_microseconds() {
+        let map = metadata(&track(1));
+        assert_eq!(map["xesam:title"].0.as_str(), Some("Commando"));
+        assert_eq!(map["mpris:length"].0.as_i64(), Some(90_000_000));
+    }
+
+    #[test]
+    fn can_go_next_until_last_song() {
+        assert!(track(1).can_go_next());
+        assert!(!track(1).can_go_previous());
+        assert!(!track(3).can_go_next());
+        assert!(track(3).can_go_previous());
+    }
+
+    #[test]
+    fn first_snapshot_emits_nothing() {
+        let bus = MockBus::default();
+        let mut last = None;
+        emit_changes(&bus, &mut last, Snapshot::default());
+        assert!(bus.0.borrow().is_empty());
+    }
+
+    #[test]
+    fn pause_emits_playback_status() {
+        let bus = MockBus::default();
+        let mut last = Some(Snapshot::default());
+        let paused = Snapshot {
+            paused: true,
+            ..Snapshot::default()
+        };
+        emit_changes(&bus, &mut last, paused.clone());
+        emit_changes(&bus, &mut last, paused);
+
+        let signals = bus.0.borrow();
+        assert_eq!(signals.len(), 1, "unchanged state must not re-emit");
+        assert_eq!(signals[0]["PlaybackStatus"].0.as_str(), Some("Paused"));
+        assert!(!signals[0].contains_key("Metadata"));
+    }
+
+    #[test]
+    fn song_change_emits_metadata() {
+        let bus = MockBus::default();
+        let mut last = Some(Snapshot {
+            track: track(1),
+            paused: false,
+        });
+        emit_changes(
+            &bus,
+            &mut last,
+            Snapshot {
+                track: track(2),
+                paused: false,
+            },
+        );
+
+        let signals = bus.0.borrow();
+        assert_eq!(signals.len(), 1);
+        assert!(signals[0].contains_key("Metadata"));
+        assert!(!signals[0].contains_key("PlaybackStatus"));
+    }
+}
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Mikael Lund
+
+//! Lock file holding the PID of the running instance.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Lock file that is removed again when dropped.
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Writes the current PID to `path`, replacing a stale lock.
+    /// Fails with `AlreadyExists` if the PID in an existing lock is still running.
+    pub fn new(path: &Path) -> io::Result<Self> {
+        if path.exists() {
+            if !Self::is_stale(path) {
+                let pid = Self::holder(path).unwrap_or_default();
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("locked by PID {pid}"),
+                ));
+            }
+            fs::remove_file(path)?;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)?;
+        writeln!(file, "{}", std::process::id())?;
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// True if the lock at `path` is unreadable or names a process that no longer runs.
+    pub fn is_stale(path: &Path) -> bool {
+        Self::holder(path).is_none_or(|pid| !process_running(pid))
+    }
+
+    /// Returns the PID stored in the lock at `path`.
+    pub fn holder(path: &Path) -> Option<u32> {
+        fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Default lock location, `~/.local/state/crabsid/lock` on Linux.
+pub fn default_lock_path() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|d| d.join("crabsid").join("lock"))
+}
+
+/// Checks whether a process with this PID exists.
+#[cfg(target_os = "linux")]
+fn process_running(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+/// Checks whether a process with this PID exists.
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_running(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Liveness can't be checked here, so a lock is only released with `--force`.
+#[cfg(not(unix))]
+const fn process_running(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Command, Stdio};
+
+    #[test]
+    fn stale_lock_of_exited_process_is_replaced() {
+        let dir = std::env::temp_dir().join(format!("crabsid-lock-{}", std::process::id()));
+        let path = dir.join("lock");
+
+        let lock = PidFile::new(&path).expect("create lock");
+        assert_eq!(PidFile::holder(&path), Some(std::process::id()));
+        assert!(!PidFile::is_stale(&path));
+        assert_eq!(
+            PidFile::new(&path).err().map(|e| e.kind()),
+            Some(io::ErrorKind::AlreadyExists)
+        );
+        drop(lock);
+        assert!(!path.exists());
+
+        // A child that has exited leaves a stale lock behind
+        let exe = std::env::current_exe().expect("test binary");
+        let mut child = Command::new(exe)
+            .arg("--list")
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("spawn child");
+        let pid = child.id();
+        child.wait().expect("wait for child");
+        fs::write(&path, format!("{pid}\n")).unwrap();
+        assert!(PidFile::is_stale(&path));
+        drop(PidFile::new(&path).expect("replace stale lock"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,8 +1,9 @@
 // SPDX-License-Identifier: MIT
 // Copyright (c) 2026 Mikael Lund
 
-use crate::memory::C64Memory;
-use crate::sid_file::SidFile;
+use crate::biquad::BiquadFilter;
+use crate::memory::{C64Memory, SID_REGISTER_COUNT, WriteEvent};
+use crate::sid_file::{ClockType, SidFile};
 use mos6502::cpu::CPU;
 use mos6502::instruction::Nmos6502;
 use mos6502::memory::Bus;
@@ -10,6 +11,7 @@ use mos6502::registers::StackPointer;
 use residfp::{clock, ChipModel};
 pub use residfp::SamplingMethod;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use std::{error, fmt};
 const PAL_FRAME_CYCLES: u32 = 19_656;
 const NTSC_FRAME_CYCLES: u32 = 17_045;
@@ -18,12 +20,56 @@ const NTSC_FRAME_CYCLES: u32 = 17_045;
 const SCOPE_BUFFER_SIZE: usize = 1024;
 /// Envelope sampling divisor (sample envelope every N audio samples)
 const ENVELOPE_SAMPLE_DIVISOR: usize = 4;
+/// Samples per `fill_buffer` call when rendering offline
+const RENDER_CHUNK: usize = 1024;
+/// Number of writable SID registers ($00-$18)
+pub const SID_WRITABLE_REGISTERS: usize = 0x19;
+/// Playback speed range for `set_speed_factor`
+const MIN_SPEED_FACTOR: f32 = 0.25;
+const MAX_SPEED_FACTOR: f32 = 4.0;
+/// Number of recent play calls kept for performance statistics
+const FRAME_STATS_LEN: usize = 30;
+/// Filter cutoff (low 3 bits, high 8 bits) and resonance/routing registers
+const FILTER_CUTOFF_LO: u8 = 0x15;
+const FILTER_CUTOFF_HI: u8 = 0x16;
+const FILTER_RES_ROUTING: u8 = 0x17;
+/// Full-scale amplitude of one SID's output
+const FULL_SCALE: i32 = 32_768;
+/// Samples over which `MixMode::SumWithNormalize` tracks the output peak
+const NORMALIZE_WINDOW: usize = 1024;
+/// Default instruction limits for the init routine and for each play call
+pub const DEFAULT_INIT_MAX_STEPS: u32 = 1_000_000;
+const DEFAULT_PLAY_MAX_STEPS: u32 = 100_000;
+/// Length of the fade between tunes started with `crossfade_to`
+const CROSSFADE_SECONDS: f32 = 0.5;
+/// Maximum number of SID chips, counting `--extra-sid` chips beyond the header's three
+const MAX_SIDS: usize = 8;
+/// Voices in each SID chip
+pub const VOICES_PER_SID: usize = 3;
+/// Maximum number of voices across all SIDs
+const MAX_VOICES: usize = MAX_SIDS * VOICES_PER_SID;
+/// Register stride between voices within one SID
+const VOICE_REGISTER_STRIDE: u8 = 7;
+/// Offset of the Attack/Decay register within a voice
+const ATTACK_DECAY: u8 = 5;
+/// Offset of the control register within a voice, and its gate and noise bits
+const CONTROL: u8 = 4;
+const CONTROL_GATE: u8 = 0x01;
+const CONTROL_NOISE: u8 = 0x80;
+/// Share of the CIA timer evidence that must point to NTSC to play a v1 tune at 60Hz
+const MIN_CLOCK_HINT_CONFIDENCE: f32 = 0.5;
+/// Peak of the triangular dither noise: half an i16 step
+const DITHER_AMPLITUDE: f32 = 0.5 / 32768.0;
+/// Oscillator accumulator range; one wrap per output period
+const OSCILLATOR_RANGE: f32 = 16_777_216.0;
+/// Soft mute envelope: instant attack, ~300ms decay, zero sustain, ~300ms release
+const SOFT_MUTE_ADSR: [u8; 2] = [0x08, 0x08];
 
 /// SID music player combining 6502 CPU and SID chip emulation.
 ///
 /// Executes the SID tune's play routine at the correct frame rate while
 /// generating audio samples. Supports PAL/NTSC timing, both SID chip models,
-/// and multi-SID tunes (2-3 SIDs for 6-9 voices).
+/// and multi-SID tunes (2-3 SIDs for 6-9 voices, up to 8 with extra chips).
 pub struct Player {
     /// 6502 CPU with C64 memory map
     cpu: CPU<C64Memory, Nmos6502>,
@@ -45,6 +91,8 @@ pub struct Player {
     frame_cycle_count: u32,
     /// Playback paused state
     paused: bool,
+    /// Song number (1-indexed) last initialized by `load_song`
+    current_song: u16,
     /// Per-voice envelope history for oscilloscope display (3 per SID)
     envelope_history: Vec<Box<[f32; SCOPE_BUFFER_SIZE]>>,
     /// Write position in envelope ring buffers
@@ -61,41 +109,316 @@ pub struct Player {
     playback_error: Option<String>,
     /// Resampling method for SID audio output
     sampling_method: SamplingMethod,
+    /// Mixed output captured while recording
+    recording_buffer: Option<Vec<f32>>,
+    /// Emulation speed relative to real time (changes pitch and tempo)
+    speed_factor: f32,
+    /// Output gain applied after mixing (0.0-1.0)
+    master_volume: f32,
+    /// How multiple SIDs are combined
+    mix_mode: MixMode,
+    /// Recent output peak for `MixMode::SumWithNormalize`
+    mix_peak: PeakWindow,
+    /// Low-pass EQ applied to the mixed output, if enabled
+    global_filter: Option<BiquadFilter>,
+    /// TPDF dither is added to each output sample
+    dithering_enabled: bool,
+    /// Noise generator for dithering
+    dither: DitherState,
+    /// Ring buffer of the most recent play calls
+    frame_stats_ring: [FrameStats; FRAME_STATS_LEN],
+    /// Audio callbacks that arrived too late to keep the device fed (since last clear)
+    underrun_count: u32,
+    /// Start of the previous `fill_buffer` call and the buffer length it produced
+    last_fill: Option<(Instant, usize)>,
+    /// Next write position in `frame_stats_ring`
+    frame_stats_pos: usize,
+    /// Number of valid entries in `frame_stats_ring`
+    frame_stats_count: usize,
+    /// SIDs whose filter is held fully open with no resonance (one per SID)
+    filter_bypassed: Vec<bool>,
+    /// Emulation only advances through `step_frame`; `fill_buffer` outputs silence
+    step_mode: bool,
+    /// Frames advanced with `step_frame`
+    step_count: u64,
+    /// Play calls since the song was initialized
+    frame_counter: u64,
+    /// Songs (1-32) that are CIA timed, as a bit mask
+    cia_songs: u32,
+    /// Smoothed absolute level of the mix before volume and filtering
+    recent_max_amplitude: f32,
+    /// Highest `recent_max_amplitude` since the song was initialized
+    peak_level_since_init: f32,
+    /// A voice has been gated since the song was initialized (soft mutes don't matter)
+    gated_since_init: bool,
+    /// Voices faded out via `set_voice_soft_mute`
+    voice_soft_muted: [bool; MAX_VOICES],
+    /// Tune's own AttDec/SusRel values for soft-muted voices, restored on unmute
+    voice_saved_adsr: [[u8; 2]; MAX_VOICES],
+    /// Hooks run after each successful play call
+    frame_callbacks: Vec<FrameCallback>,
+    /// Chips added after the file's own SIDs (`--extra-sid`), kept across tunes
+    extra_sids: Vec<(u16, ChipModel)>,
+    /// SIDs declared by the current file, which come before the extra ones
+    file_sid_count: usize,
+    /// Instructions the init routine may run before `InitTimeout`
+    init_max_steps: u32,
+    /// Instructions each play call may run before `PlayTimeout`
+    play_max_steps: u32,
+    /// Previous tune still fading out after `crossfade_to`
+    crossfade: Option<Box<CrossfadeState>>,
+    /// CPU registers as the most recent init routine left them
+    init_snapshot: CpuSnapshot,
+}
+
+/// Linear congruential generator for triangular (TPDF) dither noise.
+#[derive(Debug, Clone)]
+struct DitherState {
+    seed: u32,
+}
+
+impl Default for DitherState {
+    fn default() -> Self {
+        Self { seed: 0x1234_5678 }
+    }
+}
+
+impl DitherState {
+    /// Returns a uniform value in 0.0..1.0.
+    #[allow(clippy::cast_precision_loss)]
+    fn next_uniform(&mut self) -> f32 {
+        self.seed = self
+            .seed
+            .wrapping_mul(1_664_525)
+            .wrapping_add(1_013_904_223);
+        (self.seed >> 8) as f32 / (1 << 24) as f32
+    }
+
+    /// Adds zero-mean triangular noise of at most `DITHER_AMPLITUDE` to `sample`.
+    fn apply(&mut self, sample: f32) -> f32 {
+        let noise = self.next_uniform() - self.next_uniform();
+        noise.mul_add(DITHER_AMPLITUDE, sample)
+    }
+}
+
+/// How the outputs of several SID chips are combined into one sample.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MixMode {
+    /// Divide the sum by the number of SIDs
+    #[default]
+    WeightedAverage,
+    /// Sum all SIDs and clip
+    SumWithClip,
+    /// Sum all SIDs, scaled down by the peak of the last `NORMALIZE_WINDOW` samples
+    SumWithNormalize,
+}
+
+/// Peak of the mixed sum over roughly the last `NORMALIZE_WINDOW` samples,
+/// kept as the peaks of the current and the previous block.
+#[derive(Debug, Default)]
+struct PeakWindow {
+    current: i32,
+    previous: i32,
+    count: usize,
+}
+
+impl PeakWindow {
+    /// Records `sum` and scales it so the recent peak fits one SID's full scale.
+    fn normalize(&mut self, sum: i32) -> i32 {
+        self.current = self.current.max(sum.saturating_abs());
+        self.count += 1;
+        if self.count == NORMALIZE_WINDOW {
+            self.previous = std::mem::take(&mut self.current);
+            self.count = 0;
+        }
+        let peak = self.current.max(self.previous).max(FULL_SCALE);
+        // |sum| <= peak, so the result fits in i32
+        (i64::from(sum) * i64::from(FULL_SCALE) / i64::from(peak)) as i32
+    }
+}
+
+/// 6502 register values at one point in time, for debugging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CpuSnapshot {
+    /// Program counter
+    pub pc: u16,
+    /// Accumulator
+    pub a: u8,
+    /// X index register
+    pub x: u8,
+    /// Y index register
+    pub y: u8,
+    /// Stack pointer (offset into page $01)
+    pub sp: u8,
+    /// Processor status flags (NV-BDIZC)
+    pub status: u8,
+}
+
+impl CpuSnapshot {
+    fn of(cpu: &CPU<C64Memory, Nmos6502>) -> Self {
+        let registers = &cpu.registers;
+        Self {
+            pc: registers.program_counter,
+            a: registers.accumulator,
+            x: registers.index_x,
+            y: registers.index_y,
+            sp: registers.stack_pointer.0,
+            status: registers.status.bits(),
+        }
+    }
+}
+
+/// A tune fading out underneath the one that replaced it.
+struct CrossfadeState {
+    /// Player of the previous tune
+    outgoing: Player,
+    /// Share of the new tune in the mix, from 0.0 to 1.0
+    progress: f32,
+    /// Output of `outgoing`, reused between callbacks
+    scratch: Vec<f32>,
+}
+
+/// Instructions the init and play routines may run before they count as hung.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepLimits {
+    /// Limit for the init routine
+    pub init_max_steps: u32,
+    /// Limit for each call of the play routine
+    pub play_max_steps: u32,
+}
+
+impl Default for StepLimits {
+    fn default() -> Self {
+        Self {
+            init_max_steps: DEFAULT_INIT_MAX_STEPS,
+            play_max_steps: DEFAULT_PLAY_MAX_STEPS,
+        }
+    }
 }
 
 /// Errors that can occur while initializing or running SID routines.
 #[derive(Debug, PartialEq, Eq)]
 pub enum PlayerError {
     /// The init routine never returned before the step limit.
-    InitTimeout { steps: u32, address: u16 },
+    InitTimeout {
+        steps: u32,
+        address: u16,
+        elapsed_cycles: u32,
+    },
     /// The play routine never returned before the step limit.
-    PlayTimeout { steps: u32, address: u16 },
+    PlayTimeout {
+        steps: u32,
+        address: u16,
+        elapsed_cycles: u32,
+    },
+    /// The song is CIA timer driven, which isn't emulated.
+    CiaTimingRequired { song: u16 },
 }
 
 impl fmt::Display for PlayerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::InitTimeout { steps, address } => {
+            Self::InitTimeout {
+                steps,
+                address,
+                elapsed_cycles,
+            } => {
                 write!(
                     f,
-                    "SID init routine at ${address:04X} exceeded {steps} steps \
-                    (may require CIA/interrupt emulation)"
+                    "SID init routine at ${address:04X} exceeded {} steps ({}; \
+                    may require CIA/interrupt emulation)",
+                    millions(*steps),
+                    approx_cycles(*elapsed_cycles)
                 )
             }
-            Self::PlayTimeout { steps, address } => {
+            Self::PlayTimeout {
+                steps,
+                address,
+                elapsed_cycles,
+            } => {
                 write!(
                     f,
-                    "SID play routine at ${address:04X} exceeded {steps} steps"
+                    "SID play routine at ${address:04X} exceeded {} steps ({})",
+                    millions(*steps),
+                    approx_cycles(*elapsed_cycles)
                 )
             }
+            Self::CiaTimingRequired { song } => {
+                write!(f, "Song {song} requires CIA timer emulation")
+            }
         }
     }
 }
 
 impl error::Error for PlayerError {}
 
+/// Formats a count in millions with at most one decimal: "1M", "4.2M".
+fn millions(n: u32) -> String {
+    let text = format!("{:.1}", f64::from(n) / 1e6);
+    format!("{}M", text.trim_end_matches(".0"))
+}
+
+/// Describes a cycle count and the time it takes on a ~1 MHz C64.
+fn approx_cycles(cycles: u32) -> String {
+    format!(
+        "approx. {} cycles at 1MHz = {:.1}s",
+        millions(cycles),
+        f64::from(cycles) / 1e6
+    )
+}
+
 type PlayerResult<T> = Result<T, PlayerError>;
 
+/// State passed to frame callbacks after each call of the play routine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Play calls since the song was initialized, starting at 1
+    pub frame_number: u64,
+    /// Envelope levels (0-255), three per SID
+    pub voice_levels: Vec<u8>,
+    /// Write position in the oscilloscope ring buffers
+    pub envelope_write_pos: usize,
+}
+
+/// Hook run after every play call, see `Player::add_frame_callback`.
+pub type FrameCallback = Box<dyn Fn(&FrameInfo) + Send>;
+
+/// CPU work done by one call of the play routine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// 6502 instructions executed
+    pub steps: u32,
+    /// Wall-clock time spent emulating them
+    pub duration: Duration,
+}
+
+/// Summary of recent `FrameStats`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerfSummary {
+    pub min_steps: u32,
+    pub max_steps: u32,
+    pub avg_steps: f64,
+    pub avg_duration: Duration,
+    /// `clock_hz / (avg_steps * sample_rate / buffer_size)`
+    pub emulation_ratio: f64,
+}
+
+/// Envelope levels for all voices, grouped three per SID chip.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoiceLevels(pub Vec<u8>);
+
+impl VoiceLevels {
+    /// Iterates over per-SID slices of three voice levels.
+    pub fn iter_sids(&self) -> impl Iterator<Item = &[u8]> {
+        self.0.chunks(VOICES_PER_SID)
+    }
+
+    /// Returns all voice levels as a flat slice.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+}
+
 impl Player {
     /// Creates a player for the given SID file and song number (1-indexed).
     ///
@@ -108,6 +431,7 @@ impl Player {
     /// - `ResampleFast`: FIR resampling without interpolation
     /// - `Resample`: FIR resampling with interpolation (highest quality)
     /// - `ResampleTwoPass`: Two-stage FIR resampling (high quality, efficient)
+    #[cfg(test)]
     pub fn new(
         sid_file: &SidFile,
         song: u16,
@@ -115,10 +439,56 @@ impl Player {
         chip_override: Option<u16>,
         sampling_method: SamplingMethod,
     ) -> PlayerResult<Self> {
+        Self::with_step_limits(
+            sid_file,
+            song,
+            sample_rate,
+            chip_override,
+            sampling_method,
+            StepLimits::default(),
+        )
+    }
+
+    /// Like `new`, but with custom limits for tunes whose init or play
+    /// routines legitimately run longer than the defaults allow.
+    pub fn with_step_limits(
+        sid_file: &SidFile,
+        song: u16,
+        sample_rate: u32,
+        chip_override: Option<u16>,
+        sampling_method: SamplingMethod,
+        limits: StepLimits,
+    ) -> PlayerResult<Self> {
+        let mut player = Self::without_init(
+            sid_file,
+            song,
+            sample_rate,
+            chip_override,
+            sampling_method,
+            limits,
+        );
+        run_init(
+            &mut player.cpu,
+            sid_file.init_address,
+            limits.init_max_steps,
+        )?;
+        player.init_snapshot = CpuSnapshot::of(&player.cpu);
+        Ok(player)
+    }
+
+    /// Sets up the machine for `sid_file` without running its init routine.
+    fn without_init(
+        sid_file: &SidFile,
+        song: u16,
+        sample_rate: u32,
+        chip_override: Option<u16>,
+        sampling_method: SamplingMethod,
+        limits: StepLimits,
+    ) -> Self {
         let (clock_hz, cycles_per_frame) = timing_from_file(sid_file);
         let chip_models = select_chip_models(sid_file, chip_override);
 
-        let mut cpu = bootstrap_cpu(
+        let cpu = bootstrap_cpu(
             sid_file,
             &chip_models,
             sample_rate,
@@ -127,14 +497,12 @@ impl Player {
             sampling_method,
         );
 
-        run_init(&mut cpu, sid_file.init_address)?;
-
-        let voice_count = chip_models.len() * 3;
+        let voice_count = chip_models.len() * VOICES_PER_SID;
         let envelope_history = (0..voice_count)
             .map(|_| Box::new([0.0; SCOPE_BUFFER_SIZE]))
             .collect();
 
-        Ok(Self {
+        Self {
             cpu,
             play_address: sid_file.play_address,
             init_address: sid_file.init_address,
@@ -145,6 +513,7 @@ impl Player {
             cycle_accumulator: 0.0,
             frame_cycle_count: 0,
             paused: false,
+            current_song: song,
             envelope_history,
             envelope_write_pos: 0,
             envelope_sample_counter: 0,
@@ -153,7 +522,37 @@ impl Player {
             sample_rate,
             playback_error: None,
             sampling_method,
-        })
+            recording_buffer: None,
+            speed_factor: 1.0,
+            master_volume: 1.0,
+            mix_mode: MixMode::default(),
+            mix_peak: PeakWindow::default(),
+            global_filter: None,
+            dithering_enabled: false,
+            dither: DitherState::default(),
+            frame_stats_ring: [FrameStats::default(); FRAME_STATS_LEN],
+            underrun_count: 0,
+            last_fill: None,
+            frame_stats_pos: 0,
+            frame_stats_count: 0,
+            filter_bypassed: vec![false; voice_count / VOICES_PER_SID],
+            step_mode: false,
+            step_count: 0,
+            frame_counter: 0,
+            cia_songs: cia_song_mask(sid_file),
+            recent_max_amplitude: 0.0,
+            peak_level_since_init: 0.0,
+            gated_since_init: false,
+            voice_soft_muted: [false; MAX_VOICES],
+            voice_saved_adsr: [[0; 2]; MAX_VOICES],
+            frame_callbacks: Vec::new(),
+            extra_sids: Vec::new(),
+            file_sid_count: voice_count / VOICES_PER_SID,
+            init_max_steps: limits.init_max_steps,
+            play_max_steps: limits.play_max_steps,
+            crossfade: None,
+            init_snapshot: CpuSnapshot::default(),
+        }
     }
 
     /// Fills the buffer with audio samples, advancing emulation accordingly.
@@ -162,49 +561,286 @@ impl Player {
     /// to maintain cycle-accurate timing between the 1MHz system and audio rate.
     /// On error, auto-pauses and stores error message for TUI to display.
     pub fn fill_buffer(&mut self, buffer: &mut [f32]) {
-        if self.paused || self.playback_error.is_some() {
+        self.detect_underrun(buffer.len());
+        if self.paused || self.step_mode || self.playback_error.is_some() {
             buffer.fill(0.0);
             return;
         }
 
-        let sid_count = self.cpu.memory.sids.len();
-
         for sample in buffer.iter_mut() {
-            self.cycle_accumulator += self.cycles_per_sample;
-            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            let cycles_to_run = self.cycle_accumulator as u32;
-            self.cycle_accumulator -= f64::from(cycles_to_run);
-
-            for _ in 0..cycles_to_run {
-                if self.frame_cycle_count >= self.cycles_per_frame {
-                    self.frame_cycle_count = 0;
-                    if let Err(e) = self.call_play() {
-                        self.playback_error = Some(e.to_string());
-                        self.paused = true;
-                        buffer.fill(0.0);
-                        return;
-                    }
-                }
+            match self.render_sample() {
+                Ok(mixed) if self.dithering_enabled => *sample = self.dither.apply(mixed),
+                Ok(mixed) => *sample = mixed,
+                Err(e) => return self.stop_on_error(&e, buffer),
+            }
+        }
+
+        if let Some(recording) = &mut self.recording_buffer {
+            recording.extend_from_slice(buffer);
+        }
+    }
 
-                // Clock all SIDs
-                for sid_chip in &mut self.cpu.memory.sids {
-                    sid_chip.sid.clock();
+    /// Fills an interleaved buffer of `channels` channels, channel `i` holding voice `i`
+    /// (0-2 first SID, 3-5 second, 6-8 third). Channels beyond the tune's voices stay silent.
+    ///
+    /// `channels` is the device layout fixed at startup, so it may differ from
+    /// `sid_count() * 3` after switching to a tune with another SID count.
+    ///
+    /// The emulator only exposes the mixed chip output, so the channels are an
+    /// approximation: see `split_by_envelope`. The channels of a frame always sum to
+    /// the mono sample produced by `fill_buffer`.
+    pub fn fill_buffer_multichannel(&mut self, buffer: &mut [f32], channels: usize) {
+        let channels = channels.max(1);
+        self.detect_underrun(buffer.len() / channels);
+        if self.paused || self.step_mode || self.playback_error.is_some() {
+            buffer.fill(0.0);
+            return;
+        }
+
+        for frame in buffer.chunks_exact_mut(channels) {
+            match self.render_sample() {
+                Ok(mixed) => self.split_by_envelope(mixed, frame),
+                Err(e) => return self.stop_on_error(&e, buffer),
+            }
+            if let Some(recording) = &mut self.recording_buffer {
+                recording.push(frame.iter().sum());
+            }
+        }
+    }
+
+    /// Fills `buffer` like `fill_buffer` (mono) or `fill_buffer_multichannel`,
+    /// blending in the tune left behind by `crossfade_to` while it fades out.
+    pub fn fill_buffer_crossfade(&mut self, buffer: &mut [f32], channels: usize) {
+        if self.crossfade.is_none() || self.paused || self.step_mode {
+            return self.fill_channels(buffer, channels);
+        }
+        let Some(mut fade) = self.crossfade.take() else {
+            return;
+        };
+        // Record the blend rather than the new tune on its own
+        let recording = self.recording_buffer.take();
+        self.fill_channels(buffer, channels);
+        fade.scratch.resize(buffer.len(), 0.0);
+        fade.outgoing.fill_channels(&mut fade.scratch, channels);
+
+        #[allow(clippy::cast_precision_loss)]
+        let step = 1.0 / (CROSSFADE_SECONDS * self.sample_rate as f32);
+        let channels = channels.max(1);
+        for (frame, old) in buffer
+            .chunks_exact_mut(channels)
+            .zip(fade.scratch.chunks_exact(channels))
+        {
+            let progress = fade.progress.min(1.0);
+            for (sample, &old) in frame.iter_mut().zip(old) {
+                *sample = old.mul_add(1.0 - progress, *sample * progress);
+            }
+            fade.progress += step;
+        }
+
+        self.recording_buffer = recording;
+        if let Some(recording) = &mut self.recording_buffer {
+            if channels > 1 {
+                recording.extend(buffer.chunks_exact(channels).map(|f| f.iter().sum::<f32>()));
+            } else {
+                recording.extend_from_slice(buffer);
+            }
+        }
+        if fade.progress < 1.0 {
+            self.crossfade = Some(fade);
+        }
+    }
+
+    /// Mono `fill_buffer` for one channel, `fill_buffer_multichannel` otherwise.
+    fn fill_channels(&mut self, buffer: &mut [f32], channels: usize) {
+        if channels > 1 {
+            self.fill_buffer_multichannel(buffer, channels);
+        } else {
+            self.fill_buffer(buffer);
+        }
+    }
+
+    /// Writes `mixed` into `frame` split by voice envelope level (equal split if all silent).
+    /// An approximation of per-voice output: a voice gets a share of the mix even when its
+    /// own waveform is silent, and filter or ring modulation effects are not separated.
+    fn split_by_envelope(&self, mixed: f32, frame: &mut [f32]) {
+        // Fixed-size scratch keeps the audio callback free of allocations
+        let mut levels = [0u8; MAX_VOICES];
+        let envelopes = self
+            .cpu
+            .memory
+            .sids
+            .iter()
+            .flat_map(|s| s.sid.read_state().envelope_counter);
+        for (slot, level) in levels.iter_mut().zip(envelopes) {
+            *slot = level;
+        }
+        let voice_count = self.total_voice_count().min(frame.len()).max(1);
+        let levels = &levels[..voice_count];
+        let total: f32 = levels.iter().map(|&l| f32::from(l)).sum();
+        frame.fill(0.0);
+        for (out, &level) in frame.iter_mut().zip(levels) {
+            #[allow(clippy::cast_precision_loss)]
+            let share = if total > 0.0 {
+                f32::from(level) / total
+            } else {
+                1.0 / voice_count as f32
+            };
+            *out = mixed * share;
+        }
+    }
+
+    /// Advances emulation by exactly one play call, writing the samples rendered up to
+    /// and including it into `buffer` (silence-padded; samples past its end are dropped).
+    pub fn step_frame(&mut self, buffer: &mut [f32]) -> PlayerResult<()> {
+        let mut written = 0;
+        loop {
+            let cycles_before = self.frame_cycle_count;
+            let sample = self.render_sample()?;
+            if let Some(slot) = buffer.get_mut(written) {
+                *slot = sample;
+                written += 1;
+            }
+            // The cycle count wraps when the play routine runs
+            if self.frame_cycle_count < cycles_before {
+                break;
+            }
+        }
+        buffer[written..].fill(0.0);
+        self.step_count += 1;
+        Ok(())
+    }
+
+    /// Enables or disables step-through mode (see `step_frame`).
+    pub const fn set_step_mode(&mut self, enabled: bool) {
+        self.step_mode = enabled;
+    }
+
+    /// Returns whether emulation only advances through `step_frame`.
+    pub const fn is_step_mode(&self) -> bool {
+        self.step_mode
+    }
+
+    /// Returns the number of frames advanced with `step_frame`.
+    pub const fn step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// Returns the number of play calls since the current song was initialized.
+    pub const fn position_as_frame_count(&self) -> u64 {
+        self.frame_counter
+    }
+
+    /// Restarts the current song and runs `target` play calls without rendering audio.
+    ///
+    /// The SID chips are not clocked while seeking, so this is deterministic but slow
+    /// for large targets (one full play routine per frame).
+    pub fn seek_to_frame(&mut self, target: u64) -> PlayerResult<()> {
+        self.load_song(self.current_song)?;
+        for _ in 0..target {
+            self.call_play()?;
+        }
+        Ok(())
+    }
+
+    /// Records a play routine failure, pausing playback and silencing the buffer.
+    fn stop_on_error(&mut self, error: &PlayerError, buffer: &mut [f32]) {
+        self.playback_error = Some(error.to_string());
+        self.paused = true;
+        buffer.fill(0.0);
+    }
+
+    /// Emulates one audio sample's worth of cycles and returns the mixed output.
+    fn render_sample(&mut self) -> PlayerResult<f32> {
+        self.cycle_accumulator += self.cycles_per_sample * f64::from(self.speed_factor);
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let cycles_to_run = self.cycle_accumulator as u32;
+        self.cycle_accumulator -= f64::from(cycles_to_run);
+
+        for _ in 0..cycles_to_run {
+            if self.frame_cycle_count >= self.cycles_per_frame {
+                self.frame_cycle_count = 0;
+                self.call_play()?;
+                self.gated_since_init = self.gated_since_init || self.any_voice_gated();
+            }
+
+            // Clock all SIDs
+            for (i, sid_chip) in self.cpu.memory.sids.iter_mut().enumerate() {
+                sid_chip.sid.clock();
+                if self.filter_bypassed.get(i).copied().unwrap_or(false) {
+                    force_filter_open(&mut sid_chip.sid);
                 }
-                self.frame_cycle_count += 1;
             }
+            self.frame_cycle_count += 1;
+        }
 
-            // Mix all SID outputs
-            let sum: i32 = self
-                .cpu
-                .memory
-                .sids
-                .iter()
-                .map(|s| i32::from(s.sid.output()))
-                .sum();
-            *sample = mix_sample(sum, sid_count);
+        // Mix all SID outputs
+        let sid_count = self.cpu.memory.sids.len();
+        let sum: i32 = self
+            .cpu
+            .memory
+            .sids
+            .iter()
+            .map(|s| i32::from(s.sid.output()))
+            .sum();
+        self.capture_envelope_history();
+        let sum = match self.mix_mode {
+            MixMode::SumWithNormalize => self.mix_peak.normalize(sum),
+            MixMode::WeightedAverage | MixMode::SumWithClip => sum,
+        };
+        let mixed = mix_sample_with_mode(sum, sid_count, self.mix_mode);
+        self.recent_max_amplitude = self.recent_max_amplitude.mul_add(0.9, mixed.abs() * 0.1);
+        self.peak_level_since_init = self.peak_level_since_init.max(self.recent_max_amplitude);
+        let mut sample = mixed * self.master_volume;
+        if let Some(filter) = &mut self.global_filter {
+            sample = filter.process(sample);
+        }
+        Ok(sample)
+    }
 
-            self.capture_envelope_history();
+    /// True if the song has produced nothing since it was initialized: no
+    /// voice was gated and the mix stayed below `threshold` (0.0-1.0).
+    ///
+    /// Rests after the song got going don't count, and neither do master
+    /// volume, soft mutes or solo, so only tunes that never play qualify.
+    pub fn never_sounded(&self, threshold: f32) -> bool {
+        !self.gated_since_init && self.peak_level_since_init < threshold
+    }
+
+    /// True if the tune has the gate bit set on any voice.
+    fn any_voice_gated(&self) -> bool {
+        self.cpu.memory.sids.iter().any(|chip| {
+            let registers = chip.sid.read_state().sid_register;
+            (0..VOICES_PER_SID).any(|voice| {
+                registers[usize::from(voice_register(voice, CONTROL))] & CONTROL_GATE != 0
+            })
+        })
+    }
+
+    /// Counts an underrun when the previous buffer ran dry before this call.
+    ///
+    /// The device asks for the next buffer as the previous one starts playing, so
+    /// a gap of more than two buffer durations means it starved in between.
+    fn detect_underrun(&mut self, len: usize) {
+        let now = Instant::now();
+        if let Some((previous, previous_len)) = self.last_fill {
+            #[allow(clippy::cast_precision_loss)]
+            let budget =
+                Duration::from_secs_f64(2.0 * previous_len as f64 / f64::from(self.sample_rate));
+            if now.duration_since(previous) > budget {
+                self.underrun_count += 1;
+            }
         }
+        self.last_fill = Some((now, len));
+    }
+
+    /// Returns the number of underruns since the last `clear_underrun_count`.
+    pub const fn underrun_count(&self) -> u32 {
+        self.underrun_count
+    }
+
+    /// Resets the underrun counter, e.g. when a new tune starts.
+    pub const fn clear_underrun_count(&mut self) {
+        self.underrun_count = 0;
     }
 
     /// Captures envelope history at reduced rate for oscilloscope display.
@@ -215,15 +851,16 @@ impl Player {
         }
         self.envelope_sample_counter = 0;
 
-        let mut voice_idx = 0;
-        for sid_chip in &self.cpu.memory.sids {
+        let voices_per_sid = self.voices_per_sid();
+        for (sid_idx, sid_chip) in self.cpu.memory.sids.iter().enumerate() {
             let state = sid_chip.sid.read_state();
-            for &env in &state.envelope_counter {
-                if voice_idx < self.envelope_history.len() {
-                    self.envelope_history[voice_idx][self.envelope_write_pos] =
-                        f32::from(env) / 255.0;
+            for (voice, &env) in state.envelope_counter.iter().enumerate() {
+                if let Some(history) = self
+                    .envelope_history
+                    .get_mut(sid_idx * voices_per_sid + voice)
+                {
+                    history[self.envelope_write_pos] = f32::from(env) / 255.0;
                 }
-                voice_idx += 1;
             }
         }
         self.envelope_write_pos = (self.envelope_write_pos + 1) % SCOPE_BUFFER_SIZE;
@@ -257,6 +894,202 @@ impl Player {
         self.paused
     }
 
+    /// Starts capturing mixed output samples (paused time is not recorded).
+    pub fn start_recording(&mut self) {
+        self.recording_buffer = Some(Vec::new());
+    }
+
+    /// Stops recording and returns the captured samples.
+    pub const fn stop_recording(&mut self) -> Option<Vec<f32>> {
+        self.recording_buffer.take()
+    }
+
+    /// Returns whether output is being recorded.
+    pub const fn is_recording(&self) -> bool {
+        self.recording_buffer.is_some()
+    }
+
+    /// Returns the length of the recording so far.
+    pub fn recording_duration(&self) -> Duration {
+        let samples = self.recording_buffer.as_ref().map_or(0, Vec::len);
+        #[allow(clippy::cast_precision_loss)]
+        Duration::from_secs_f64(samples as f64 / f64::from(self.sample_rate))
+    }
+
+    /// Switches the resampling method on all SID chips.
+    pub fn set_sampling_method(&mut self, method: SamplingMethod) {
+        self.sampling_method = method;
+        for sid_chip in &mut self.cpu.memory.sids {
+            sid_chip
+                .sid
+                .set_sampling_parameters(method, self.clock_hz, self.sample_rate)
+                .unwrap();
+        }
+    }
+
+    /// Returns the current resampling method.
+    pub const fn sampling_method(&self) -> SamplingMethod {
+        self.sampling_method
+    }
+
+    /// Sets the playback speed, clamped to 0.25-4.0. Like a tape speed change,
+    /// this shifts pitch along with tempo.
+    pub fn set_speed_factor(&mut self, factor: f32) {
+        self.speed_factor = factor.clamp(MIN_SPEED_FACTOR, MAX_SPEED_FACTOR);
+    }
+
+    /// Returns the playback speed relative to real time.
+    pub const fn speed_factor(&self) -> f32 {
+        self.speed_factor
+    }
+
+    /// Sets the output gain, clamped to 0.0-1.0.
+    pub fn set_master_volume(&mut self, volume: f32) {
+        self.master_volume = volume.clamp(0.0, 1.0);
+    }
+
+    /// Sets how the outputs of multiple SIDs are combined.
+    pub fn set_mix_mode(&mut self, mode: MixMode) {
+        self.mix_mode = mode;
+        self.mix_peak = PeakWindow::default();
+    }
+
+    /// Returns how the outputs of multiple SIDs are combined.
+    pub const fn mix_mode(&self) -> MixMode {
+        self.mix_mode
+    }
+
+    /// Enables the output low-pass filter, or retunes it if already enabled.
+    /// `cutoff_pct` is a fraction of the Nyquist frequency and `resonance_pct` 0.0-1.0.
+    pub fn apply_global_filter(&mut self, cutoff_pct: f32, resonance_pct: f32) {
+        match &mut self.global_filter {
+            Some(filter) => filter.set_low_pass(cutoff_pct, resonance_pct, self.sample_rate),
+            None => {
+                self.global_filter = Some(BiquadFilter::low_pass(
+                    cutoff_pct,
+                    resonance_pct,
+                    self.sample_rate,
+                ));
+            }
+        }
+    }
+
+    /// Adds triangular dither to the output, masking i16 quantization at low volumes.
+    pub fn apply_dithering(&mut self, enabled: bool) {
+        self.dithering_enabled = enabled;
+    }
+
+    /// Installs a Kernal ROM image for tunes that read $E000-$FFFF.
+    /// Takes effect for reads from now on; reload the song to rerun init with it.
+    pub fn install_kernal_rom(&mut self, data: &[u8]) {
+        self.cpu.memory.install_kernal_rom(data);
+    }
+
+    /// Installs a BASIC ROM image for tunes that read $A000-$BFFF (see `install_kernal_rom`).
+    pub fn install_basic_rom(&mut self, data: &[u8]) {
+        self.cpu.memory.install_basic_rom(data);
+    }
+
+    /// Installs a character ROM image, read at $D000-$DFFF while I/O is banked out
+    /// (see `install_kernal_rom`).
+    pub fn install_char_rom(&mut self, data: &[u8]) {
+        self.cpu.memory.install_char_rom(data);
+    }
+
+    /// Starts recording SID register writes, collected with `take_write_trace`.
+    pub fn enable_write_trace(&mut self) {
+        self.cpu.memory.trace_writes(true);
+    }
+
+    /// Stops recording SID register writes and drops any not yet taken.
+    pub fn disable_write_trace(&mut self) {
+        self.cpu.memory.trace_writes(false);
+    }
+
+    /// Returns the SID writes recorded since the last call.
+    pub fn take_write_trace(&mut self) -> Vec<WriteEvent> {
+        self.cpu.memory.take_write_trace()
+    }
+
+    /// Removes the output low-pass filter.
+    pub fn disable_global_filter(&mut self) {
+        self.global_filter = None;
+    }
+
+    /// Returns the audio output sample rate in Hz.
+    pub const fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Renders `secs` seconds of audio without an output device.
+    /// Returns the number of samples rendered.
+    pub fn render_seconds(&mut self, secs: u32) -> usize {
+        let total = secs as usize * self.sample_rate as usize;
+        let mut buffer = vec![0.0f32; RENDER_CHUNK];
+        let mut rendered = 0;
+        while rendered < total {
+            let len = RENDER_CHUNK.min(total - rendered);
+            self.fill_buffer(&mut buffer[..len]);
+            rendered += len;
+        }
+        rendered
+    }
+
+    /// Returns the current 6502 register values.
+    pub fn snapshot_cpu_registers(&self) -> CpuSnapshot {
+        CpuSnapshot::of(&self.cpu)
+    }
+
+    /// Returns the 6502 registers as the last init routine left them.
+    pub const fn init_cpu_snapshot(&self) -> CpuSnapshot {
+        self.init_snapshot
+    }
+
+    /// Returns registers $00-$18 of each SID chip.
+    pub fn register_snapshot(&self) -> Vec<[u8; SID_WRITABLE_REGISTERS]> {
+        self.cpu
+            .memory
+            .sids
+            .iter()
+            .map(|s| {
+                let mut regs = [0; SID_WRITABLE_REGISTERS];
+                regs.copy_from_slice(&s.sid.read_state().sid_register[..SID_WRITABLE_REGISTERS]);
+                regs
+            })
+            .collect()
+    }
+
+    /// Returns the oscillator frequency in Hz of every voice, or `None` when
+    /// the gate is off or the voice plays noise.
+    pub fn voice_frequencies(&self) -> Vec<Option<f32>> {
+        self.register_snapshot()
+            .iter()
+            .flat_map(|regs| {
+                (0..VOICES_PER_SID).map(|voice| voice_frequency(regs, voice, self.clock_hz))
+            })
+            .collect()
+    }
+
+    /// Returns the base address of each SID chip.
+    pub fn sid_addresses(&self) -> Vec<u16> {
+        self.cpu
+            .memory
+            .sids
+            .iter()
+            .map(|s| s.base_address)
+            .collect()
+    }
+
+    /// Returns the address where the tune data is loaded.
+    pub const fn load_address(&self) -> u16 {
+        self.load_address
+    }
+
+    /// Reads emulated memory for inspection (see `C64Memory::read_range`).
+    pub fn read_memory(&mut self, start: u16, len: u16) -> Vec<u8> {
+        self.cpu.memory.read_range(start, len)
+    }
+
     /// Takes and clears any pending playback error.
     pub fn take_error(&mut self) -> Option<String> {
         self.playback_error.take()
@@ -264,24 +1097,102 @@ impl Player {
 
     /// Loads a completely new SID file, replacing the current tune.
     pub fn load_sid_file(&mut self, sid_file: &SidFile, song: u16) -> PlayerResult<()> {
-        let is_pal = sid_file.is_pal();
-        self.clock_hz = if is_pal { clock::PAL } else { clock::NTSC };
-        self.cycles_per_frame = if is_pal {
-            PAL_FRAME_CYCLES
-        } else {
-            NTSC_FRAME_CYCLES
-        };
+        (self.clock_hz, self.cycles_per_frame) = timing_from_file(sid_file);
         self.cycles_per_sample = f64::from(self.clock_hz) / f64::from(self.sample_rate);
 
         self.play_address = sid_file.play_address;
+        self.cia_songs = cia_song_mask(sid_file);
         self.init_address = sid_file.init_address;
         self.load_address = sid_file.load_address;
         self.sid_data = sid_file.data.clone();
 
-        // Configure SIDs from file (may be 1, 2, or 3 chips)
-        self.chip_models = select_chip_models(sid_file, None);
-        let sid_configs = build_sid_configs(sid_file, &self.chip_models);
-        self.cpu.memory.configure_sids(&sid_configs);
+        // Configure SIDs from file (may be 1, 2, or 3 chips) plus any extra ones
+        let chip_models = select_chip_models(sid_file, None);
+        let file_configs = build_sid_configs(sid_file, &chip_models);
+        self.file_sid_count = file_configs.len();
+        self.configure_sids(&with_extra_sids(file_configs, &self.extra_sids));
+
+        self.load_song(song)?;
+        Ok(())
+    }
+
+    /// Switches to `song` of `sid_file` like `load_sid_file`, but fades the
+    /// current tune out over half a second instead of cutting it off.
+    ///
+    /// The new tune gets a fresh emulator with this player's settings, so the
+    /// old one keeps running untouched until the fade completes.
+    pub fn crossfade_to(&mut self, sid_file: &SidFile, song: u16) -> PlayerResult<()> {
+        let limits = StepLimits {
+            init_max_steps: self.init_max_steps,
+            play_max_steps: self.play_max_steps,
+        };
+        let mut next = Self::without_init(
+            sid_file,
+            song,
+            self.sample_rate,
+            None,
+            self.sampling_method,
+            limits,
+        );
+        next.take_settings_from(self);
+        // The same path as a direct load, so ROMs and extra SIDs apply to init
+        next.load_sid_file(sid_file, song)?;
+
+        // Session state stays with the player the rest of the app talks to
+        std::mem::swap(self, &mut next);
+        self.recording_buffer = next.recording_buffer.take();
+        self.frame_callbacks = std::mem::take(&mut next.frame_callbacks);
+        self.last_fill = next.last_fill;
+        self.paused = next.paused;
+        self.step_mode = next.step_mode;
+        next.crossfade = None;
+        self.crossfade = Some(Box::new(CrossfadeState {
+            outgoing: next,
+            progress: 0.0,
+            scratch: Vec::new(),
+        }));
+        Ok(())
+    }
+
+    /// Copies everything `load_sid_file` keeps across tunes from `other`.
+    fn take_settings_from(&mut self, other: &Self) {
+        self.cpu.memory.copy_settings_from(&other.cpu.memory);
+        self.extra_sids.clone_from(&other.extra_sids);
+        self.speed_factor = other.speed_factor;
+        self.master_volume = other.master_volume;
+        self.mix_mode = other.mix_mode;
+        self.global_filter.clone_from(&other.global_filter);
+        self.dithering_enabled = other.dithering_enabled;
+        self.voice_soft_muted = other.voice_soft_muted;
+        self.filter_bypassed.clone_from(&other.filter_bypassed);
+    }
+
+    /// Adds SID chips beyond those in the file header, for unofficial 4SID/8SID tunes.
+    /// Replaces earlier extra chips and reruns init for the current song. Each
+    /// tune keeps at most `MAX_SIDS` in total, skipping extras at an address
+    /// the file already uses.
+    pub fn set_extra_sids(&mut self, extra_sids: Vec<(u16, ChipModel)>) -> PlayerResult<()> {
+        let file_configs: Vec<_> = self
+            .cpu
+            .memory
+            .sids
+            .iter()
+            .zip(&self.chip_models)
+            .take(self.file_sid_count)
+            .map(|(chip, &model)| (chip.base_address, model))
+            .collect();
+        self.extra_sids = extra_sids;
+        self.configure_sids(&with_extra_sids(file_configs, &self.extra_sids));
+        self.load_song(self.current_song)
+    }
+
+    /// Replaces the SID chips, sizing the per-chip and per-voice state to match.
+    /// Chips past `MAX_SIDS` are ignored.
+    fn configure_sids(&mut self, sid_configs: &[(u16, ChipModel)]) {
+        let sid_configs = &sid_configs[..sid_configs.len().min(MAX_SIDS)];
+        self.chip_models = sid_configs.iter().map(|&(_, model)| model).collect();
+        self.filter_bypassed.resize(self.chip_models.len(), false);
+        self.cpu.memory.configure_sids(sid_configs);
 
         // Set sampling parameters for all SIDs
         for sid_chip in &mut self.cpu.memory.sids {
@@ -292,13 +1203,10 @@ impl Player {
         }
 
         // Resize envelope history for new voice count
-        let voice_count = self.chip_models.len() * 3;
+        let voice_count = self.total_voice_count();
         self.envelope_history = (0..voice_count)
             .map(|_| Box::new([0.0; SCOPE_BUFFER_SIZE]))
             .collect();
-
-        self.load_song(song)?;
-        Ok(())
     }
 
     /// Reinitialize for a different song number (1-indexed).
@@ -309,6 +1217,7 @@ impl Player {
 
         // Reload the SID data to reset any modified memory
         self.cpu.memory.load(self.load_address, &self.sid_data);
+        self.cpu.memory.reset_processor_port(self.init_address);
 
         // Reset all SID chips
         for sid_chip in &mut self.cpu.memory.sids {
@@ -331,13 +1240,19 @@ impl Player {
         self.cpu.registers.program_counter = self.init_address;
 
         // Run init routine
-        run_init(&mut self.cpu, self.init_address)?;
+        run_init(&mut self.cpu, self.init_address, self.init_max_steps)?;
+        self.init_snapshot = CpuSnapshot::of(&self.cpu);
 
         // Reset playback state
         self.cycle_accumulator = 0.0;
         self.frame_cycle_count = 0;
+        self.frame_counter = 0;
+        self.recent_max_amplitude = 0.0;
+        self.peak_level_since_init = 0.0;
+        self.gated_since_init = false;
         self.paused = false;
         self.playback_error = None;
+        self.current_song = song;
         Ok(())
     }
 
@@ -346,7 +1261,7 @@ impl Player {
     /// Unlike hardware where only ENV3 ($D41C) is readable, emulation
     /// gives us direct access to all voice envelopes via internal state.
     pub fn voice_levels(&self) -> Vec<u8> {
-        let voice_count = self.cpu.memory.sids.len() * 3;
+        let voice_count = self.cpu.memory.sids.len() * self.voices_per_sid();
         if self.paused {
             return vec![0; voice_count];
         }
@@ -358,16 +1273,60 @@ impl Player {
             .collect()
     }
 
+    /// Returns envelope levels grouped per SID chip.
+    pub fn voice_levels_grouped(&self) -> VoiceLevels {
+        VoiceLevels(self.voice_levels())
+    }
+
     /// Returns the chip models for all SIDs.
     pub fn chip_models(&self) -> &[ChipModel] {
         &self.chip_models
     }
 
+    /// Bypasses the filter of a SID by holding cutoff at maximum ($7FF) and
+    /// resonance at zero after every clock, whatever the tune writes.
+    /// Turning the bypass off restores the filter settings the tune last wrote.
+    pub fn set_filter_override(&mut self, sid_index: usize, enabled: bool) {
+        let Some(flag) = self.filter_bypassed.get_mut(sid_index) else {
+            return;
+        };
+        let was_bypassed = std::mem::replace(flag, enabled);
+        if was_bypassed && !enabled {
+            self.cpu.memory.sids[sid_index].restore_filter_registers();
+        }
+    }
+
+    /// Returns true if the filter of the given SID is bypassed.
+    pub fn is_filter_bypassed(&self, sid_index: usize) -> bool {
+        self.filter_bypassed
+            .get(sid_index)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Returns "6581" or "8580" for each emulated SID.
+    pub fn chip_model_descriptions(&self) -> Vec<&'static str> {
+        self.chip_models
+            .iter()
+            .map(|&m| chip_model_name(m))
+            .collect()
+    }
+
     /// Returns the number of SID chips.
     pub fn sid_count(&self) -> usize {
         self.chip_models.len()
     }
 
+    /// Returns the number of voices in each SID chip.
+    pub const fn voices_per_sid(&self) -> usize {
+        VOICES_PER_SID
+    }
+
+    /// Returns the number of voices across all SID chips.
+    pub fn total_voice_count(&self) -> usize {
+        self.sid_count() * self.voices_per_sid()
+    }
+
     /// Cycles the chip model for the specified SID (or first if index is None).
     /// Returns the new model for that SID.
     pub fn switch_chip_model(&mut self, sid_index: Option<usize>) -> ChipModel {
@@ -397,7 +1356,10 @@ impl Player {
             .unwrap();
 
         // Restore writable registers (0x00-0x18) to maintain playback
-        for (reg, &val) in state.sid_register[..0x19].iter().enumerate() {
+        for (reg, &val) in state.sid_register[..SID_WRITABLE_REGISTERS]
+            .iter()
+            .enumerate()
+        {
             #[allow(clippy::cast_possible_truncation)]
             self.cpu.memory.sids[idx].sid.write(reg as u8, val);
         }
@@ -405,6 +1367,76 @@ impl Player {
         new_model
     }
 
+    /// Sets the chip model for a SID, switching only if it differs.
+    pub fn set_chip_model(&mut self, sid_index: usize, model: ChipModel) {
+        if self.chip_models.get(sid_index).is_some_and(|&m| m != model) {
+            self.switch_chip_model(Some(sid_index));
+        }
+    }
+
+    /// Fades a voice out by letting its envelope decay to zero sustain, or restores it.
+    ///
+    /// Unlike zeroing registers, the oscillator and gate keep running, so the voice
+    /// fades musically and resumes cleanly. While muted, ADSR values written by the
+    /// play routine are captured and restored on unmute. Voices are numbered across
+    /// all SIDs (0-2 first SID, 3-5 second, 6-8 third).
+    pub fn set_voice_soft_mute(&mut self, voice: usize, muted: bool) {
+        if voice >= self.total_voice_count() || self.voice_soft_muted[voice] == muted {
+            return;
+        }
+        self.voice_soft_muted[voice] = muted;
+        if muted {
+            self.voice_saved_adsr[voice] = self.voice_adsr(voice);
+            self.write_voice_adsr(voice, SOFT_MUTE_ADSR);
+        } else {
+            self.write_voice_adsr(voice, self.voice_saved_adsr[voice]);
+        }
+    }
+
+    /// Soft-mutes every voice except `solo`, or unmutes all voices for `None`.
+    pub fn set_solo_voice(&mut self, solo: Option<usize>) {
+        for voice in 0..self.total_voice_count() {
+            self.set_voice_soft_mute(voice, solo.is_some_and(|s| s != voice));
+        }
+    }
+
+    /// Returns true if the voice is soft-muted.
+    #[cfg(test)]
+    pub fn is_voice_soft_muted(&self, voice: usize) -> bool {
+        self.voice_soft_muted.get(voice).copied().unwrap_or(false)
+    }
+
+    /// Re-applies the fade envelope after the play routine, saving any new tune values.
+    fn enforce_soft_mutes(&mut self) {
+        for voice in 0..MAX_VOICES.min(self.total_voice_count()) {
+            if !self.voice_soft_muted[voice] {
+                continue;
+            }
+            let adsr = self.voice_adsr(voice);
+            if adsr != SOFT_MUTE_ADSR {
+                self.voice_saved_adsr[voice] = adsr;
+                self.write_voice_adsr(voice, SOFT_MUTE_ADSR);
+            }
+        }
+    }
+
+    /// Reads a voice's AttDec/SusRel registers from the SID state.
+    fn voice_adsr(&self, voice: usize) -> [u8; 2] {
+        let regs = self.cpu.memory.sids[voice / VOICES_PER_SID]
+            .sid
+            .read_state()
+            .sid_register;
+        let base = usize::from(voice_register(voice, ATTACK_DECAY));
+        [regs[base], regs[base + 1]]
+    }
+
+    fn write_voice_adsr(&mut self, voice: usize, adsr: [u8; 2]) {
+        let reg = voice_register(voice, ATTACK_DECAY);
+        let sid = &mut self.cpu.memory.sids[voice / VOICES_PER_SID].sid;
+        sid.write(reg, adsr[0]);
+        sid.write(reg + 1, adsr[1]);
+    }
+
     /// Toggles between standard and EKV transistor model filter.
     ///
     /// The EKV filter provides more accurate 6581 emulation using physics-based
@@ -419,10 +1451,54 @@ impl Player {
         self.cpu.memory.sids[idx].sid.toggle_ekv_filter()
     }
 
-    fn call_play(&mut self) -> PlayerResult<()> {
+    /// Runs the play routine once and returns the number of 6502 steps it took.
+    #[cfg(test)]
+    pub fn measure_frame_cpu_cycles(&mut self) -> u32 {
+        match self.call_play() {
+            Ok(steps) => steps,
+            Err(e) => {
+                self.playback_error = Some(e.to_string());
+                0
+            }
+        }
+    }
+
+    /// Summarizes the recorded play calls, or None before the first frame.
+    /// `buffer_size` is the number of samples per audio callback.
+    pub fn perf_summary(&self, buffer_size: usize) -> Option<PerfSummary> {
+        let stats = &self.frame_stats_ring[..self.frame_stats_count];
+        let count = u32::try_from(stats.len()).ok().filter(|&n| n > 0)?;
+        let total_steps: u64 = stats.iter().map(|s| u64::from(s.steps)).sum();
+        #[allow(clippy::cast_precision_loss)]
+        let avg_steps = total_steps as f64 / f64::from(count);
+        #[allow(clippy::cast_precision_loss)]
+        let callbacks_per_sec = f64::from(self.sample_rate) / buffer_size as f64;
+        Some(PerfSummary {
+            min_steps: stats.iter().map(|s| s.steps).min().unwrap_or(0),
+            max_steps: stats.iter().map(|s| s.steps).max().unwrap_or(0),
+            avg_steps,
+            avg_duration: stats.iter().map(|s| s.duration).sum::<Duration>() / count,
+            emulation_ratio: f64::from(self.clock_hz) / (avg_steps * callbacks_per_sec),
+        })
+    }
+
+    fn record_frame_stats(&mut self, stats: FrameStats) {
+        self.frame_stats_ring[self.frame_stats_pos] = stats;
+        self.frame_stats_pos = (self.frame_stats_pos + 1) % FRAME_STATS_LEN;
+        self.frame_stats_count = (self.frame_stats_count + 1).min(FRAME_STATS_LEN);
+    }
+
+    fn call_play(&mut self) -> PlayerResult<u32> {
+        let song = self.current_song;
+        if (1..=32).contains(&song) && self.cia_songs & (1 << (song - 1)) != 0 {
+            return Err(PlayerError::CiaTimingRequired { song });
+        }
+        self.frame_counter += 1;
+
         // play_address == 0 means the tune uses IRQ-driven playback
         if self.play_address == 0 {
-            return Ok(());
+            self.notify_frame_callbacks();
+            return Ok(0);
         }
 
         // Reset stack for each call to handle tunes that don't balance the stack
@@ -431,23 +1507,91 @@ impl Player {
         self.cpu.registers.stack_pointer = StackPointer(0xFD);
         self.cpu.registers.program_counter = self.play_address;
 
-        run_play(&mut self.cpu, self.play_address)?;
-        Ok(())
+        let started = Instant::now();
+        let steps = run_play(&mut self.cpu, self.play_address, self.play_max_steps)?;
+        self.record_frame_stats(FrameStats {
+            steps,
+            duration: started.elapsed(),
+        });
+        self.enforce_soft_mutes();
+        self.notify_frame_callbacks();
+        Ok(steps)
+    }
+
+    /// Registers a hook run after every play call, e.g. for beat detection or logging.
+    #[allow(dead_code)] // Extension point for embedders and test harnesses
+    pub fn add_frame_callback(&mut self, callback: FrameCallback) {
+        self.frame_callbacks.push(callback);
+    }
+
+    /// Removes all hooks registered with `add_frame_callback`.
+    #[allow(dead_code)] // Extension point for embedders and test harnesses
+    pub fn clear_frame_callbacks(&mut self) {
+        self.frame_callbacks.clear();
+    }
+
+    fn notify_frame_callbacks(&self) {
+        if self.frame_callbacks.is_empty() {
+            return;
+        }
+        let info = FrameInfo {
+            frame_number: self.frame_counter,
+            voice_levels: self.voice_levels(),
+            envelope_write_pos: self.envelope_write_pos,
+        };
+        for callback in &self.frame_callbacks {
+            callback(&info);
+        }
     }
 }
 
+/// Bit mask of the songs that `SidFile::uses_cia_timing` reports as CIA timed.
+fn cia_song_mask(sid_file: &SidFile) -> u32 {
+    (1..=32)
+        .filter(|&song| sid_file.uses_cia_timing(song))
+        .fold(0, |mask, song| mask | (1 << (song - 1)))
+}
+
+/// Pitch of `voice` (0-2) given one SID's registers, if it is gated and not noise.
+fn voice_frequency(regs: &[u8], voice: usize, clock_hz: u32) -> Option<f32> {
+    let control = regs[usize::from(voice_register(voice, CONTROL))];
+    if control & CONTROL_GATE == 0 || control & CONTROL_NOISE != 0 {
+        return None;
+    }
+    let base = usize::from(voice_register(voice, 0));
+    let freq_reg = u16::from_le_bytes([regs[base], regs[base + 1]]);
+    Some(oscillator_hz(freq_reg, clock_hz))
+}
+
+/// Converts a 16-bit frequency register value to Hz: `freq_reg * clock / 2^24`.
+#[allow(clippy::cast_precision_loss)]
+fn oscillator_hz(freq_reg: u16, clock_hz: u32) -> f32 {
+    f32::from(freq_reg) * clock_hz as f32 / OSCILLATOR_RANGE
+}
+
+/// Returns the SID register for `offset` within a voice (voice numbered across SIDs).
+#[allow(clippy::cast_possible_truncation)]
+const fn voice_register(voice: usize, offset: u8) -> u8 {
+    (voice % VOICES_PER_SID) as u8 * VOICE_REGISTER_STRIDE + offset
+}
+
 fn timing_from_file(sid_file: &SidFile) -> (u32, u32) {
-    let clock_hz = if sid_file.is_pal() {
-        clock::PAL
-    } else {
-        clock::NTSC
-    };
-    let cycles_per_frame = if sid_file.is_pal() {
-        PAL_FRAME_CYCLES
+    if uses_pal_timing(sid_file) {
+        (clock::PAL, PAL_FRAME_CYCLES)
     } else {
-        NTSC_FRAME_CYCLES
-    };
-    (clock_hz, cycles_per_frame)
+        (clock::NTSC, NTSC_FRAME_CYCLES)
+    }
+}
+
+/// v1 headers have no clock flag, so their code is checked for an NTSC
+/// CIA timer setup before falling back to the PAL default.
+fn uses_pal_timing(sid_file: &SidFile) -> bool {
+    if sid_file.version != 1 {
+        return sid_file.is_pal();
+    }
+    !SidFile::detect_clock_from_timer_writes(&sid_file.data).is_some_and(|hint| {
+        hint.clock == ClockType::Ntsc && hint.confidence >= MIN_CLOCK_HINT_CONFIDENCE
+    })
 }
 
 /// Selects chip models for all SIDs in the file.
@@ -497,6 +1641,26 @@ fn build_sid_configs(sid_file: &SidFile, chip_models: &[ChipModel]) -> Vec<(u16,
     configs
 }
 
+/// Appends `extra` to a file's SID configs, skipping chips whose register
+/// window overlaps one already in use and stopping at `MAX_SIDS` chips.
+fn with_extra_sids(
+    mut sid_configs: Vec<(u16, ChipModel)>,
+    extra: &[(u16, ChipModel)],
+) -> Vec<(u16, ChipModel)> {
+    for &(address, model) in extra {
+        if sid_configs.len() >= MAX_SIDS {
+            break;
+        }
+        if sid_configs
+            .iter()
+            .all(|&(taken, _)| taken.abs_diff(address) >= SID_REGISTER_COUNT)
+        {
+            sid_configs.push((address, model));
+        }
+    }
+    sid_configs
+}
+
 fn bootstrap_cpu(
     sid_file: &SidFile,
     chip_models: &[ChipModel],
@@ -539,53 +1703,80 @@ fn setup_stack_for_rts(cpu: &mut CPU<C64Memory, Nmos6502>) {
     cpu.registers.stack_pointer = StackPointer(0xFD);
 }
 
-fn mix_sample(sum: i32, sid_count: usize) -> f32 {
+/// Opens the filter fully and clears resonance, keeping the voice routing bits.
+fn force_filter_open(sid: &mut residfp::Sid) {
+    let routing = sid.read_state().sid_register[usize::from(FILTER_RES_ROUTING)] & 0x0F;
+    sid.write(FILTER_CUTOFF_LO, 0x07);
+    sid.write(FILTER_CUTOFF_HI, 0xFF);
+    sid.write(FILTER_RES_ROUTING, routing);
+}
+
+/// Returns the display name of a chip model ("6581" or "8580").
+pub const fn chip_model_name(model: ChipModel) -> &'static str {
+    match model {
+        ChipModel::Mos6581 => "6581",
+        ChipModel::Mos8580 => "8580",
+    }
+}
+
+/// Scales the summed SID outputs to -1.0..1.0. `SumWithNormalize` expects a sum
+/// already scaled by `PeakWindow::normalize`.
+fn mix_sample_with_mode(sum: i32, sid_count: usize, mode: MixMode) -> f32 {
     #[allow(clippy::cast_precision_loss)]
-    let mixed = (sum as f32) / (sid_count as f32) / 32768.0;
+    let divisor = match mode {
+        MixMode::WeightedAverage => sid_count as f32,
+        MixMode::SumWithClip | MixMode::SumWithNormalize => 1.0,
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let mixed = (sum as f32) / divisor / FULL_SCALE as f32;
     // Keep headroom to avoid int16 overflow in platform backends (DirectSound wraps on >1.0)
     mixed.clamp(-0.999_5, 0.999_5)
 }
 
-fn run_init(cpu: &mut CPU<C64Memory, Nmos6502>, init_address: u16) -> PlayerResult<()> {
-    run_routine(
-        cpu,
-        init_address,
-        1_000_000,
-        PlayerError::InitTimeout {
-            steps: 1_000_000,
-            address: init_address,
-        },
-    )
+fn run_init(
+    cpu: &mut CPU<C64Memory, Nmos6502>,
+    init_address: u16,
+    max_steps: u32,
+) -> PlayerResult<()> {
+    run_routine(cpu, max_steps, |elapsed_cycles| PlayerError::InitTimeout {
+        steps: max_steps,
+        address: init_address,
+        elapsed_cycles,
+    })
+    .map(|_| ())
 }
 
-fn run_play(cpu: &mut CPU<C64Memory, Nmos6502>, play_address: u16) -> PlayerResult<()> {
-    run_routine(
-        cpu,
-        play_address,
-        100_000,
-        PlayerError::PlayTimeout {
-            steps: 100_000,
-            address: play_address,
-        },
-    )
+/// Runs the play routine, returning the number of instructions executed.
+fn run_play(
+    cpu: &mut CPU<C64Memory, Nmos6502>,
+    play_address: u16,
+    max_steps: u32,
+) -> PlayerResult<u32> {
+    run_routine(cpu, max_steps, |elapsed_cycles| PlayerError::PlayTimeout {
+        steps: max_steps,
+        address: play_address,
+        elapsed_cycles,
+    })
 }
 
+/// Steps the CPU until it returns to $0000, or builds the timeout error from
+/// the cycles (bus accesses) spent once `max_steps` is reached.
 fn run_routine(
     cpu: &mut CPU<C64Memory, Nmos6502>,
-    address: u16,
     max_steps: u32,
-    timeout_err: PlayerError,
-) -> PlayerResult<()> {
+    timeout_err: impl FnOnce(u32) -> PlayerError,
+) -> PlayerResult<u32> {
+    let start_cycles = cpu.memory.bus_accesses();
     let mut steps = 0;
     while steps < max_steps {
         if cpu.registers.program_counter == 0x0000 {
-            return Ok(());
+            return Ok(steps);
         }
         cpu.single_step();
         steps += 1;
     }
-    let _ = address; // address kept for symmetry; timeout carries it
-    Err(timeout_err)
+    let elapsed = cpu.memory.bus_accesses() - start_cycles;
+    Err(timeout_err(u32::try_from(elapsed).unwrap_or(u32::MAX)))
 }
 
 /// Thread-safe handle for sharing the player between audio and UI threads.
@@ -598,9 +1789,17 @@ pub fn create_shared_player(
     sample_rate: u32,
     chip_override: Option<u16>,
     sampling_method: SamplingMethod,
+    limits: StepLimits,
 ) -> PlayerResult<SharedPlayer> {
-    Player::new(sid_file, song, sample_rate, chip_override, sampling_method)
-        .map(|p| Arc::new(Mutex::new(p)))
+    Player::with_step_limits(
+        sid_file,
+        song,
+        sample_rate,
+        chip_override,
+        sampling_method,
+        limits,
+    )
+    .map(|p| Arc::new(Mutex::new(p)))
 }
 
 #[cfg(test)]
@@ -656,6 +1855,7 @@ mod tests {
                 flags: 0,
                 data: vec![0x60, 0x60, 0x60],
                 md5: String::new(),
+                data_crc32: crc32fast::hash(&[0x60, 0x60, 0x60]),
                 second_sid_address: None,
                 third_sid_address: None,
             }
@@ -693,7 +1893,7 @@ mod tests {
             Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
 
         for reg in 0..=0x18 {
-            first_sid_mut!(player).write(reg, reg as u8);
+            first_sid_mut!(player).write(reg, reg);
         }
         let before = first_sid!(player).read_state();
 
@@ -703,8 +1903,631 @@ mod tests {
         assert_sid_registers_eq!(before, after, 0..=0x18);
     }
 
+    #[test]
+    fn voice_levels_iterate_per_sid() {
+        let levels = VoiceLevels((1..=6).collect());
+        let sids: Vec<&[u8]> = levels.iter_sids().collect();
+        assert_eq!(sids, [&[1, 2, 3][..], &[4, 5, 6][..]]);
+
+        let sid = test_sid!();
+        let player = Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        let grouped = player.voice_levels_grouped();
+        assert_eq!(grouped.iter_sids().count(), 1);
+        assert_eq!(grouped.as_slice(), player.voice_levels());
+    }
+
+    #[test]
+    fn recording_one_second_of_samples() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        assert!(!player.is_recording());
+
+        player.start_recording();
+        let mut buffer = vec![0.0f32; 44_100];
+        player.fill_buffer(&mut buffer);
+        assert_eq!(player.recording_duration(), Duration::from_secs(1));
+
+        let samples = player.stop_recording().expect("recorded samples");
+        assert_eq!(samples.len(), 44_100);
+        assert!(!player.is_recording());
+        assert_eq!(player.recording_duration(), Duration::ZERO);
+    }
+
+    #[test]
+    fn switching_sampling_method_keeps_output_finite() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        let mut buffer = vec![0.0f32; 1024];
+        player.fill_buffer(&mut buffer);
+
+        for (_, method) in crate::config::SAMPLING_METHODS {
+            player.set_sampling_method(method);
+            player.fill_buffer(&mut buffer);
+            assert!(buffer.iter().all(|s| s.is_finite()));
+        }
+    }
+
+    #[test]
+    fn init_returns_to_address_zero() {
+        let sid = crate::create_silent_sid();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        assert_eq!(player.init_cpu_snapshot().pc, 0x0000);
+        assert_eq!(player.snapshot_cpu_registers(), player.init_cpu_snapshot());
+
+        player.load_song(1).expect("reinit");
+        assert_eq!(player.init_cpu_snapshot().pc, 0x0000);
+    }
+
+    /// A tune whose init sets full volume and gates the first `voices` voices.
+    fn gated_sid(voices: u8) -> SidFile {
+        let mut sid = test_sid!();
+        // LDA #$0F / STA $D418
+        sid.data = vec![0xA9, 0x0F, 0x8D, 0x18, 0xD4];
+        for voice in 0..voices {
+            let control = 0x04 + voice * VOICE_REGISTER_STRIDE;
+            // LDA #$F0 / STA sustain-release, LDA #$11 / STA control
+            sid.data.extend([0xA9, 0xF0, 0x8D, control + 2, 0xD4]);
+            sid.data.extend([0xA9, 0x11, 0x8D, control, 0xD4]);
+        }
+        sid.data.push(0x60);
+        sid.play_address = sid.init_address + sid.data.len() as u16 - 1;
+        sid.data_crc32 = crc32fast::hash(&sid.data);
+        sid
+    }
+
+    #[test]
+    fn crossfade_keeps_kernal_rom_and_write_trace() {
+        let mut player =
+            Player::new(&gated_sid(1), 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        player.install_kernal_rom(&[0xEA; 16]);
+        player.enable_write_trace();
+        player.crossfade_to(&gated_sid(3), 1).expect("crossfade");
+
+        // The incoming tune's init writes were traced
+        assert!(!player.take_write_trace().is_empty());
+        assert_eq!(player.cpu.memory.read_range(0xE000, 1), [0xEA]);
+    }
+
+    #[test]
+    fn crossfade_keeps_filter_bypass() {
+        let mut player =
+            Player::new(&gated_sid(1), 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        player.set_filter_override(0, true);
+        player.crossfade_to(&gated_sid(3), 1).expect("crossfade");
+        assert!(player.is_filter_bypassed(0));
+    }
+
+    #[test]
+    fn crossfade_midpoint_is_mean_of_both_tunes() {
+        let (old_sid, new_sid) = (gated_sid(1), gated_sid(3));
+        let player_for = |sid: &SidFile| {
+            Player::new(sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init")
+        };
+        let mut player = player_for(&old_sid);
+        player.crossfade_to(&new_sid, 1).expect("crossfade");
+        let (mut old, mut new) = (player_for(&old_sid), player_for(&new_sid));
+
+        // Let both tunes get going before looking at the blend
+        let mut warmup = vec![0.0f32; 4_410];
+        player.fill_buffer_crossfade(&mut warmup, 1);
+        old.fill_buffer(&mut warmup);
+        new.fill_buffer(&mut warmup);
+        if let Some(fade) = &mut player.crossfade {
+            fade.progress = 0.5;
+        }
+
+        let mut mixed = [0.0f32; 1];
+        let mut old_out = [0.0f32; 1];
+        let mut new_out = [0.0f32; 1];
+        player.fill_buffer_crossfade(&mut mixed, 1);
+        old.fill_buffer(&mut old_out);
+        new.fill_buffer(&mut new_out);
+        assert_ne!(old_out, new_out, "the tunes should sound different");
+        assert!((mixed[0] - (old_out[0] + new_out[0]) / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn render_seconds_produces_exact_sample_count() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        player.start_recording();
+        assert_eq!(player.render_seconds(2), 2 * 44_100);
+        assert_eq!(player.stop_recording().map(|s| s.len()), Some(2 * 44_100));
+    }
+
+    #[test]
+    fn register_snapshot_reports_written_registers() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        first_sid_mut!(player).write(0x18, 0x0F);
+        let snapshot = player.register_snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0][0x18], 0x0F);
+        assert_eq!(player.sid_addresses(), [0xD400]);
+    }
+
+    #[test]
+    fn frame_callbacks_see_each_play_call() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&frames);
+        player.add_frame_callback(Box::new(move |info| {
+            seen.lock().unwrap().push(info.frame_number);
+        }));
+
+        // About 880 samples per PAL frame at 44.1 kHz
+        let mut buffer = vec![0.0; 2000];
+        player.fill_buffer(&mut buffer);
+        player.fill_buffer(&mut buffer);
+        let frames = frames.lock().unwrap().clone();
+        assert!(frames.len() >= 4, "{frames:?}");
+        assert!(frames.windows(2).all(|w| w[1] == w[0] + 1), "{frames:?}");
+
+        player.clear_frame_callbacks();
+        assert!(player.frame_callbacks.is_empty());
+    }
+
+    #[test]
+    fn extra_sids_add_voices() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        let extra = [0xD500, 0xD600, 0xD700].map(|addr| (addr, ChipModel::Mos6581));
+        player.set_extra_sids(extra.to_vec()).expect("extra sids");
+        assert_eq!(player.sid_count(), 4);
+        assert_eq!(player.voice_levels().len(), 12);
+        assert_eq!(player.sid_addresses(), [0xD400, 0xD500, 0xD600, 0xD700]);
+
+        // Replacing the extras keeps the file's own chip, and the total is capped
+        let many = (0..10u16).map(|i| (0xDE00 + i * 0x20, ChipModel::Mos8580));
+        player.set_extra_sids(many.collect()).expect("extra sids");
+        assert_eq!(player.sid_count(), MAX_SIDS);
+        assert_eq!(player.sid_addresses()[..2], [0xD400, 0xDE00]);
+    }
+
+    #[test]
+    fn extra_sids_skip_overlapping_windows() {
+        let extra = [0xD410, 0xD500, 0xD510, 0xD520].map(|addr| (addr, ChipModel::Mos6581));
+        let configs = with_extra_sids(vec![(0xD400, ChipModel::Mos8580)], &extra);
+        let addresses: Vec<u16> = configs.iter().map(|&(addr, _)| addr).collect();
+        assert_eq!(addresses, [0xD400, 0xD500, 0xD520]);
+    }
+
+    #[test]
+    fn extra_sids_stay_capped_across_tunes() {
+        let mut player =
+            Player::new(&test_sid!(), 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        // The first extra duplicates the 3SID tune's second chip below
+        let extra = (1..MAX_SIDS as u16).map(|i| (0xD400 + i * 0x20, ChipModel::Mos6581));
+        player.set_extra_sids(extra.collect()).expect("extra sids");
+        assert_eq!(player.sid_count(), MAX_SIDS);
+
+        let mut three_sid = test_sid!();
+        three_sid.second_sid_address = Some(0xD420);
+        three_sid.third_sid_address = Some(0xD500);
+        player.load_sid_file(&three_sid, 1).expect("load 3SID tune");
+        assert_eq!(player.sid_count(), MAX_SIDS);
+        let addresses = player.sid_addresses();
+        assert_eq!(addresses[..4], [0xD400, 0xD420, 0xD500, 0xD440]);
+        player.set_voice_soft_mute(MAX_VOICES - 1, true);
+
+        // Back to one SID, all extras fit again
+        player
+            .load_sid_file(&test_sid!(), 1)
+            .expect("load 1SID tune");
+        assert_eq!(player.sid_count(), MAX_SIDS);
+        assert_eq!(player.sid_addresses()[1], 0xD420);
+    }
+
+    #[test]
+    fn total_voice_count_follows_chip_count() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        for extra_count in 0..MAX_SIDS as u16 {
+            let extra = (0..extra_count).map(|i| (0xD420 + i * 0x20, ChipModel::Mos8580));
+            player.set_extra_sids(extra.collect()).expect("extra sids");
+            assert_eq!(player.total_voice_count(), player.chip_models().len() * 3);
+            assert_eq!(player.voice_levels().len(), player.total_voice_count());
+        }
+    }
+
+    #[test]
+    fn zero_play_address_skips_play_routine() {
+        let mut sid = test_sid!();
+        sid.play_address = 0;
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        assert_eq!(player.play_address, 0);
+        assert_eq!(player.call_play().expect("play"), 0);
+    }
+
+    #[test]
+    fn soft_mute_fades_only_the_muted_voice() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+
+        // Gate all three voices with instant attack and full sustain
+        for voice in 0..3u8 {
+            let base = voice * VOICE_REGISTER_STRIDE;
+            first_sid_mut!(player).write(base + ATTACK_DECAY, 0x00);
+            first_sid_mut!(player).write(base + ATTACK_DECAY + 1, 0xF0);
+            first_sid_mut!(player).write(base + 4, 0x21);
+        }
+        let mut buffer = vec![0.0; 1024];
+        player.fill_buffer(&mut buffer);
+        let before = player.voice_levels();
+
+        player.set_voice_soft_mute(0, true);
+        assert!(player.is_voice_soft_muted(0));
+        let mut previous = before.clone();
+        for _ in 0..8 {
+            player.fill_buffer(&mut buffer);
+            let levels = player.voice_levels();
+            assert!(levels[0] <= previous[0]);
+            assert_eq!(&levels[1..], &before[1..]);
+            previous = levels;
+        }
+        assert!(previous[0] < before[0]);
+
+        player.set_voice_soft_mute(0, false);
+        assert_eq!(player.voice_adsr(0), [0x00, 0xF0]);
+    }
+
+    #[test]
+    fn multichannel_voices_sum_to_mono() {
+        let sid = test_sid!();
+        let mut mono =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        let mut multi =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        for player in [&mut mono, &mut multi] {
+            first_sid_mut!(player).write(ATTACK_DECAY, 0x00);
+            first_sid_mut!(player).write(ATTACK_DECAY + 1, 0xF0);
+            first_sid_mut!(player).write(4, 0x21);
+            first_sid_mut!(player).write(VOICE_REGISTER_STRIDE + ATTACK_DECAY + 1, 0x80);
+            first_sid_mut!(player).write(VOICE_REGISTER_STRIDE + 4, 0x41);
+        }
+
+        let channels = multi.total_voice_count();
+        let mut mono_buffer = vec![0.0; 512];
+        let mut multi_buffer = vec![0.0; 512 * channels];
+        mono.fill_buffer(&mut mono_buffer);
+        multi.fill_buffer_multichannel(&mut multi_buffer, channels);
+
+        assert!(mono_buffer.iter().any(|&s| s != 0.0));
+        for (frame, &expected) in multi_buffer.chunks_exact(channels).zip(&mono_buffer) {
+            let sum: f32 = frame.iter().sum();
+            assert!((sum - expected).abs() < 1e-5, "{sum} != {expected}");
+        }
+    }
+
+    #[test]
+    fn filter_bypass_overrides_play_routine_writes() {
+        let mut sid = test_sid!();
+        // play: LDA #$00, STA $D416, LDA #$F1, STA $D417, RTS
+        sid.data = vec![
+            0x60, 0x60, 0x60, 0xA9, 0x00, 0x8D, 0x16, 0xD4, 0xA9, 0xF1, 0x8D, 0x17, 0xD4, 0x60,
+        ];
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        let mut buffer = vec![0.0; 1024];
+
+        player.fill_buffer(&mut buffer);
+        let regs = player.register_snapshot()[0];
+        assert_eq!((regs[0x16], regs[0x17]), (0x00, 0xF1));
+
+        player.set_filter_override(0, true);
+        assert!(player.is_filter_bypassed(0));
+        player.fill_buffer(&mut buffer);
+        let regs = player.register_snapshot()[0];
+        assert_eq!((regs[0x15], regs[0x16], regs[0x17]), (0x07, 0xFF, 0x01));
+
+        player.set_filter_override(0, false);
+        let regs = player.register_snapshot()[0];
+        assert_eq!((regs[0x15], regs[0x16], regs[0x17]), (0x00, 0x00, 0xF1));
+    }
+
+    #[test]
+    fn step_frame_advances_one_play_call() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        let mut buffer = vec![1.0; 1024];
+
+        player.set_step_mode(true);
+        player.fill_buffer(&mut buffer);
+        assert!(buffer.iter().all(|&s| s == 0.0));
+        assert_eq!(player.frame_cycle_count, 0);
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let max_cycles_per_sample = player.cycles_per_sample.ceil() as u32;
+        for n in 1..=5 {
+            player.step_frame(&mut buffer).expect("step");
+            assert!(player.frame_cycle_count <= max_cycles_per_sample);
+            assert_eq!(player.step_count(), n);
+        }
+    }
+
+    #[test]
+    fn fifty_pal_frames_are_one_second() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        assert_eq!(player.position_as_frame_count(), 0);
+
+        player.render_seconds(1);
+        assert_eq!(player.position_as_frame_count(), 50);
+        #[allow(clippy::cast_precision_loss)]
+        let emulated = 50.0 * f64::from(player.cycles_per_frame) / f64::from(player.clock_hz);
+        assert!((emulated - 1.0).abs() < 0.01, "{emulated}");
+
+        player.seek_to_frame(10).expect("seek");
+        assert_eq!(player.position_as_frame_count(), 10);
+    }
+
+    #[test]
+    fn cia_timed_song_fails_when_played() {
+        let mut sid = test_sid!();
+        sid.songs = 2;
+        sid.speed = 0b10;
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        player.render_seconds(1);
+        assert_eq!(player.take_error(), None);
+
+        player.load_song(2).expect("init still runs");
+        player.render_seconds(1);
+        assert_eq!(
+            player.take_error().as_deref(),
+            Some("Song 2 requires CIA timer emulation")
+        );
+    }
+
+    #[test]
+    fn nop_loop_timeout_counts_one_cycle_per_step() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        player.cpu.memory.load(0x2000, &[0xEA; 1000]);
+        player.cpu.registers.program_counter = 0x2000;
+
+        let err = run_routine(&mut player.cpu, 1000, |elapsed_cycles| {
+            PlayerError::InitTimeout {
+                steps: 1000,
+                address: 0x2000,
+                elapsed_cycles,
+            }
+        })
+        .unwrap_err();
+        let PlayerError::InitTimeout { elapsed_cycles, .. } = err else {
+            panic!("unexpected error {err}");
+        };
+        assert_eq!(elapsed_cycles, 1000);
+        assert_eq!(millions(4_200_000), "4.2M");
+        assert_eq!(millions(1_000_000), "1M");
+    }
+
+    #[test]
+    fn init_step_limit_is_configurable() {
+        let sid = test_sid!();
+        let limits = |init_max_steps| StepLimits {
+            init_max_steps,
+            ..StepLimits::default()
+        };
+        // The fixture's init is a single RTS
+        let player =
+            Player::with_step_limits(&sid, 1, 44_100, None, SamplingMethod::Fast, limits(2))
+                .expect("one step fits");
+        assert_eq!(player.init_max_steps, 2);
+
+        let mut sid = test_sid!();
+        sid.data = vec![0xEA, 0xEA, 0xEA, 0x60];
+        let err = Player::with_step_limits(&sid, 1, 44_100, None, SamplingMethod::Fast, limits(2))
+            .err()
+            .expect("four steps don't fit");
+        assert!(
+            matches!(err, PlayerError::InitTimeout { steps: 2, .. }),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn v1_tune_with_ntsc_timer_plays_at_ntsc_clock() {
+        let mut sid = test_sid!();
+        sid.version = 1;
+        let player = Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        assert_eq!(player.clock_hz, clock::PAL);
+
+        // A stray NTSC-looking byte pair in the data is not a timer write
+        sid.data.extend([0x95, 0x42]);
+        let player = Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        assert_eq!(player.clock_hz, clock::PAL);
+
+        // Unreached after the RTS: LDA #$95 / STA $DC06 / LDA #$42 / STA $DC07
+        sid.data
+            .extend([0xA9, 0x95, 0x8D, 0x06, 0xDC, 0xA9, 0x42, 0x8D, 0x07, 0xDC]);
+        let player = Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        assert_eq!(player.clock_hz, clock::NTSC);
+        assert_eq!(player.cycles_per_frame, NTSC_FRAME_CYCLES);
+    }
+
+    #[test]
+    fn write_trace_records_sid_writes() {
+        let mut player =
+            Player::new(&test_sid!(), 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        player.enable_write_trace();
+        player.render_seconds(1);
+        assert!(player.take_write_trace().is_empty());
+
+        // play: LDA #$11 / STA $D400 / RTS
+        let mut sid = test_sid!();
+        sid.data = vec![0x60, 0x60, 0x60, 0xA9, 0x11, 0x8D, 0x00, 0xD4, 0x60];
+        sid.play_address = 0x1003;
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        player.enable_write_trace();
+        player.seek_to_frame(3).expect("play calls");
+        let trace = player.take_write_trace();
+        assert_eq!(trace.len(), 3);
+        assert!(trace.iter().all(|e| e.address == 0xD400 && e.value == 0x11));
+        assert!(trace.windows(2).all(|w| w[0].cpu_cycle < w[1].cpu_cycle));
+        assert!(player.take_write_trace().is_empty());
+    }
+
+    #[test]
+    fn dither_varies_output_and_keeps_mean() {
+        let mut dither = DitherState::default();
+        let out: Vec<f32> = (0..10_000).map(|_| dither.apply(0.25)).collect();
+        assert!(out.windows(2).any(|pair| pair[0] != pair[1]));
+        assert!(out.iter().all(|s| (s - 0.25).abs() <= DITHER_AMPLITUDE));
+        #[allow(clippy::cast_precision_loss)]
+        let mean = out.iter().sum::<f32>() / out.len() as f32;
+        assert!((mean - 0.25).abs() < DITHER_AMPLITUDE / 10.0, "mean {mean}");
+    }
+
+    #[test]
+    fn frequency_register_converts_to_hz() {
+        // A4 on a PAL machine
+        assert!((oscillator_hz(0x1D45, clock::PAL) - 440.0).abs() < 1.0);
+
+        let mut regs = [0u8; SID_WRITABLE_REGISTERS];
+        regs[7..9].copy_from_slice(&0x1D45u16.to_le_bytes());
+        regs[7 + 4] = 0x11; // triangle, gate on
+        assert!(voice_frequency(&regs, 1, clock::PAL).is_some());
+        assert_eq!(voice_frequency(&regs, 0, clock::PAL), None);
+        regs[7 + 4] = 0x81; // noise
+        assert_eq!(voice_frequency(&regs, 1, clock::PAL), None);
+    }
+
+    #[test]
+    fn silent_tune_reports_silence() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        let mut buffer = vec![0.0; 4096];
+        player.fill_buffer(&mut buffer);
+        assert!(
+            player.never_sounded(0.001),
+            "{}",
+            player.recent_max_amplitude
+        );
+    }
+
+    #[test]
+    fn muted_or_resting_tune_is_not_silent() {
+        let mut player =
+            Player::new(&gated_sid(1), 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        // Nothing reaches the output, but the tune itself plays
+        player.set_master_volume(0.0);
+        player.set_solo_voice(Some(2));
+        let mut buffer = vec![0.0; 4096];
+        player.fill_buffer(&mut buffer);
+        assert!(!player.never_sounded(0.001));
+
+        // A rest later in the song doesn't make it silent either
+        player.recent_max_amplitude = 0.0;
+        player.fill_buffer(&mut buffer);
+        assert!(!player.never_sounded(0.001));
+
+        player.load_song(1).expect("reinit");
+        assert!(player.never_sounded(0.001));
+    }
+
+    #[test]
+    fn solo_voice_mutes_the_others() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+
+        player.set_solo_voice(Some(0));
+        let muted: Vec<bool> = (0..3).map(|v| player.is_voice_soft_muted(v)).collect();
+        assert_eq!(muted, [false, true, true]);
+
+        player.set_solo_voice(None);
+        assert!((0..3).all(|v| !player.is_voice_soft_muted(v)));
+    }
+
+    #[test]
+    fn rts_only_play_routine_takes_one_step() {
+        let mut sid = test_sid!();
+        sid.play_address = 0x1001;
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        assert_eq!(player.perf_summary(1024), None);
+
+        assert_eq!(player.measure_frame_cpu_cycles(), 1);
+        assert_eq!(player.measure_frame_cpu_cycles(), 1);
+
+        let summary = player.perf_summary(1024).expect("frames recorded");
+        assert_eq!((summary.min_steps, summary.max_steps), (1, 1));
+        assert_eq!(summary.avg_steps, 1.0);
+    }
+
+    #[test]
+    fn late_fill_counts_as_underrun() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+
+        // 64 samples last ~1.5ms, so a 20ms gap starves the device
+        let mut buffer = vec![0.0; 64];
+        player.fill_buffer(&mut buffer);
+        std::thread::sleep(Duration::from_millis(20));
+        player.fill_buffer(&mut buffer);
+        assert_eq!(player.underrun_count(), 1);
+
+        player.clear_underrun_count();
+        assert_eq!(player.underrun_count(), 0);
+    }
+
+    #[test]
+    fn fill_keeps_pending_error_for_the_ui() {
+        let sid = test_sid!();
+        let mut player =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        player.playback_error = Some("play routine crashed".to_string());
+
+        let mut buffer = vec![1.0; 64];
+        player.fill_buffer(&mut buffer);
+        player.fill_buffer_multichannel(&mut buffer, 3);
+        assert!(buffer.iter().all(|&s| s == 0.0));
+        assert_eq!(player.take_error().as_deref(), Some("play routine crashed"));
+    }
+
+    #[test]
+    fn double_speed_advances_frame_twice_as_fast() {
+        let sid = test_sid!();
+        let mut normal =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        let mut fast =
+            Player::new(&sid, 1, 44_100, None, SamplingMethod::Fast).expect("player init");
+        fast.set_speed_factor(2.0);
+
+        // Stay within one frame so the cycle counter doesn't wrap
+        let mut buffer = vec![0.0; 100];
+        normal.fill_buffer(&mut buffer);
+        fast.fill_buffer(&mut buffer);
+
+        let expected = 2 * normal.frame_cycle_count;
+        assert!(fast.frame_cycle_count.abs_diff(expected) <= 1);
+
+        fast.set_speed_factor(10.0);
+        assert_eq!(fast.speed_factor(), MAX_SPEED_FACTOR);
+    }
+
     #[test]
     fn mix_sample_limits_output() {
+        let mix_sample =
+            |sum, sid_count| mix_sample_with_mode(sum, sid_count, MixMode::WeightedAverage);
         assert_eq!(mix_sample(0, 1), 0.0);
         assert!(mix_sample(i32::MAX, 1) <= 1.0);
         assert!(mix_sample(i32::MIN, 1) >= -1.0);
@@ -712,6 +2535,35 @@ mod tests {
         assert!(clipped < 0.999_6);
     }
 
+    #[test]
+    fn mix_modes_scale_multiple_sids() {
+        // One of two SIDs at full scale
+        let sum = i32::from(i16::MAX);
+        assert_eq!(mix_sample_with_mode(sum, 2, MixMode::SumWithClip), 0.999_5);
+        let average = mix_sample_with_mode(sum, 2, MixMode::WeightedAverage);
+        assert!((average - 0.999_5 / 2.0).abs() < 1e-3, "{average}");
+        // Both at full scale still clip
+        assert_eq!(
+            mix_sample_with_mode(2 * sum, 2, MixMode::SumWithClip),
+            0.999_5
+        );
+    }
+
+    #[test]
+    fn normalize_scales_by_recent_peak() {
+        let mut peak = PeakWindow::default();
+        // Quiet signals pass unchanged
+        assert_eq!(peak.normalize(1000), 1000);
+        // A sum of two full-scale SIDs is brought back to one SID's range
+        assert_eq!(peak.normalize(2 * FULL_SCALE), FULL_SCALE);
+        assert_eq!(peak.normalize(FULL_SCALE), FULL_SCALE / 2);
+        // The peak is forgotten after two windows
+        for _ in 0..2 * NORMALIZE_WINDOW {
+            peak.normalize(0);
+        }
+        assert_eq!(peak.normalize(FULL_SCALE), FULL_SCALE);
+    }
+
     #[test]
     fn glitch_fixture_stays_within_i16_range() {
         let sid = load_fixture("Glitch.sid");
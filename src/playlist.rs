@@ -1,10 +1,19 @@
 // SPDX-License-Identifier: MIT
 // Copyright (c) 2026 Mikael Lund
 
+use crate::hvsc::fetch_bytes_with_timeout;
 use crate::sid_file::SidFile;
+use quick_xml::Writer;
+use quick_xml::events::{BytesDecl, BytesText, Event};
+use quick_xml::reader::Reader;
+use rand::Rng;
+use rand::seq::SliceRandom;
 use std::fs;
-use std::io::{self, Read};
+use std::io;
 use std::path::Path;
+use std::time::Duration;
+
+const XSPF_NAMESPACE: &str = "http://xspf.org/ns/0/";
 
 /// Default songs for a new playlist.
 const DEFAULT_PLAYLIST: &[&str] = &[
@@ -25,11 +34,13 @@ pub struct PlaylistEntry {
     pub display_name: String,
     /// Optional subsong override (1-indexed)
     pub subsong: Option<u16>,
+    /// `<annotation>` from an XSPF playlist (typically the STIL comment)
+    pub xspf_comment: Option<String>,
 }
 
 impl PlaylistEntry {
     /// Creates a new entry, extracting display name and optional subsong.
-    fn new(source: &str) -> Option<Self> {
+    pub fn new(source: &str) -> Option<Self> {
         let trimmed = source.trim();
         if trimmed.is_empty() || trimmed.starts_with('#') {
             return None;
@@ -42,18 +53,43 @@ impl PlaylistEntry {
             source: path_part.to_string(),
             display_name,
             subsong,
+            xspf_comment: None,
         })
     }
 
-    /// Returns true if this entry is a URL (http/https).
+    /// Returns true if this entry is a URL (http/https) or an embedded `data:` URI.
     pub fn is_url(&self) -> bool {
-        self.source.starts_with("http://") || self.source.starts_with("https://")
+        ["http://", "https://", "data:"]
+            .iter()
+            .any(|scheme| self.source.starts_with(scheme))
+    }
+
+    /// Returns the source with any `@N` subsong suffix, local paths made relative to `base`.
+    fn location(&self, base: Option<&Path>) -> String {
+        let source = match base {
+            Some(base) if !self.is_url() => pathdiff::diff_paths(&self.source, base)
+                .map_or_else(|| self.source.clone(), |p| p.to_string_lossy().to_string()),
+            _ => self.source.clone(),
+        };
+        match self.subsong {
+            Some(sub) => format!("{source}@{sub}"),
+            None => source,
+        }
     }
 
     /// Loads the SID file from this entry's source.
     pub fn load(&self) -> io::Result<SidFile> {
         if self.is_url() {
-            load_from_url(&self.source)
+            SidFile::from_url(&self.source)
+        } else {
+            SidFile::load(&self.source)
+        }
+    }
+
+    /// Loads the SID file, giving up on URL downloads after `timeout`.
+    pub fn load_with_timeout(&self, timeout: Duration) -> io::Result<SidFile> {
+        if self.is_url() {
+            SidFile::from_bytes(&fetch_bytes_with_timeout(&self.source, timeout)?)
         } else {
             SidFile::load(&self.source)
         }
@@ -73,33 +109,105 @@ fn parse_subsong(s: &str) -> (&str, Option<u16>) {
 
 /// Extracts filename from path or URL.
 fn extract_filename(path: &str) -> String {
+    // Base64 payloads contain '/', so there is no file name to take
+    if path.starts_with("data:") {
+        return "embedded.sid".to_string();
+    }
     path.rsplit(['/', '\\']).next().unwrap_or(path).to_string()
 }
 
-/// Fetches and parses a SID file from a URL.
-fn load_from_url(url: &str) -> io::Result<SidFile> {
-    let response = ureq::get(url)
-        .call()
-        .map_err(|e| io::Error::other(e.to_string()))?;
+/// Encodes the tune as a base64 `data:` URI that a playlist line can hold in place of a path.
+#[cfg(test)]
+pub fn encode_sid_as_data_uri(sid: &SidFile) -> String {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    format!(
+        "data:application/octet-stream;base64,{}",
+        BASE64.encode(sid.to_bytes())
+    )
+}
+
+/// Track fields collected while parsing XSPF.
+#[derive(Default)]
+struct XspfTrack {
+    location: Option<String>,
+    title: Option<String>,
+    annotation: Option<String>,
+}
+
+impl XspfTrack {
+    fn into_entry(self) -> Option<PlaylistEntry> {
+        let location = self.location?;
+        let mut entry = PlaylistEntry::new(location.strip_prefix("file://").unwrap_or(&location))?;
+        if let Some(title) = self.title {
+            entry.display_name = title;
+        }
+        entry.xspf_comment = self.annotation;
+        Some(entry)
+    }
+}
+
+/// XSPF element whose text is being read.
+#[derive(Clone, Copy)]
+enum XspfField {
+    Location,
+    Title,
+    Annotation,
+}
+
+fn is_xspf(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("xspf"))
+}
 
-    let mut bytes = Vec::new();
-    response.into_body().into_reader().read_to_end(&mut bytes)?;
+/// Articles skipped when sorting with `ignore_articles`, lowercase with trailing space.
+const LEADING_ARTICLES: &[&str] = &["the ", "an ", "a "];
 
-    SidFile::parse(&bytes)
+/// Order applied by `Playlist::sort`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Case-insensitive display name, optionally ignoring "The", "A" and "An"
+    DisplayName { ignore_articles: bool },
+}
+
+/// Strips a leading "The ", "A " or "An " (any case) from `s`.
+fn strip_leading_article(s: &str) -> &str {
+    LEADING_ARTICLES
+        .iter()
+        .find_map(|article| {
+            s.get(..article.len())
+                .filter(|prefix| prefix.eq_ignore_ascii_case(article))
+                .map(|_| &s[article.len()..])
+        })
+        .unwrap_or(s)
+}
+
+fn invalid_xspf(e: impl ToString) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Invalid XSPF: {}", e.to_string()),
+    )
 }
 
 /// A playlist of SID tunes loaded from an m3u file.
 #[derive(Debug, Clone)]
 pub struct Playlist {
     pub entries: Vec<PlaylistEntry>,
+    /// Permutation undoing the last shuffle; dropped by any other change to the entries
+    undo_order: Option<Vec<usize>>,
 }
 
 impl Playlist {
     /// Creates an empty playlist.
     #[allow(dead_code)]
     pub fn new() -> Self {
+        Self::from_entries(Vec::new())
+    }
+
+    fn from_entries(entries: Vec<PlaylistEntry>) -> Self {
         Self {
-            entries: Vec::new(),
+            entries,
+            undo_order: None,
         }
     }
 
@@ -118,46 +226,144 @@ impl Playlist {
             .iter()
             .filter_map(|s| PlaylistEntry::new(s))
             .collect();
-        Self { entries }
+        Self::from_entries(entries)
     }
 
-    /// Loads a playlist from an m3u file.
+    /// Loads a playlist from an m3u file, or XSPF if the extension is `.xspf`.
     pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
         let content = fs::read_to_string(&path)?;
-        let base_dir = path.as_ref().parent();
-
-        let entries: Vec<PlaylistEntry> = content
-            .lines()
-            .filter_map(|line| {
-                let mut entry = PlaylistEntry::new(line)?;
-                // Resolve relative paths against playlist directory
-                if !entry.is_url()
-                    && !Path::new(&entry.source).is_absolute()
-                    && let Some(base) = base_dir
-                {
+        let mut playlist = if is_xspf(path.as_ref()) {
+            Self::load_xspf(&content)?
+        } else {
+            Self::from_entries(content.lines().filter_map(PlaylistEntry::new).collect())
+        };
+
+        // Resolve relative paths against playlist directory
+        if let Some(base) = path.as_ref().parent() {
+            for entry in &mut playlist.entries {
+                if !entry.is_url() && !Path::new(&entry.source).is_absolute() {
                     entry.source = base.join(&entry.source).to_string_lossy().to_string();
                 }
-                Some(entry)
-            })
-            .collect();
+            }
+        }
+        Ok(playlist)
+    }
+
+    /// Parses an XSPF document, reading `<location>`, `<title>` and `<annotation>` per track.
+    pub fn load_xspf(content: &str) -> io::Result<Self> {
+        let mut reader = Reader::from_str(content);
+        reader.config_mut().trim_text(true);
 
-        Ok(Self { entries })
+        let mut entries = Vec::new();
+        let mut track: Option<XspfTrack> = None;
+        let mut field = None;
+        let mut depth = 0usize;
+        loop {
+            match reader.read_event().map_err(invalid_xspf)? {
+                Event::Start(tag) => {
+                    depth += 1;
+                    field = match tag.local_name().as_ref() {
+                        b"track" => {
+                            track = Some(XspfTrack::default());
+                            None
+                        }
+                        b"location" => Some(XspfField::Location),
+                        b"title" => Some(XspfField::Title),
+                        b"annotation" => Some(XspfField::Annotation),
+                        _ => None,
+                    };
+                }
+                Event::End(tag) => {
+                    depth = depth.saturating_sub(1);
+                    field = None;
+                    if tag.local_name().as_ref() == b"track"
+                        && let Some(entry) = track.take().and_then(XspfTrack::into_entry)
+                    {
+                        entries.push(entry);
+                    }
+                }
+                Event::Text(text) => {
+                    if let (Some(track), Some(field)) = (&mut track, field) {
+                        let text = text.unescape().map_err(invalid_xspf)?.into_owned();
+                        match field {
+                            XspfField::Location => track.location = Some(text),
+                            XspfField::Title => track.title = Some(text),
+                            XspfField::Annotation => track.annotation = Some(text),
+                        }
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if depth != 0 {
+            return Err(invalid_xspf("unclosed element at end of document"));
+        }
+        Ok(Self::from_entries(entries))
     }
 
-    /// Saves the playlist to an m3u file.
+    /// Saves the playlist as m3u, or XSPF if the extension is `.xspf`.
     pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        let content: String = self
-            .entries
+        if is_xspf(path.as_ref()) {
+            self.save_xspf(path.as_ref())
+        } else {
+            fs::write(path, self.to_m3u(None))
+        }
+    }
+
+    /// Saves the playlist as an XSPF document.
+    pub fn save_xspf(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_xspf(None)?)
+    }
+
+    /// Saves the playlist to `target` with local paths relative to `base`.
+    /// URLs stay absolute, so the file works after moving the directory tree.
+    pub fn save_relative(&self, base: &Path, target: &Path) -> io::Result<()> {
+        if is_xspf(target) {
+            fs::write(target, self.to_xspf(Some(base))?)
+        } else {
+            fs::write(target, self.to_m3u(Some(base)))
+        }
+    }
+
+    /// Renders m3u lines, optionally rewriting local paths relative to `base`.
+    fn to_m3u(&self, base: Option<&Path>) -> String {
+        self.entries
             .iter()
-            .map(|e| {
-                if let Some(sub) = e.subsong {
-                    format!("{}@{}\n", e.source, sub)
-                } else {
-                    format!("{}\n", e.source)
-                }
-            })
-            .collect();
-        fs::write(path, content)
+            .map(|e| format!("{}\n", e.location(base)))
+            .collect()
+    }
+
+    /// Renders an XSPF document, optionally rewriting local paths relative to `base`.
+    fn to_xspf(&self, base: Option<&Path>) -> io::Result<String> {
+        let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        writer
+            .create_element("playlist")
+            .with_attribute(("version", "1"))
+            .with_attribute(("xmlns", XSPF_NAMESPACE))
+            .write_inner_content(|w| {
+                w.create_element("trackList").write_inner_content(|w| {
+                    for entry in &self.entries {
+                        w.create_element("track").write_inner_content(|w| {
+                            let location = entry.location(base);
+                            w.create_element("location")
+                                .write_text_content(BytesText::new(&location))?;
+                            w.create_element("title")
+                                .write_text_content(BytesText::new(&entry.display_name))?;
+                            if let Some(comment) = &entry.xspf_comment {
+                                w.create_element("annotation")
+                                    .write_text_content(BytesText::new(comment))?;
+                            }
+                            Ok(())
+                        })?;
+                    }
+                    Ok(())
+                })?;
+                Ok(())
+            })?;
+        String::from_utf8(writer.into_inner()).map_err(invalid_xspf)
     }
 
     /// Returns true if playlist contains an entry with the given source and subsong.
@@ -175,19 +381,122 @@ impl Playlist {
         if let Some(mut entry) = PlaylistEntry::new(source) {
             entry.subsong = subsong;
             self.entries.push(entry);
+            self.undo_order = None;
             true
         } else {
             false
         }
     }
 
+    /// Adds every entry of `other` not already present. Returns the number added.
+    pub fn merge(&mut self, other: &Playlist) -> usize {
+        other
+            .entries
+            .iter()
+            .filter(|entry| self.add(&entry.source, entry.subsong))
+            .count()
+    }
+
+    /// Stable sort of the entries; only the comparison key is affected by `key`.
+    pub fn sort(&mut self, key: SortKey) {
+        self.undo_order = None;
+        match key {
+            SortKey::DisplayName { ignore_articles } => {
+                self.entries.sort_by_cached_key(|entry| {
+                    let name = if ignore_articles {
+                        strip_leading_article(&entry.display_name)
+                    } else {
+                        &entry.display_name
+                    };
+                    name.to_lowercase()
+                });
+            }
+        }
+    }
+
+    /// Shuffles the entries in place (Fisher-Yates), remembering the old order for `undo_shuffle`.
+    pub fn shuffle(&mut self, rng: &mut impl Rng) {
+        // order[i] is the original index of the entry now at position i
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        order.shuffle(rng);
+        self.reorder_by(&order);
+        let mut undo = vec![0; order.len()];
+        for (position, &original) in order.iter().enumerate() {
+            undo[original] = position;
+        }
+        self.undo_order = Some(undo);
+    }
+
+    /// Restores the order from before the last shuffle. Returns false if there is
+    /// nothing to undo, e.g. because the playlist changed since.
+    pub fn undo_shuffle(&mut self) -> bool {
+        match self.undo_order.take() {
+            Some(order) => {
+                self.reorder_by(&order);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Rearranges the entries so position `i` holds the entry previously at
+    /// `permutation[i]`. Ignored unless `permutation` covers every entry.
+    pub fn reorder_by(&mut self, permutation: &[usize]) {
+        if permutation.len() != self.entries.len() {
+            return;
+        }
+        self.undo_order = None;
+        let mut old: Vec<Option<PlaylistEntry>> = self.entries.drain(..).map(Some).collect();
+        self.entries = permutation
+            .iter()
+            .filter_map(|&i| old.get_mut(i).and_then(Option::take))
+            .collect();
+    }
+
+    /// Returns a random entry and its index, skipping the indices in `recent`.
+    /// None if every entry is excluded.
+    pub fn random_entry_excluding(&self, recent: &[usize]) -> Option<(usize, &PlaylistEntry)> {
+        let candidates: Vec<usize> = (0..self.entries.len())
+            .filter(|i| !recent.contains(i))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let index = candidates[rand::random_range(0..candidates.len())];
+        Some((index, &self.entries[index]))
+    }
+
+    /// Replaces the entry at `idx` with `entries`, in place.
+    /// Returns false, leaving the playlist as is, if `idx` is out of range or `entries` is empty.
+    pub fn replace_with_expansion(&mut self, idx: usize, entries: Vec<PlaylistEntry>) -> bool {
+        if idx >= self.entries.len() || entries.is_empty() {
+            return false;
+        }
+        self.entries.splice(idx..=idx, entries);
+        self.undo_order = None;
+        true
+    }
+
     /// Removes an entry at the given index.
     pub fn remove(&mut self, index: usize) {
         if index < self.entries.len() {
             self.entries.remove(index);
+            self.undo_order = None;
         }
     }
 
+    /// Moves the entry at `from` to position `to`, shifting entries in between.
+    /// `to` is clamped to the last index; out-of-range `from` is ignored.
+    pub fn move_entry(&mut self, from: usize, to: usize) {
+        if from >= self.entries.len() {
+            return;
+        }
+        let to = to.min(self.entries.len() - 1);
+        let entry = self.entries.remove(from);
+        self.entries.insert(to, entry);
+        self.undo_order = None;
+    }
+
     /// Returns true if the playlist has no entries.
     pub fn is_empty(&self) -> bool {
         self.entries.is_empty()
@@ -199,9 +508,47 @@ impl Playlist {
     }
 }
 
+/// Playlist file change reported by `Playlist::watch`.
+#[cfg(feature = "watch")]
+pub enum PlaylistChange {
+    /// The file was written and parsed again
+    Reloaded(Playlist),
+}
+
+#[cfg(feature = "watch")]
+impl Playlist {
+    /// Watches the playlist file at `path`, sending the reparsed playlist whenever it changes.
+    ///
+    /// The parent directory is watched so editors that save by replacing the file are
+    /// noticed too. Watching stops when the returned watcher is dropped.
+    pub fn watch(
+        path: &Path,
+        tx: std::sync::mpsc::Sender<PlaylistChange>,
+    ) -> notify::Result<notify::RecommendedWatcher> {
+        use notify::{EventKind, RecursiveMode, Watcher};
+
+        let file = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let dir = file.parent().unwrap_or(Path::new(".")).to_path_buf();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else { return };
+                let touched = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                    && event.paths.contains(&file);
+                if touched && let Ok(playlist) = Self::load(&file) {
+                    let _ = tx.send(PlaylistChange::Reloaded(playlist));
+                }
+            })?;
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+        Ok(watcher)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hvsc::fetch_bytes;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
 
     macro_rules! subsong_tests {
         ($($name:ident: $input:expr => ($path:expr, $subsong:expr),)*) => {
@@ -221,6 +568,60 @@ mod tests {
         invalid_subsong: "file.sid@abc" => ("file.sid@abc", None),
     }
 
+    #[test]
+    fn data_uri_entries_roundtrip_through_parse() {
+        let sid = crate::create_silent_sid();
+        let uri = encode_sid_as_data_uri(&sid);
+        let entry = PlaylistEntry::new(&uri).expect("entry");
+        assert!(entry.is_url());
+        assert_eq!(entry.display_name, "embedded.sid");
+
+        let mut loaded = entry.load().expect("load data URI");
+        loaded.md5.clone_from(&sid.md5);
+        assert_eq!(loaded, sid);
+
+        let short = uri.replacen("application/octet-stream", "", 1);
+        assert_eq!(fetch_bytes(&short).expect("decode"), sid.to_bytes());
+        assert!(fetch_bytes("data:text/plain,PSID").is_err());
+    }
+
+    #[test]
+    fn expansion_replaces_entry_in_place() {
+        let mut playlist = Playlist::new();
+        for name in ["a", "b", "tune", "d", "e"] {
+            playlist.add(&format!("/sids/{name}.sid"), None);
+        }
+        let expanded: Vec<PlaylistEntry> = (1..=3)
+            .map(|song| PlaylistEntry {
+                subsong: Some(song),
+                ..playlist.entries[2].clone()
+            })
+            .collect();
+        assert!(playlist.replace_with_expansion(2, expanded));
+
+        assert_eq!(playlist.len(), 7);
+        let sources: Vec<&str> = playlist.entries.iter().map(|e| e.source.as_str()).collect();
+        assert_eq!(
+            sources[1..6],
+            [
+                "/sids/b.sid",
+                "/sids/tune.sid",
+                "/sids/tune.sid",
+                "/sids/tune.sid",
+                "/sids/d.sid"
+            ]
+        );
+        let subsongs: Vec<Option<u16>> = playlist.entries.iter().map(|e| e.subsong).collect();
+        assert_eq!(
+            subsongs,
+            [None, None, Some(1), Some(2), Some(3), None, None]
+        );
+
+        assert!(!playlist.replace_with_expansion(7, vec![playlist.entries[0].clone()]));
+        assert!(!playlist.replace_with_expansion(0, Vec::new()));
+        assert_eq!(playlist.len(), 7);
+    }
+
     macro_rules! filename_tests {
         ($($name:ident: $input:expr => $expected:expr,)*) => {
             $(
@@ -238,4 +639,231 @@ mod tests {
         windows_path: "C:\\Music\\tune.sid" => "tune.sid",
         url_path: "https://example.com/music/tune.sid" => "tune.sid",
     }
+
+    fn playlist_of(sources: &[&str]) -> Playlist {
+        let mut playlist = Playlist::new();
+        for source in sources {
+            playlist.add(source, None);
+        }
+        playlist
+    }
+
+    #[test]
+    fn random_entry_skips_recent_indices() {
+        let playlist = playlist_of(&["a.sid", "b.sid", "c.sid"]);
+        for _ in 0..20 {
+            let (index, entry) = playlist.random_entry_excluding(&[0, 2]).unwrap();
+            assert_eq!((index, entry.source.as_str()), (1, "b.sid"));
+        }
+        assert!(playlist.random_entry_excluding(&[0, 1, 2]).is_none());
+        assert!(playlist.random_entry_excluding(&[]).is_some());
+        assert!(Playlist::new().random_entry_excluding(&[]).is_none());
+    }
+
+    #[test]
+    fn shuffle_keeps_entries_and_undo_restores_order() {
+        let names: Vec<String> = (0..20).map(|i| format!("{i}.sid")).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let mut playlist = playlist_of(&names);
+
+        playlist.shuffle(&mut StdRng::seed_from_u64(7));
+        let mut shuffled: Vec<&str> = playlist.entries.iter().map(|e| e.source.as_str()).collect();
+        assert_ne!(shuffled, names);
+        shuffled.sort_unstable();
+        let mut expected = names.clone();
+        expected.sort_unstable();
+        assert_eq!(shuffled, expected);
+
+        assert!(playlist.undo_shuffle());
+        let restored: Vec<&str> = playlist.entries.iter().map(|e| e.source.as_str()).collect();
+        assert_eq!(restored, names);
+        assert!(!playlist.undo_shuffle());
+    }
+
+    #[test]
+    fn editing_after_shuffle_drops_the_undo() {
+        let edits: [fn(&mut Playlist); 4] = [
+            |p| p.remove(0),
+            |p| p.move_entry(0, 2),
+            |p| {
+                p.add("new.sid", None);
+            },
+            |p| {
+                p.merge(&playlist_of(&["other.sid"]));
+            },
+        ];
+        for edit in edits {
+            let mut playlist = playlist_of(&["a.sid", "b.sid", "c.sid"]);
+            playlist.shuffle(&mut StdRng::seed_from_u64(7));
+            edit(&mut playlist);
+            assert!(!playlist.undo_shuffle());
+        }
+    }
+
+    #[test]
+    fn merge_identical_adds_nothing() {
+        let mut playlist = playlist_of(&["a.sid", "b.sid", "c.sid"]);
+        let other = playlist_of(&["a.sid", "b.sid", "c.sid"]);
+        assert_eq!(playlist.merge(&other), 0);
+        assert_eq!(playlist.len(), 3);
+    }
+
+    #[test]
+    fn merge_disjoint_doubles_size() {
+        let mut playlist = playlist_of(&["a.sid", "b.sid", "c.sid"]);
+        let other = playlist_of(&["d.sid", "e.sid", "f.sid"]);
+        assert_eq!(playlist.merge(&other), 3);
+        assert_eq!(playlist.len(), 6);
+    }
+
+    fn display_names(playlist: &Playlist) -> Vec<&str> {
+        playlist
+            .entries
+            .iter()
+            .map(|e| e.display_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn sort_ignores_leading_articles() {
+        let mut playlist = playlist_of(&["The Ace", "Beta", "An Alpha"]);
+        playlist.sort(SortKey::DisplayName {
+            ignore_articles: true,
+        });
+        assert_eq!(display_names(&playlist), ["The Ace", "An Alpha", "Beta"]);
+
+        playlist.sort(SortKey::DisplayName {
+            ignore_articles: false,
+        });
+        assert_eq!(display_names(&playlist), ["An Alpha", "Beta", "The Ace"]);
+        assert_eq!(strip_leading_article("THE END"), "END");
+        assert_eq!(strip_leading_article("Anthem"), "Anthem");
+    }
+
+    macro_rules! move_entry_tests {
+        ($($name:ident: ($from:expr, $to:expr) => $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let mut playlist = Playlist::from_entries(
+                        ["a", "b", "c", "d", "e"]
+                            .iter()
+                            .filter_map(|s| PlaylistEntry::new(s))
+                            .collect(),
+                    );
+                    playlist.move_entry($from, $to);
+                    let order: Vec<&str> =
+                        playlist.entries.iter().map(|e| e.source.as_str()).collect();
+                    assert_eq!(order, $expected);
+                }
+            )*
+        };
+    }
+
+    move_entry_tests! {
+        move_first_up_is_noop: (0, 0) => ["a", "b", "c", "d", "e"],
+        move_last_down_is_noop: (4, 5) => ["a", "b", "c", "d", "e"],
+        move_second_to_fourth: (1, 3) => ["a", "c", "d", "b", "e"],
+        move_out_of_range_is_noop: (7, 0) => ["a", "b", "c", "d", "e"],
+    }
+
+    #[test]
+    fn portable_playlist_survives_directory_move() {
+        let root = std::env::temp_dir().join(format!("crabsid-portable-{}", std::process::id()));
+        let original = root.join("original");
+        let moved = root.join("moved");
+        fs::create_dir_all(original.join("music")).unwrap();
+        fs::write(original.join("music").join("tune.sid"), b"").unwrap();
+
+        let mut playlist = Playlist::new();
+        let tune = original.join("music").join("tune.sid");
+        playlist.add(&tune.to_string_lossy(), Some(2));
+        playlist.add("https://example.com/remote.sid", None);
+        let list = original.join("list.m3u");
+        playlist.save_relative(&original, &list).unwrap();
+
+        let content = fs::read_to_string(&list).unwrap();
+        let expected_path = Path::new("music").join("tune.sid");
+        assert_eq!(
+            content,
+            format!(
+                "{}@2\nhttps://example.com/remote.sid\n",
+                expected_path.display()
+            )
+        );
+
+        fs::rename(&original, &moved).unwrap();
+        let loaded = Playlist::load(moved.join("list.m3u")).unwrap();
+        let source = Path::new(&loaded.entries[0].source);
+        assert_eq!(source, moved.join("music").join("tune.sid"));
+        assert!(source.exists());
+        assert_eq!(loaded.entries[0].subsong, Some(2));
+        assert_eq!(loaded.entries[1].source, "https://example.com/remote.sid");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn watch_reports_reloaded_playlist() {
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let dir = std::env::temp_dir().join(format!("crabsid-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let list = dir.join("list.m3u");
+        fs::write(&list, "a.sid\n").unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let _watcher = Playlist::watch(&list, tx).unwrap();
+        fs::write(&list, "a.sid\nb.sid\n").unwrap();
+
+        // Writing may be reported in several steps (truncate, then data)
+        let deadline = std::time::Instant::now() + Duration::from_millis(500);
+        let mut entries = 0;
+        while entries != 2 {
+            let timeout = deadline.saturating_duration_since(std::time::Instant::now());
+            let PlaylistChange::Reloaded(playlist) =
+                rx.recv_timeout(timeout).expect("reload event");
+            entries = playlist.len();
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn xspf_roundtrip_preserves_fields() {
+        let mut playlist = Playlist::new();
+        playlist.add("https://example.com/Commando.sid", Some(2));
+        playlist.entries[0].display_name = "Commando & Co".to_string();
+        playlist.entries[0].xspf_comment = Some("Written in <one> night".to_string());
+        playlist.add("/music/Delta.sid", None);
+
+        let xml = playlist.to_xspf(None).unwrap();
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+
+        let loaded = Playlist::load_xspf(&xml).unwrap();
+        assert_eq!(loaded.len(), 2);
+        let first = &loaded.entries[0];
+        assert_eq!(first.source, "https://example.com/Commando.sid");
+        assert_eq!(first.subsong, Some(2));
+        assert_eq!(first.display_name, "Commando & Co");
+        assert_eq!(
+            first.xspf_comment.as_deref(),
+            Some("Written in <one> night")
+        );
+        assert_eq!(loaded.entries[1].source, "/music/Delta.sid");
+        assert_eq!(loaded.entries[1].xspf_comment, None);
+    }
+
+    #[test]
+    fn malformed_xspf_is_invalid_data() {
+        for xml in [
+            "<playlist><trackList><track></playlist>",
+            "<playlist><trackList>",
+        ] {
+            let err = Playlist::load_xspf(xml).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
 }
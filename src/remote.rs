@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Mikael Lund
+
+//! WebSocket server for remote control (`--ws-port`).
+//!
+//! Each client gets its own thread. JSON commands are forwarded to the TUI
+//! over a channel, and the status published by the TUI is broadcast to every
+//! client once per second.
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, ErrorKind};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tungstenite::{Message, WebSocket};
+
+const BROADCAST_INTERVAL: Duration = Duration::from_secs(1);
+/// Read timeout so client threads can interleave status broadcasts
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+const UNKNOWN_CMD: &str = r#"{"error":"unknown_cmd"}"#;
+
+/// Command sent by a WebSocket client, e.g. `{"cmd":"next_song"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum RemoteCommand {
+    PlayPause,
+    NextSong,
+    /// Loads a SID file from a path or URL
+    Load {
+        url: String,
+    },
+}
+
+/// Status frame broadcast to clients.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RemoteStatus {
+    pub title: String,
+    pub song: u16,
+    pub paused: bool,
+    /// Elapsed play time of the current song in seconds
+    pub elapsed: f64,
+}
+
+/// TUI side of the server: receives commands and publishes status.
+pub struct RemoteHandle {
+    commands: Receiver<RemoteCommand>,
+    status: Arc<Mutex<RemoteStatus>>,
+}
+
+impl RemoteHandle {
+    /// Returns the next pending command, if any.
+    pub fn try_recv(&self) -> Option<RemoteCommand> {
+        self.commands.try_recv().ok()
+    }
+
+    /// Publishes the status broadcast to clients.
+    pub fn publish(&self, status: RemoteStatus) {
+        if let Ok(mut current) = self.status.lock() {
+            *current = status;
+        }
+    }
+}
+
+/// Binds to `127.0.0.1:<port>` and spawns the accept loop.
+pub fn spawn(port: u16) -> io::Result<RemoteHandle> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    let (tx, rx) = mpsc::channel();
+    let status = Arc::new(Mutex::new(RemoteStatus::default()));
+
+    let shared = Arc::clone(&status);
+    thread::Builder::new()
+        .name("ws-accept".to_string())
+        .spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                let status = Arc::clone(&shared);
+                let _ = thread::Builder::new()
+                    .name("ws-client".to_string())
+                    .spawn(move || serve_client(stream, &tx, &status));
+            }
+        })?;
+
+    Ok(RemoteHandle {
+        commands: rx,
+        status,
+    })
+}
+
+/// Handles one client until it disconnects.
+fn serve_client(stream: TcpStream, commands: &Sender<RemoteCommand>, status: &Mutex<RemoteStatus>) {
+    let Ok(mut ws) = tungstenite::accept(stream) else {
+        return;
+    };
+    if ws.get_ref().set_read_timeout(Some(READ_TIMEOUT)).is_err() {
+        return;
+    }
+
+    let mut last_broadcast = Instant::now();
+    loop {
+        match ws.read() {
+            Ok(Message::Text(text)) => {
+                if let Some(reply) = handle_text(text.as_str(), commands)
+                    && ws.send(Message::text(reply)).is_err()
+                {
+                    return;
+                }
+            }
+            Ok(Message::Close(_)) => return,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => return,
+        }
+
+        if last_broadcast.elapsed() >= BROADCAST_INTERVAL {
+            last_broadcast = Instant::now();
+            if !send_status(&mut ws, status) {
+                return;
+            }
+        }
+    }
+}
+
+/// Sends the current status frame. Returns false if the client is gone.
+fn send_status(ws: &mut WebSocket<TcpStream>, status: &Mutex<RemoteStatus>) -> bool {
+    let status = status.lock().map(|s| s.clone()).unwrap_or_default();
+    ws.send(Message::text(status_json(&status))).is_ok()
+}
+
+/// Parses a client message and forwards it. Returns an error reply for unknown commands.
+fn handle_text(text: &str, commands: &Sender<RemoteCommand>) -> Option<String> {
+    match serde_json::from_str::<RemoteCommand>(text) {
+        Ok(cmd) => {
+            let _ = commands.send(cmd);
+            None
+        }
+        Err(_) => Some(UNKNOWN_CMD.to_string()),
+    }
+}
+
+fn status_json(status: &RemoteStatus) -> String {
+    serde_json::to_string(status).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_broadcast_format() {
+        let status = RemoteStatus {
+            title: "Commando".to_string(),
+            song: 2,
+            paused: false,
+            elapsed: 45.0,
+        };
+        assert_eq!(
+            status_json(&status),
+            r#"{"title":"Commando","song":2,"paused":false,"elapsed":45.0}"#
+        );
+    }
+
+    #[test]
+    fn unknown_command_returns_error() {
+        let (tx, rx) = mpsc::channel();
+        assert_eq!(
+            handle_text(r#"{"cmd":"self_destruct"}"#, &tx).as_deref(),
+            Some(UNKNOWN_CMD)
+        );
+        assert_eq!(handle_text("not json", &tx).as_deref(), Some(UNKNOWN_CMD));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn known_commands_are_forwarded() {
+        let (tx, rx) = mpsc::channel();
+        assert_eq!(handle_text(r#"{"cmd":"play_pause"}"#, &tx), None);
+        assert_eq!(
+            handle_text(r#"{"cmd":"load","url":"https://example.com/a.sid"}"#, &tx),
+            None
+        );
+        assert_eq!(rx.try_recv().ok(), Some(RemoteCommand::PlayPause));
+        assert_eq!(
+            rx.try_recv().ok(),
+            Some(RemoteCommand::Load {
+                url: "https://example.com/a.sid".to_string()
+            })
+        );
+    }
+}
@@ -1,9 +1,12 @@
 // SPDX-License-Identifier: MIT
 // Copyright (c) 2026 Mikael Lund
 
+use crate::hvsc::fetch_bytes;
 use md5::{Digest, Md5};
+use residfp::ChipModel;
+use std::fmt;
 use std::fs;
-use std::io;
+use std::io::{self, Read};
 use std::path::Path;
 
 // PSID/RSID header field offsets (big-endian format)
@@ -22,20 +25,142 @@ const OFFSET_RELEASED: usize = 0x56;
 const OFFSET_FLAGS: usize = 0x76;
 const OFFSET_SECOND_SID: usize = 0x7A;
 const OFFSET_THIRD_SID: usize = 0x7B;
+/// v2+ header size (v1 headers end at `OFFSET_FLAGS`)
+const HEADER_V2_SIZE: usize = 0x7C;
+const STRING_FIELD_SIZE: usize = 0x20;
+/// End of the area reserved for the PSID driver
+const DRIVER_AREA_END: u16 = 0x07E8;
+/// Maximum number of songs allowed by the PSID spec
+const MAX_SONGS: u16 = 256;
+/// SIDs with a chip model in the header flags (PSID v4 supports three)
+const MAX_SIDS: usize = 3;
+const C64_RAM_SIZE: usize = 0x10000;
+/// CIA 1 timer A/B latch registers ($DC04-$DC07)
+const CIA_TIMER_FIRST: u16 = 0xDC04;
+const CIA_TIMER_LAST: u16 = 0xDC07;
+/// CIA timer latch values giving one interrupt per PAL or NTSC frame
+const PAL_FRAME_TIMER: u16 = 0x4025;
+const NTSC_FRAME_TIMER: u16 = 0x4295;
+/// Distance from a frame timer value still counted as a match
+const FRAME_TIMER_TOLERANCE: u16 = 0x40;
+/// Weight of a bare 16-bit constant in the data, relative to a timer write in code
+const RAW_CONSTANT_WEIGHT: f32 = 0.5;
+
+/// Video standard suggested by `SidFile::detect_clock_from_data`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockType {
+    Pal,
+    Ntsc,
+    Unknown,
+}
+
+impl fmt::Display for ClockType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Pal => "PAL",
+            Self::Ntsc => "NTSC",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+/// Guessed clock of a tune and how much of the evidence agrees (0.0-1.0).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockHint {
+    pub clock: ClockType,
+    pub confidence: f32,
+}
+
+/// Classifies a CIA timer value as a PAL or NTSC frame rate.
+const fn frame_timer_clock(value: u16) -> ClockType {
+    if value.abs_diff(PAL_FRAME_TIMER) <= FRAME_TIMER_TOLERANCE {
+        ClockType::Pal
+    } else if value.abs_diff(NTSC_FRAME_TIMER) <= FRAME_TIMER_TOLERANCE {
+        ClockType::Ntsc
+    } else {
+        ClockType::Unknown
+    }
+}
+
+/// Finds 16-bit values written to a CIA timer latch with immediate loads, e.g.
+/// `LDA #$25 / STA $DC04 / LDA #$40 / STA $DC05`. X and Y register pairs count too.
+fn cia_timer_writes(data: &[u8]) -> Vec<u16> {
+    // Low byte last written to the timer A and timer B latches
+    let mut low_bytes: [Option<u8>; 2] = [None; 2];
+    let mut values = Vec::new();
+    for window in data.windows(5) {
+        let [load, value, store, lo, hi] = *window else {
+            continue;
+        };
+        let same_register = matches!((load, store), (0xA9, 0x8D) | (0xA2, 0x8E) | (0xA0, 0x8C));
+        let target = u16::from_le_bytes([lo, hi]);
+        if !same_register || !(CIA_TIMER_FIRST..=CIA_TIMER_LAST).contains(&target) {
+            continue;
+        }
+        let timer = usize::from((target - CIA_TIMER_FIRST) / 2);
+        if target % 2 == 0 {
+            low_bytes[timer] = Some(value);
+        } else if let Some(low) = low_bytes[timer].take() {
+            values.push(u16::from_le_bytes([low, value]));
+        }
+    }
+    values
+}
+
+/// Finds little-endian 16-bit constants close to a PAL or NTSC frame timer value.
+fn raw_frame_timer_constants(data: &[u8]) -> Vec<u16> {
+    data.windows(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .filter(|&value| frame_timer_clock(value) != ClockType::Unknown)
+        .collect()
+}
+
+/// Potential problem found by [`SidFile::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    /// Load address overlaps the PSID driver area below $07E8
+    LoadInDriverArea(u16),
+    /// Init address lies outside the loaded data
+    InitOutsideData { init: u16, start: u16, end: usize },
+    /// More songs than the PSID format allows
+    TooManySongs(u16),
+    /// Play and init share an address, which is unusual for PSID tunes
+    PlayEqualsInit(u16),
+    /// Data extends past the end of C64 memory
+    DataTooLarge { end: usize },
+}
+
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LoadInDriverArea(addr) => {
+                write!(f, "load address ${addr:04X} overlaps PSID driver area")
+            }
+            Self::InitOutsideData { init, start, end } => write!(
+                f,
+                "init address ${init:04X} outside data ${start:04X}-${:04X}",
+                end.saturating_sub(1)
+            ),
+            Self::TooManySongs(songs) => write!(f, "{songs} songs exceeds maximum of {MAX_SONGS}"),
+            Self::PlayEqualsInit(addr) => write!(f, "play and init share address ${addr:04X}"),
+            Self::DataTooLarge { end } => {
+                write!(f, "data ends at ${end:05X}, beyond 64K of C64 RAM")
+            }
+        }
+    }
+}
 
 /// Parsed PSID/RSID file containing a C64 SID tune.
 ///
 /// The PSID format stores 6502 machine code along with metadata
 /// (title, author, release info) and playback parameters.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SidFile {
     /// File format identifier ("PSID" or "RSID")
-    #[allow(dead_code)] // Parsed for format validation
     pub magic: String,
     /// PSID version (1, 2, 3, or 4)
     pub version: u16,
     /// Offset to binary data in original file
-    #[allow(dead_code)] // Parsed for completeness
     pub data_offset: u16,
     /// C64 memory address where data is loaded
     pub load_address: u16,
@@ -48,7 +173,6 @@ pub struct SidFile {
     /// Default song to play (1-indexed)
     pub start_song: u16,
     /// Per-song timing flags (bit set = CIA, clear = VBI)
-    #[allow(dead_code)] // For future CIA timing support
     pub speed: u32,
     /// Song title from file header
     pub name: String,
@@ -62,6 +186,8 @@ pub struct SidFile {
     pub data: Vec<u8>,
     /// MD5 hash of original file (for Songlengths lookup)
     pub md5: String,
+    /// CRC32 of `data`, computed by `parse`
+    pub data_crc32: u32,
     /// v3+ second SID address (e.g., $D420, $D500)
     pub second_sid_address: Option<u16>,
     /// v3+ third SID address
@@ -71,10 +197,28 @@ pub struct SidFile {
 impl SidFile {
     /// Loads and parses a PSID/RSID file from disk.
     pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
-        let bytes = fs::read(path)?;
+        Self::read(&mut fs::File::open(path)?)
+    }
+
+    /// Reads and parses a PSID/RSID file from any reader (memory, network stream, ...).
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
         Self::parse(&bytes)
     }
 
+    /// Downloads and parses a tune from an http(s), file:// or data: URL.
+    pub fn from_url(url: &str) -> io::Result<Self> {
+        fetch_bytes(url)
+            .and_then(|bytes| Self::parse(&bytes))
+            .map_err(|e| io::Error::new(e.kind(), format!("{url}: {e}")))
+    }
+
+    /// Parses PSID/RSID data from a byte slice (same as `parse`).
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        Self::parse(bytes)
+    }
+
     /// Parses PSID/RSID data from a byte slice.
     pub fn parse(bytes: &[u8]) -> io::Result<Self> {
         if bytes.len() < HEADER_MIN_SIZE {
@@ -139,6 +283,8 @@ impl SidFile {
             data.drain(..2);
         }
 
+        let data_crc32 = crc32fast::hash(&data);
+
         Ok(Self {
             magic,
             version,
@@ -155,11 +301,113 @@ impl SidFile {
             flags,
             data,
             md5,
+            data_crc32,
             second_sid_address,
             third_sid_address,
         })
     }
 
+    /// Returns the CRC32 of the data section, a quick check independent of the file MD5.
+    pub fn data_checksum(&self) -> u32 {
+        crc32fast::hash(&self.data)
+    }
+
+    /// Reads the file at `path` and checks the CRC32 of its raw data section
+    /// against the one stored at parse time, returning the checksum. A mismatch
+    /// means the data was not read in full.
+    pub fn verify_integrity(path: &Path) -> io::Result<u32> {
+        let bytes = fs::read(path)?;
+        let sid = Self::parse(&bytes)?;
+        let raw = &bytes[bytes.len() - sid.data.len()..];
+        let crc = crc32fast::hash(raw);
+        if crc != sid.data_crc32 || sid.data_checksum() != sid.data_crc32 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("data CRC32 {crc:08X} does not match {:08X}", sid.data_crc32),
+            ));
+        }
+        Ok(crc)
+    }
+
+    /// Writes the tune back to disk in PSID/RSID format.
+    pub fn write_to_path(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    /// Serializes the tune to PSID/RSID bytes, the inverse of [`Self::parse`].
+    ///
+    /// The header load address is written as 0 and the real address is
+    /// prepended to the data (as HVSC does), so an unmodified HVSC file
+    /// round-trips with an identical MD5. The header is `data_offset` bytes,
+    /// grown if needed to fit the version. The version is raised to 3 (or 4)
+    /// when a second (or third) SID address is set.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let version = match (self.second_sid_address, self.third_sid_address) {
+            (_, Some(_)) => self.version.max(4),
+            (Some(_), None) => self.version.max(3),
+            _ => self.version,
+        };
+        let min_header_size = if version >= 2 {
+            HEADER_V2_SIZE
+        } else {
+            OFFSET_FLAGS
+        };
+        let header_size = usize::from(self.data_offset).max(min_header_size);
+
+        let mut bytes = vec![0u8; header_size];
+        bytes[..4].copy_from_slice(&self.magic.as_bytes()[..4.min(self.magic.len())]);
+        bytes[OFFSET_VERSION..OFFSET_VERSION + 2].copy_from_slice(&version.to_be_bytes());
+        #[allow(clippy::cast_possible_truncation)] // at most max(data_offset, 0x7C)
+        let data_offset = header_size as u16;
+        bytes[OFFSET_DATA..OFFSET_DATA + 2].copy_from_slice(&data_offset.to_be_bytes());
+        // Header load address stays 0: the real one is prepended to the data
+        bytes[OFFSET_INIT..OFFSET_INIT + 2].copy_from_slice(&self.init_address.to_be_bytes());
+        bytes[OFFSET_PLAY..OFFSET_PLAY + 2].copy_from_slice(&self.play_address.to_be_bytes());
+        bytes[OFFSET_SONGS..OFFSET_SONGS + 2].copy_from_slice(&self.songs.to_be_bytes());
+        bytes[OFFSET_START..OFFSET_START + 2].copy_from_slice(&self.start_song.to_be_bytes());
+        bytes[OFFSET_SPEED..OFFSET_SPEED + 4].copy_from_slice(&self.speed.to_be_bytes());
+        write_string(&mut bytes[OFFSET_NAME..OFFSET_AUTHOR], &self.name);
+        write_string(&mut bytes[OFFSET_AUTHOR..OFFSET_RELEASED], &self.author);
+        write_string(&mut bytes[OFFSET_RELEASED..OFFSET_FLAGS], &self.released);
+
+        if version >= 2 {
+            bytes[OFFSET_FLAGS..OFFSET_FLAGS + 2].copy_from_slice(&self.flags.to_be_bytes());
+            bytes[OFFSET_SECOND_SID] = encode_sid_address(self.second_sid_address);
+            bytes[OFFSET_THIRD_SID] = encode_sid_address(self.third_sid_address);
+        }
+
+        bytes.extend_from_slice(&self.load_address.to_le_bytes());
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// Checks the header for common problems. An empty result means no warnings.
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let mut warnings = Vec::new();
+        let data_end = usize::from(self.load_address) + self.data.len();
+
+        if self.load_address < DRIVER_AREA_END {
+            warnings.push(ValidationWarning::LoadInDriverArea(self.load_address));
+        }
+        if self.init_address < self.load_address || usize::from(self.init_address) >= data_end {
+            warnings.push(ValidationWarning::InitOutsideData {
+                init: self.init_address,
+                start: self.load_address,
+                end: data_end,
+            });
+        }
+        if self.songs > MAX_SONGS {
+            warnings.push(ValidationWarning::TooManySongs(self.songs));
+        }
+        if self.play_address != 0 && self.play_address == self.init_address {
+            warnings.push(ValidationWarning::PlayEqualsInit(self.play_address));
+        }
+        if data_end > C64_RAM_SIZE {
+            warnings.push(ValidationWarning::DataTooLarge { end: data_end });
+        }
+        warnings
+    }
+
     /// Returns true if the tune should use PAL timing (50Hz).
     ///
     /// Most European C64 software used PAL; NTSC (60Hz) was common in North America.
@@ -177,7 +425,6 @@ impl SidFile {
     ///
     /// Most tunes sync to the vertical blank interrupt (50/60Hz), but some
     /// use CIA timers for custom playback rates.
-    #[allow(dead_code)] // For future CIA timing support
     pub const fn uses_cia_timing(&self, song: u16) -> bool {
         if song == 0 || song > 32 {
             return false;
@@ -189,8 +436,11 @@ impl SidFile {
     ///
     /// RSID files and interrupt-driven tunes need CIA/VIC emulation
     /// that this player doesn't provide, so they may fail to initialize.
+    ///
+    /// PSID files with CIA-timed subsongs load fine; the player rejects those
+    /// subsongs when they start playing.
     pub fn requires_full_emulation(&self) -> bool {
-        self.magic == "RSID" || self.play_address == 0 || self.speed != 0
+        self.magic == "RSID" || self.play_address == 0
     }
 
     /// Returns the number of SID chips used (1, 2, or 3).
@@ -205,7 +455,7 @@ impl SidFile {
     /// Returns the preferred chip model for the nth SID (0-indexed).
     /// Bits 4-5 of flags: first SID, bits 6-7: second SID, bits 8-9: third SID.
     pub fn chip_model_for_sid(&self, index: usize) -> Option<u8> {
-        if self.version < 2 {
+        if self.version < 2 || index >= MAX_SIDS {
             return None;
         }
         let shift = 4 + index * 2;
@@ -213,6 +463,119 @@ impl SidFile {
         // 0=unknown, 1=6581, 2=8580, 3=6581+8580
         if model == 0 { None } else { Some(model as u8) }
     }
+
+    /// Returns the header's chip model for the nth SID as "6581", "8580",
+    /// "6581+8580" (either) or "Unknown" (also for indices past the third SID).
+    pub fn chip_model_name_for_sid(&self, index: usize) -> &'static str {
+        match self.chip_model_for_sid(index) {
+            Some(1) => "6581",
+            Some(2) => "8580",
+            Some(3) => "6581+8580",
+            _ => "Unknown",
+        }
+    }
+
+    /// Returns the file's preferred chip model for a subsong.
+    ///
+    /// PSID only stores one model per SID, so this is the first SID's model
+    /// for every subsong; it exists as the hook for per-subsong metadata.
+    pub fn chip_model_for_subsong(&self, _song: u16) -> Option<ChipModel> {
+        match self.chip_model_for_sid(0) {
+            Some(1) => Some(ChipModel::Mos6581),
+            Some(2) => Some(ChipModel::Mos8580),
+            _ => None,
+        }
+    }
+
+    /// Guesses PAL or NTSC from the CIA timer values a tune sets up, for v1
+    /// headers that have no clock flag. Returns None if the data holds no
+    /// CIA timer writes and no frame timer constants.
+    ///
+    /// Timer values written in code weigh double those only found as data.
+    /// Stray byte pairs easily look like frame timer constants, so this is
+    /// for display only; playback goes by `detect_clock_from_timer_writes`.
+    pub fn detect_clock_from_data(data: &[u8]) -> Option<ClockHint> {
+        clock_hint(&cia_timer_writes(data), &raw_frame_timer_constants(data))
+    }
+
+    /// Like `detect_clock_from_data`, but only counts values the code writes
+    /// to a CIA timer. Returns None if there are no such writes.
+    pub fn detect_clock_from_timer_writes(data: &[u8]) -> Option<ClockHint> {
+        clock_hint(&cia_timer_writes(data), &[])
+    }
+}
+
+/// Weighs timer values written in code against raw constants found in the data.
+fn clock_hint(written: &[u16], constants: &[u16]) -> Option<ClockHint> {
+    if written.is_empty() && constants.is_empty() {
+        return None;
+    }
+
+    let (mut pal, mut ntsc, mut other) = (0.0f32, 0.0f32, 0.0f32);
+    let weighted = written
+        .iter()
+        .map(|&v| (v, 1.0))
+        .chain(constants.iter().map(|&v| (v, RAW_CONSTANT_WEIGHT)));
+    for (value, weight) in weighted {
+        match frame_timer_clock(value) {
+            ClockType::Pal => pal += weight,
+            ClockType::Ntsc => ntsc += weight,
+            ClockType::Unknown => other += weight,
+        }
+    }
+
+    let total = pal + ntsc + other;
+    let (clock, votes) = if pal > ntsc {
+        (ClockType::Pal, pal)
+    } else if ntsc > pal {
+        (ClockType::Ntsc, ntsc)
+    } else {
+        (ClockType::Unknown, 0.0)
+    };
+    Some(ClockHint {
+        clock,
+        confidence: votes / total,
+    })
+}
+
+/// Decodes the v2+ header flags into one human-readable line per bit group.
+pub fn format_flags(flags: u16, version: u16) -> Vec<String> {
+    if version < 2 {
+        return vec!["none (v1 header)".to_string()];
+    }
+    let two_bits =
+        |shift: u16, names: [&'static str; 4]| names[usize::from((flags >> shift) & 0x03)];
+    let models = ["Unknown", "6581", "8580", "6581+8580"];
+    let mut lines = vec![
+        format!(
+            "Player:      {}",
+            if flags & 0x01 != 0 {
+                "Compute! MUS data"
+            } else {
+                "built-in"
+            }
+        ),
+        format!(
+            "Bit 1:       {}",
+            if flags & 0x02 != 0 {
+                "PlaySID samples / C64 BASIC"
+            } else {
+                "C64 compatible"
+            }
+        ),
+        format!(
+            "Clock:       {}",
+            two_bits(2, ["Unknown", "PAL", "NTSC", "PAL and NTSC"])
+        ),
+        format!("SID 1 model: {}", two_bits(4, models)),
+    ];
+    if version >= 3 {
+        lines.push(format!("SID 2 model: {}", two_bits(6, models)));
+    }
+    if version >= 4 {
+        lines.push(format!("SID 3 model: {}", two_bits(8, models)));
+    }
+    lines
 }
 
 fn read_u16_be(bytes: &[u8]) -> u16 {
@@ -233,6 +596,19 @@ fn parse_sid_address(byte: u8) -> Option<u16> {
     }
 }
 
+/// Inverse of `parse_sid_address`: $D420 -> 0x42, None -> 0x00.
+fn encode_sid_address(address: Option<u16>) -> u8 {
+    address.map_or(0, |addr| ((addr >> 4) & 0xFF) as u8)
+}
+
+/// Writes a zero-padded Latin-1 string, truncated to the field size.
+fn write_string(field: &mut [u8], s: &str) {
+    debug_assert_eq!(field.len(), STRING_FIELD_SIZE);
+    for (dst, c) in field.iter_mut().zip(s.chars()) {
+        *dst = u8::try_from(u32::from(c)).unwrap_or(b'?');
+    }
+}
+
 /// Reads a null-terminated Latin-1 string (ISO-8859-1, used in SID headers).
 fn read_string(bytes: &[u8]) -> String {
     let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
@@ -247,6 +623,7 @@ fn read_string(bytes: &[u8]) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     macro_rules! test_sid {
         () => {
@@ -266,12 +643,34 @@ mod tests {
                 flags: 0,
                 data: vec![],
                 md5: String::new(),
+                data_crc32: 0,
                 second_sid_address: None,
                 third_sid_address: None,
             }
         };
     }
 
+    #[test]
+    fn loads_from_file_urls() {
+        let root = std::env::temp_dir().join(format!("crabsid-url-{}", std::process::id()));
+        let dir = root.join("MUSICIANS/H/Hubbard_Rob");
+        fs::create_dir_all(&dir).unwrap();
+        let mut sid = test_sid!();
+        sid.name = "Delta".to_string();
+        sid.data = vec![0x60; 4];
+        fs::write(dir.join("Delta.sid"), sid.to_bytes()).unwrap();
+
+        let base_url = format!("file://{}", root.display());
+        let url = format!("{base_url}/MUSICIANS/H/Hubbard_Rob/Delta.sid");
+        assert_eq!(SidFile::from_url(&url).unwrap().name, "Delta");
+
+        let missing = format!("{base_url}/MUSICIANS/H/Hubbard_Rob/Missing.sid");
+        let err = SidFile::from_url(&missing).unwrap_err();
+        assert!(err.to_string().starts_with(&missing), "{err}");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn parse_sid_address_none_for_zero() {
         assert_eq!(parse_sid_address(0x00), None);
@@ -300,6 +699,253 @@ mod tests {
         assert_eq!(sid.chip_model_for_sid(1), Some(2));
     }
 
+    #[test]
+    fn read_from_cursor_matches_load() {
+        let path = "tests/Hexadecimal_2SID.sid";
+        let loaded = SidFile::load(path).expect("load 2SID file");
+        let mut cursor = io::Cursor::new(fs::read(path).expect("read 2SID file"));
+        let read = SidFile::read(&mut cursor).expect("read 2SID file");
+        assert_eq!(read, loaded);
+    }
+
+    #[test]
+    fn write_roundtrip_preserves_fields_and_md5() {
+        let sid = SidFile::load("tests/Hexadecimal_2SID.sid").expect("load 2SID file");
+        let path =
+            std::env::temp_dir().join(format!("crabsid-roundtrip-{}.sid", std::process::id()));
+        sid.write_to_path(&path).expect("write SID file");
+        let reparsed = SidFile::load(&path).expect("reload SID file");
+        let _ = fs::remove_file(&path);
+        assert_eq!(reparsed, sid);
+    }
+
+    #[test]
+    fn to_bytes_reproduces_hvsc_file_md5() {
+        let path = "tests/Hexadecimal_2SID.sid";
+        let sid = SidFile::load(path).expect("load 2SID file");
+        let bytes = sid.to_bytes();
+        assert_eq!(bytes, fs::read(path).expect("read 2SID file"));
+        assert_eq!(
+            format!("{:x}", Md5::digest(&bytes)),
+            "a6dc53b4c0b40190aa2ca3c2eb28ee18"
+        );
+    }
+
+    #[test]
+    fn write_reflects_modified_author() {
+        let mut sid = SidFile::load("tests/Hexadecimal_2SID.sid").expect("load 2SID file");
+        sid.author = "Jeroen Tel".to_string();
+        let path = std::env::temp_dir().join(format!("crabsid-author-{}.sid", std::process::id()));
+        sid.write_to_path(&path).expect("write SID file");
+        let reparsed = SidFile::load(&path).expect("reload SID file");
+        let _ = fs::remove_file(&path);
+        assert_eq!(reparsed.author, "Jeroen Tel");
+        assert_eq!(reparsed.name, sid.name);
+        assert_eq!(reparsed.data, sid.data);
+        assert_ne!(reparsed.md5, sid.md5);
+    }
+
+    #[test]
+    fn write_bumps_version_for_extra_sids() {
+        let mut sid = test_sid!();
+        sid.version = 2;
+        sid.second_sid_address = Some(0xD420);
+        sid.third_sid_address = Some(0xD500);
+        let path = std::env::temp_dir().join(format!("crabsid-version-{}.sid", std::process::id()));
+        sid.write_to_path(&path).expect("write SID file");
+        let reparsed = SidFile::load(&path).expect("reload SID file");
+        let _ = fs::remove_file(&path);
+        assert_eq!(reparsed.version, 4);
+        assert_eq!(reparsed.second_sid_address, Some(0xD420));
+        assert_eq!(reparsed.third_sid_address, Some(0xD500));
+    }
+
+    /// Header strings as `read_string` returns them: trimmed, no NUL, at most 32 chars.
+    fn header_string() -> impl Strategy<Value = String> {
+        "([A-Za-z0-9()]([A-Za-z0-9() ]{0,30}[A-Za-z0-9()])?)?"
+    }
+
+    /// Generates SID files whose fields are consistent with their version.
+    fn valid_sid() -> impl Strategy<Value = SidFile> {
+        let header = (
+            prop_oneof![Just("PSID"), Just("RSID")],
+            1u16..=4,
+            any::<[u16; 5]>(),
+            any::<u32>(),
+            any::<u16>(),
+            1u8..=0xFF,
+            1u8..=0xFF,
+        );
+        let body = (
+            header_string(),
+            header_string(),
+            header_string(),
+            proptest::collection::vec(any::<u8>(), 0..512),
+        );
+        (header, body).prop_map(
+            |(
+                (magic, version, [load, init, play, songs, start], speed, flags, sid2, sid3),
+                (name, author, released, data),
+            )| SidFile {
+                magic: magic.to_string(),
+                version,
+                data_offset: if version >= 2 { 0x7C } else { 0x76 },
+                load_address: load,
+                init_address: init,
+                play_address: play,
+                songs,
+                start_song: start,
+                speed,
+                name,
+                author,
+                released,
+                flags: if version >= 2 { flags } else { 0 },
+                data_crc32: crc32fast::hash(&data),
+                data,
+                md5: String::new(),
+                second_sid_address: (version >= 3).then(|| parse_sid_address(sid2)).flatten(),
+                third_sid_address: (version >= 4).then(|| parse_sid_address(sid3)).flatten(),
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn to_bytes_roundtrips_through_parse(sid in valid_sid()) {
+            let bytes = sid.to_bytes();
+            prop_assert_eq!(bytes.len(), usize::from(sid.data_offset) + 2 + sid.data.len());
+            let mut reparsed = SidFile::parse(&bytes).expect("parse serialized SID");
+            reparsed.md5.clone_from(&sid.md5);
+            prop_assert_eq!(reparsed, sid);
+        }
+    }
+
+    #[test]
+    fn data_checksum_is_crc32_of_data_section() {
+        let mut sid = test_sid!();
+        sid.data = b"123456789".to_vec();
+        let parsed = SidFile::parse(&sid.to_bytes()).expect("parse");
+        // Standard CRC-32 check value for "123456789"
+        assert_eq!(parsed.data_crc32, 0xCBF4_3926);
+        assert_eq!(parsed.data_checksum(), crc32fast::hash(b"123456789"));
+
+        let path = std::env::temp_dir().join(format!("crabsid-crc-{}.sid", std::process::id()));
+        fs::write(&path, sid.to_bytes()).expect("write");
+        let verified = SidFile::verify_integrity(&path);
+        fs::remove_file(&path).ok();
+        assert_eq!(verified.expect("verify"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn encode_sid_address_inverts_parse() {
+        for byte in 0..=0xFF {
+            assert_eq!(encode_sid_address(parse_sid_address(byte)), byte);
+        }
+    }
+
+    #[test]
+    fn validate_real_file_has_no_warnings() {
+        let sid = SidFile::load("tests/Hexadecimal_2SID.sid").expect("load 2SID file");
+        assert_eq!(sid.validate(), []);
+    }
+
+    macro_rules! validate_tests {
+        ($($name:ident: |$sid:ident| $setup:expr => $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let mut $sid = test_sid!();
+                    $sid.data = vec![0x60; 0x10];
+                    $setup;
+                    assert_eq!($sid.validate(), $expected);
+                }
+            )*
+        };
+    }
+
+    validate_tests! {
+        validate_load_in_driver_area: |sid| {
+            sid.load_address = 0x0400;
+            sid.init_address = 0x0400;
+            sid.play_address = 0x0403;
+        } => [ValidationWarning::LoadInDriverArea(0x0400)],
+        validate_init_outside_data: |sid| sid.init_address = 0x2000
+            => [ValidationWarning::InitOutsideData { init: 0x2000, start: 0x1000, end: 0x1010 }],
+        validate_too_many_songs: |sid| sid.songs = 300 => [ValidationWarning::TooManySongs(300)],
+        validate_play_equals_init: |sid| sid.play_address = 0x1000
+            => [ValidationWarning::PlayEqualsInit(0x1000)],
+        validate_data_too_large: |sid| sid.data = vec![0; 0xF001]
+            => [ValidationWarning::DataTooLarge { end: 0x10001 }],
+    }
+
+    macro_rules! chip_name_tests {
+        ($($name:ident: $flags:expr, $index:expr => $expected:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let mut sid = test_sid!();
+                    sid.flags = $flags;
+                    assert_eq!(sid.chip_model_name_for_sid($index), $expected);
+                }
+            )*
+        };
+    }
+
+    chip_name_tests! {
+        chip_name_unknown: 0x00, 0 => "Unknown",
+        chip_name_6581: 0x10, 0 => "6581",
+        chip_name_8580: 0x20, 0 => "8580",
+        chip_name_both: 0x30, 0 => "6581+8580",
+        chip_name_second_sid: 0x90, 1 => "8580",
+        chip_name_out_of_bounds: 0xFFF0, 7 => "Unknown",
+    }
+
+    #[test]
+    fn clock_hint_from_cia_timer_setup() {
+        // LDA #$25 / STA $DC04 / LDA #$40 / STA $DC05 / RTS
+        let pal = [
+            0xA9, 0x25, 0x8D, 0x04, 0xDC, 0xA9, 0x40, 0x8D, 0x05, 0xDC, 0x60,
+        ];
+        let hint = SidFile::detect_clock_from_data(&pal).expect("timer write found");
+        assert_eq!(hint.clock, ClockType::Pal);
+        assert!(hint.confidence > 0.9);
+
+        // LDX #$95 / STX $DC06 / LDX #$42 / STX $DC07
+        let ntsc = [0xA2, 0x95, 0x8E, 0x06, 0xDC, 0xA2, 0x42, 0x8E, 0x07, 0xDC];
+        let hint = SidFile::detect_clock_from_data(&ntsc).expect("timer write found");
+        assert_eq!(hint.clock, ClockType::Ntsc);
+
+        // A timer set to some other rate
+        let other = [0xA9, 0x00, 0x8D, 0x04, 0xDC, 0xA9, 0x10, 0x8D, 0x05, 0xDC];
+        let hint = SidFile::detect_clock_from_data(&other).expect("timer write found");
+        assert_eq!(hint.clock, ClockType::Unknown);
+
+        assert_eq!(SidFile::detect_clock_from_data(&[0x60; 16]), None);
+    }
+
+    #[test]
+    fn flags_decode_clock_and_models() {
+        assert_eq!(format_flags(0x3FF, 1), ["none (v1 header)"]);
+        let lines = format_flags(0x0094, 3);
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].ends_with("built-in"));
+        assert!(lines[2].ends_with("PAL"));
+        assert!(lines[3].ends_with("6581"));
+        assert!(lines[4].ends_with("8580"));
+        assert_eq!(format_flags(0x0008, 2)[2], "Clock:       NTSC");
+    }
+
+    #[test]
+    fn cia_timed_psid_is_playable() {
+        let mut sid = test_sid!();
+        sid.speed = 0b10;
+        assert!(!sid.requires_full_emulation());
+        assert!(!sid.uses_cia_timing(1));
+        assert!(sid.uses_cia_timing(2));
+        sid.magic = "RSID".to_string();
+        assert!(sid.requires_full_emulation());
+    }
+
     #[test]
     fn sid_count_single() {
         let sid = test_sid!();
@@ -3,18 +3,198 @@
 
 //! Application state and logic.
 
+use crate::config::{
+    Config, MIX_MODES, PlayerStateRef, SAMPLING_METHODS, SCOPE_MARKERS, parse_scope_marker,
+    sampling_method_name, subsong_key,
+};
 use crate::hvsc::{HvscBrowser, HvscEntry};
-use crate::player::SharedPlayer;
-use crate::playlist::Playlist;
-use crate::sid_file::SidFile;
+use crate::memory::WriteEvent;
+use crate::player::{CpuSnapshot, PerfSummary, SharedPlayer, VOICES_PER_SID};
+use crate::playlist::{Playlist, PlaylistEntry, SortKey};
+use crate::remote::{RemoteCommand, RemoteHandle, RemoteStatus};
+use crate::sid_file::{SidFile, ValidationWarning};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::symbols::Marker;
 use ratatui::widgets::ListState;
 use residfp::ChipModel;
-use std::path::PathBuf;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use super::TuiConfig;
+use super::command::{Command, parse_command, push_history};
+use super::draw::draw_app;
+use super::screenshot::render_to_ansi;
 use super::theme::{ColorScheme, SCHEMES};
-use super::widgets::{VoiceScopes, VuMeter};
+use super::vim::{VimCommand, VimState, step_index};
+use super::widgets::{PEAK_HISTORY_LEN, VoiceScopes, VuMeter};
+
+/// Number of bytes shown in the memory dump popup.
+pub const MEM_DUMP_LEN: u16 = 256;
+
+/// Errors waiting behind the one shown; older ones are dropped beyond this.
+const MAX_QUEUED_ERRORS: usize = 5;
+
+/// Playlist entries remembered so smart shuffle doesn't repeat them.
+const RECENTLY_PLAYED_LEN: usize = 10;
+
+/// SID writes kept for the write trace popup.
+pub const WRITE_TRACE_LEN: usize = 50;
+
+/// How long the playlist title shows the new size after a merge.
+const MERGE_TITLE_DURATION: Duration = Duration::from_secs(3);
+/// How long notifications and non-blocking warnings stay on screen
+pub const NOTIFICATION_DURATION: Duration = Duration::from_secs(3);
+/// Notifications shown at once; older ones are dropped
+const MAX_NOTIFICATIONS: usize = 4;
+
+/// What the main loop does after a key press or update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Keep running
+    None,
+    /// Leave the TUI
+    Quit,
+}
+
+/// Tracks when output went silent, returning true once it has stayed silent for `timeout`.
+fn silence_timed_out(
+    silent_since: &mut Option<Instant>,
+    silent: bool,
+    now: Instant,
+    timeout: Duration,
+) -> bool {
+    if !silent {
+        *silent_since = None;
+        return false;
+    }
+    now.duration_since(*silent_since.get_or_insert(now)) >= timeout
+}
+
+/// Shows `msg` in an error popup, or queues it while another error is shown.
+fn queue_error(popup: &mut Popup, queue: &mut VecDeque<String>, msg: String) {
+    if !matches!(popup, Popup::Error(_)) {
+        *popup = Popup::Error(msg);
+        return;
+    }
+    if queue.len() == MAX_QUEUED_ERRORS {
+        queue.pop_front();
+    }
+    queue.push_back(msg);
+}
+
+/// Popup to show after closing `popup`: the next queued error, if any.
+fn popup_after_close(popup: &Popup, queue: &mut VecDeque<String>) -> Popup {
+    match (popup, queue.pop_front()) {
+        (Popup::Error(_), Some(next)) => Popup::Error(next),
+        _ => Popup::None,
+    }
+}
+
+/// Drops notifications whose display time has passed at `now`.
+fn expire_notifications(queue: &mut VecDeque<(String, Instant)>, now: Instant) {
+    queue.retain(|&(_, expires)| expires > now);
+}
+
+/// Maps recently played sources to their current playlist indices, dropping any no longer listed.
+fn recent_indices<'a>(
+    playlist: &Playlist,
+    sources: impl IntoIterator<Item = &'a String>,
+) -> Vec<usize> {
+    sources
+        .into_iter()
+        .filter_map(|source| playlist.entries.iter().position(|e| &e.source == source))
+        .collect()
+}
+
+/// True once `elapsed` has reached the `--exit-after` limit.
+fn exit_due(exit_after: Option<Duration>, elapsed: Duration) -> bool {
+    exit_after.is_some_and(|limit| elapsed >= limit)
+}
+
+/// Browser entries that can be found by their first letter.
+pub trait HasName {
+    /// Name shown in the browser list.
+    fn name(&self) -> &str;
+}
+
+impl HasName for PlaylistEntry {
+    fn name(&self) -> &str {
+        &self.display_name
+    }
+}
+
+impl HasName for HvscEntry {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T: HasName> HasName for &T {
+    fn name(&self) -> &str {
+        (*self).name()
+    }
+}
+
+/// True if `entry`'s name starts with `prefix` (case-insensitive).
+fn starts_with_letter(entry: &impl HasName, prefix: char) -> bool {
+    entry
+        .name()
+        .chars()
+        .next()
+        .is_some_and(|c| c.to_lowercase().eq(prefix.to_lowercase()))
+}
+
+/// Index of the first entry whose name starts with `prefix` (case-insensitive).
+fn find_first_with_prefix(entries: &[impl HasName], prefix: char) -> Option<usize> {
+    entries.iter().position(|e| starts_with_letter(e, prefix))
+}
+
+/// Indices of the entries whose name contains `query` (case-insensitive).
+fn filter_indices(entries: &[impl HasName], query: &str) -> Vec<usize> {
+    let query = query.to_lowercase();
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| e.name().to_lowercase().contains(&query))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Entry to jump to when `prefix` is typed: the first match, or the next one
+/// (wrapping) if the entry at `current` already starts with it.
+fn jump_target(entries: &[impl HasName], current: usize, prefix: char) -> Option<usize> {
+    let on_match = entries
+        .get(current)
+        .is_some_and(|e| starts_with_letter(e, prefix));
+    if on_match {
+        let next = find_first_with_prefix(&entries[current + 1..], prefix);
+        return next
+            .map(|i| current + 1 + i)
+            .or_else(|| find_first_with_prefix(entries, prefix));
+    }
+    find_first_with_prefix(entries, prefix)
+}
+
+/// Like `jump_target`, but only among the `visible` entries of a filtered browser.
+fn visible_jump_target<T: HasName>(
+    entries: &[T],
+    visible: Option<&[usize]>,
+    current: usize,
+    prefix: char,
+) -> Option<usize> {
+    let Some(visible) = visible else {
+        return jump_target(entries, current, prefix);
+    };
+    let shown: Vec<&T> = visible.iter().filter_map(|&i| entries.get(i)).collect();
+    // A selection hidden by the filter counts as no selection
+    let current = visible
+        .iter()
+        .position(|&i| i == current)
+        .unwrap_or(shown.len());
+    jump_target(&shown, current, prefix).map(|k| visible[k])
+}
 
 /// Which browser panel has focus.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -23,6 +203,15 @@ pub enum BrowserFocus {
     Hvsc,
 }
 
+/// Whether keys drive the player or are typed into the command line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum InputMode {
+    #[default]
+    Normal,
+    /// `:` was pressed; keys edit `input` until Enter or Esc
+    CommandMode { input: String },
+}
+
 /// Popup dialog state.
 #[derive(Debug, Clone)]
 pub enum Popup {
@@ -32,6 +221,58 @@ pub enum Popup {
     SaveConfirm,
     HvscSearch,
     ColorScheme,
+    MirrorList,
+    ValidationWarnings,
+    SamplingMethod,
+    /// Hex dump of emulated memory starting at `start`
+    MemDump {
+        start: u16,
+        data: Vec<u8>,
+    },
+    SubsongChipOverride,
+    HvscStats,
+    PerfStats,
+    /// 6502 registers now and after init (F4)
+    CpuDebug,
+    /// Frame-by-frame stepping (F5); F6 runs one play call
+    StepMode,
+    /// Output low-pass filter sliders (`E`); `field` is 0 for cutoff, 1 for resonance
+    Equalizer {
+        field: usize,
+    },
+    /// Playlist sort options (`S`)
+    SortPlaylist {
+        ignore_articles: bool,
+    },
+    /// Path prompt for a playlist to merge into the current one (`Ctrl+O`)
+    MergePlaylist {
+        path: String,
+    },
+    /// Most recent SID register writes (`Ctrl+T`), scrolled down `scroll` lines
+    WriteTrace {
+        scroll: u16,
+    },
+    /// Confirms replacing playlist entry `index` with one entry per subsong (`X`)
+    ExpandConfirm {
+        index: usize,
+        songs: u16,
+    },
+    /// Comment tags found in STIL (`Ctrl+G`); `selected` indexes `StilDatabase::genres`
+    GenreBrowser {
+        selected: usize,
+    },
+    /// SID header fields of the current tune (`I`), scrolled down `scroll` lines
+    FileInfo {
+        scroll: u16,
+    },
+    /// RGB editor for one voice color; `field` is the channel being typed (0-2)
+    VoiceColorEditor {
+        voice_idx: usize,
+        r: u8,
+        g: u8,
+        b: u8,
+        field: usize,
+    },
 }
 
 /// Browser state for playlist navigation.
@@ -75,6 +316,77 @@ pub struct App<'a> {
     pub current_song: u16,
     pub total_songs: u16,
     pub paused: bool,
+    /// Player output is being captured to a WAV recording
+    pub recording: bool,
+    /// Alternates each frame while recording to blink the [REC] indicator
+    pub rec_blink: bool,
+    pub recording_duration: Duration,
+    /// Playback speed relative to real time
+    pub speed_factor: f32,
+    /// Voice (0-indexed across SIDs) playing alone, others soft-muted
+    pub solo_voice: Option<usize>,
+    /// Audio underruns since the current tune started
+    pub underrun_count: u32,
+    /// At least one SID plays with its filter bypassed
+    pub filter_bypass_active: bool,
+    /// `F` was pressed and the next digit picks the SID whose filter bypass toggles
+    pub filter_key_pending: bool,
+    /// `O` was pressed and the next digit picks the voice to solo (0 clears)
+    pub solo_key_pending: bool,
+    /// `'` was pressed and the next character jumps to an entry, even if it is bound
+    pub jump_key_pending: bool,
+    /// Vim-style browser navigation (`gg`, `G`, `5j`, `/pattern`, `n`/`N`) is enabled
+    pub vim_mode: bool,
+    /// Keys of the unfinished vim sequence (max 10)
+    pub vim_key_buffer: String,
+    pub vim: VimState,
+    /// Last `/pattern` searched in vim mode
+    vim_search: Option<String>,
+    pub input_mode: InputMode,
+    /// Error from the last command line, shown in the footer until the next key
+    pub command_error: Option<String>,
+    /// Position in `config.command_history` while browsing it with Up/Down
+    command_history_pos: Option<usize>,
+    /// Inline `f` filter: the browser it applies to and the query typed so far
+    pub browser_filter: Option<(BrowserFocus, String)>,
+    /// Play routine statistics over the last 30 frames
+    pub perf_summary: Option<PerfSummary>,
+    /// CPU registers, refreshed while the CPU debug popup is open
+    pub cpu_snapshot: Option<CpuSnapshot>,
+    /// CPU registers as the current song's init routine left them
+    pub last_init_cpu_snapshot: Option<CpuSnapshot>,
+    /// Frames advanced in step mode
+    pub step_frames: u64,
+    /// When the player output went silent, for skipping tunes that never play
+    silent_since: Option<Instant>,
+    /// `advance_song` is loading the next file, which then fades in
+    advancing: bool,
+    /// Output filter cutoff as a fraction of the Nyquist frequency
+    pub eq_cutoff: f32,
+    /// Output filter resonance (0.0-1.0)
+    pub eq_resonance: f32,
+    /// Output low-pass filter is applied
+    pub eq_enabled: bool,
+    /// Last `WRITE_TRACE_LEN` SID writes while the write trace popup is open
+    pub write_trace: VecDeque<WriteEvent>,
+    /// Errors raised while another error popup is open, shown in turn on close
+    pub error_queue: VecDeque<String>,
+    /// Non-blocking messages in the top-right corner, with when each expires
+    pub notification_queue: VecDeque<(String, Instant)>,
+    /// When a merge last changed the playlist, for showing its size in the title
+    playlist_merged_at: Option<Instant>,
+    /// Pitch in Hz of each voice, `None` when silent or playing noise
+    pub voice_frequencies: Vec<Option<f32>>,
+    /// Play calls since the song started (shown in the F3 debug overlay)
+    pub frame_counter: u64,
+    /// Debug overlay is shown (F3)
+    pub show_debug_overlay: bool,
+    /// Voice envelope levels after the last step
+    pub step_envelopes: Vec<u8>,
+    /// Scratch audio for `Player::step_frame` (the device plays silence in step mode)
+    step_buffer: Vec<f32>,
+    /// Animation frame for the HVSC loading spinner
+    pub spinner_tick: usize,
     /// Chip models for each SID (1-3 entries)
     pub chip_models: Vec<ChipModel>,
     /// Index of currently selected SID for chip switching (cycles through)
@@ -83,20 +395,75 @@ pub struct App<'a> {
     pub voice_scopes: VoiceScopes,
     pub playlist_browser: PlaylistBrowser,
     pub playlist_path: PathBuf,
+    /// Save the playlist with paths relative to its directory (`--portable`)
+    pub portable_playlist: bool,
     pub hvsc_browser: HvscBrowser,
     pub browser_focus: BrowserFocus,
+    /// Terminal is narrower than `COMPACT_WIDTH` (set on every draw)
+    pub compact_layout: bool,
+    /// Browser panel shown in the compact layout (toggled with Tab)
+    pub browser_visible: bool,
     pub current_browser_sid: Option<SidFile>,
     pub current_source: Option<String>,
     pub popup: Popup,
     pub playlist_modified: bool,
     pub color_scheme: usize,
+    /// Built-in schemes followed by the imported ones from the config
+    pub schemes: Vec<ColorScheme>,
+    /// Selected scheme with the configured voice color overrides applied
+    pub custom_scheme: ColorScheme,
+    /// `Ctrl+V` was pressed and the next digit picks the voice color to edit
+    pub voice_color_key_pending: bool,
     pub hvsc_search: Option<String>,
     pub hvsc_search_results: Vec<String>,
     pub hvsc_search_index: usize,
     pub song_elapsed: Duration,
     pub song_resumed_at: Instant,
+    /// Quit after this much time (`--exit-after`)
+    exit_after: Option<Duration>,
+    /// Advance to a random playlist entry not among the recently played (`--smart-shuffle`)
+    smart_shuffle: bool,
+    /// Sources of the last `RECENTLY_PLAYED_LEN` playlist entries played, oldest first.
+    /// Kept as sources so sorting, shuffling or editing the playlist can't make them stale.
+    pub recently_played: VecDeque<String>,
+    /// Where to write the VU peak history on quit (`--export-vu-history`)
+    export_vu_history: Option<PathBuf>,
+    started_at: Instant,
     pub song_timeout: Duration,
     pub default_timeout: Duration,
+    /// Cursor position in the mirror list popup
+    pub mirror_list_index: usize,
+    /// Active entry in `SAMPLING_METHODS`
+    pub sampling_method: usize,
+    /// Cursor position in the sampling method popup
+    pub sampling_method_index: usize,
+    /// Active entry in `MIX_MODES`
+    pub mix_mode: usize,
+    /// Canvas marker used to draw the oscilloscopes
+    pub scope_marker: Marker,
+    /// User chip model overrides for the current tune's subsongs (first SID)
+    pub subsong_chip_overrides: HashMap<u16, ChipModel>,
+    /// First SID's model for subsongs without an override
+    default_chip_model: ChipModel,
+    /// Whether the current subsong is playing with an override
+    chip_override_active: bool,
+    /// Cursor position in the subsong chip override popup
+    pub subsong_override_index: usize,
+    /// Persistent user configuration, returned to the caller on exit
+    pub config: Config,
+    /// WebSocket remote control server, if enabled
+    pub remote: Option<RemoteHandle>,
+    /// MPRIS2 bridge, if the session bus was reachable
+    #[cfg(feature = "mpris")]
+    pub mpris: Option<crate::mpris::MprisHandle>,
+    /// Playlist file is reloaded when changed on disk (Ctrl+W)
+    pub playlist_watching: bool,
+    /// Watcher for the playlist file and its reload events
+    #[cfg(feature = "watch")]
+    playlist_watch: Option<(
+        notify::RecommendedWatcher,
+        std::sync::mpsc::Receiver<crate::playlist::PlaylistChange>,
+    )>,
 }
 
 impl<'a> App<'a> {
@@ -109,9 +476,26 @@ impl<'a> App<'a> {
             .unwrap_or_else(|_| vec![ChipModel::Mos6581]);
 
         let sid_count = chip_models.len();
+        let sampling_method = config
+            .player
+            .lock()
+            .ok()
+            .and_then(|p| {
+                let name = sampling_method_name(p.sampling_method());
+                SAMPLING_METHODS.iter().position(|(n, _)| *n == name)
+            })
+            .unwrap_or(0);
+        let mix_mode = config
+            .player
+            .lock()
+            .ok()
+            .and_then(|p| MIX_MODES.iter().position(|&(_, mode)| mode == p.mix_mode()))
+            .unwrap_or(0);
 
         let mut hvsc_browser = HvscBrowser::new(config.hvsc_url);
-        hvsc_browser.load_stil();
+        hvsc_browser.set_mirrors(config.user_config.hvsc_mirrors.clone());
+        hvsc_browser.fetch_timeout =
+            Duration::from_secs(config.user_config.hvsc_fetch_timeout_secs);
 
         let browser_focus = if config.focus_hvsc {
             BrowserFocus::Hvsc
@@ -119,37 +503,177 @@ impl<'a> App<'a> {
             BrowserFocus::Playlist
         };
 
-        Self {
+        let schemes: Vec<ColorScheme> = SCHEMES
+            .iter()
+            .chain(&config.user_config.custom_schemes)
+            .cloned()
+            .collect();
+        let custom = config.user_config.custom_color_scheme.as_deref();
+        let color_scheme = schemes[SCHEMES.len()..]
+            .iter()
+            .position(|s| Some(s.name.as_ref()) == custom)
+            .map_or_else(
+                || config.user_config.color_scheme.min(SCHEMES.len() - 1),
+                |i| SCHEMES.len() + i,
+            );
+
+        let saved = &config.user_config.recently_played;
+        let recently_played = saved[saved.len().saturating_sub(RECENTLY_PLAYED_LEN)..]
+            .iter()
+            .filter(|&source| config.playlist.entries.iter().any(|e| &e.source == source))
+            .cloned()
+            .collect();
+        let mut app = Self {
             player: config.player,
             sid_file: config.sid_file,
             current_song: config.song,
             total_songs: config.sid_file.songs,
             paused: false,
+            recording: false,
+            rec_blink: false,
+            recording_duration: Duration::ZERO,
+            speed_factor: 1.0,
+            solo_voice: None,
+            filter_bypass_active: false,
+            filter_key_pending: false,
+            solo_key_pending: false,
+            jump_key_pending: false,
+            vim_mode: false,
+            vim_key_buffer: String::new(),
+            input_mode: InputMode::Normal,
+            command_error: None,
+            command_history_pos: None,
+            vim: VimState::default(),
+            vim_search: None,
+            browser_filter: None,
+            underrun_count: 0,
+            perf_summary: None,
+            cpu_snapshot: None,
+            last_init_cpu_snapshot: None,
+            step_frames: 0,
+            silent_since: None,
+            advancing: false,
+            eq_cutoff: 1.0,
+            eq_resonance: 0.0,
+            eq_enabled: false,
+            error_queue: VecDeque::new(),
+            notification_queue: VecDeque::new(),
+            write_trace: VecDeque::new(),
+            playlist_merged_at: None,
+            voice_frequencies: Vec::new(),
+            frame_counter: 0,
+            show_debug_overlay: false,
+            step_envelopes: Vec::new(),
+            step_buffer: vec![0.0; config.user_config.sample_rate as usize / 50],
+            spinner_tick: 0,
             chip_models,
             selected_sid: 0,
-            vu_meter: VuMeter::with_voice_count(sid_count * 3),
-            voice_scopes: VoiceScopes::with_voice_count(sid_count * 3),
+            vu_meter: VuMeter::with_voice_count(sid_count * VOICES_PER_SID),
+            voice_scopes: VoiceScopes::with_voice_count(sid_count * VOICES_PER_SID),
             playlist_browser: PlaylistBrowser::new(config.playlist),
             playlist_path: config.playlist_path,
+            portable_playlist: config.portable,
             hvsc_browser,
             browser_focus,
+            compact_layout: false,
+            browser_visible: false,
             current_browser_sid: None,
-            current_source: None,
+            current_source: config.source,
             popup: Popup::None,
             playlist_modified: config.playlist_modified,
-            color_scheme: config.color_scheme.min(SCHEMES.len().saturating_sub(1)),
+            color_scheme,
+            custom_scheme: schemes[color_scheme].clone().with_voice_colors(
+                config
+                    .user_config
+                    .custom_voice_colors
+                    .get(schemes[color_scheme].name.as_ref()),
+            ),
+            schemes,
+            voice_color_key_pending: false,
             hvsc_search: None,
             hvsc_search_results: Vec::new(),
             hvsc_search_index: 0,
             song_elapsed: Duration::ZERO,
             song_resumed_at: Instant::now(),
+            exit_after: config.exit_after,
+            smart_shuffle: config.smart_shuffle,
+            recently_played,
+            export_vu_history: config.export_vu_history,
+            started_at: Instant::now(),
             song_timeout: Duration::from_secs(config.playtime_secs),
             default_timeout: Duration::from_secs(config.playtime_secs),
+            mirror_list_index: 0,
+            sampling_method,
+            sampling_method_index: sampling_method,
+            mix_mode,
+            scope_marker: parse_scope_marker(&config.user_config.scope_marker),
+            subsong_chip_overrides: HashMap::new(),
+            default_chip_model: ChipModel::Mos6581,
+            chip_override_active: false,
+            subsong_override_index: 0,
+            config: config.user_config,
+            remote: config.remote,
+            #[cfg(feature = "mpris")]
+            mpris: config.mpris,
+            playlist_watching: false,
+            #[cfg(feature = "watch")]
+            playlist_watch: None,
+        };
+        app.load_subsong_overrides();
+        app.apply_subsong_chip(app.current_song);
+        app
+    }
+
+    /// Consumes the app, returning the config with the final UI settings applied.
+    pub fn into_config(self) -> Config {
+        let md5 = self.display_sid().md5.clone();
+        let mut config = self.config;
+        // Imported schemes are saved by name, as their positions change on re-import
+        match self.schemes.get(self.color_scheme) {
+            Some(scheme) if self.color_scheme >= SCHEMES.len() => {
+                config.custom_color_scheme = Some(scheme.name.to_string());
+            }
+            _ => {
+                config.color_scheme = self.color_scheme;
+                config.custom_color_scheme = None;
+            }
         }
+        config.recently_played = self.recently_played.into();
+        config.last_session = self
+            .current_source
+            .filter(|_| !md5.is_empty())
+            .map(|source| PlayerStateRef {
+                source,
+                md5,
+                song: self.current_song,
+            });
+        config
+    }
+
+    /// Returns the VU meter peaks of the last `n_frames` frames, one value per voice.
+    pub fn snapshot_vu_peak_history(&self, n_frames: usize) -> Vec<Vec<f32>> {
+        self.vu_meter.peak_history(n_frames)
+    }
+
+    /// Writes the VU peak history as CSV if `--export-vu-history` was given.
+    pub fn export_vu_history(&self) -> std::io::Result<()> {
+        let Some(path) = &self.export_vu_history else {
+            return Ok(());
+        };
+        let history = self.snapshot_vu_peak_history(PEAK_HISTORY_LEN);
+        std::fs::write(path, vu_history_csv(&history))
+    }
+
+    /// Renders one frame at the terminal's size and saves it as ANSI text.
+    pub fn export_current_screenshot(&mut self, path: &Path) -> std::io::Result<()> {
+        let (width, height) = crossterm::terminal::size()?;
+        let Ok(mut terminal) = Terminal::new(TestBackend::new(width, height));
+        let Ok(_) = terminal.draw(|frame| draw_app(frame, self));
+        std::fs::write(path, render_to_ansi(terminal.backend()))
     }
 
     pub fn scheme(&self) -> &ColorScheme {
-        &SCHEMES[self.color_scheme]
+        &self.custom_scheme
     }
 
     /// Returns the SID file to display metadata from.
@@ -157,6 +681,31 @@ impl<'a> App<'a> {
         self.current_browser_sid.as_ref().unwrap_or(self.sid_file)
     }
 
+    /// Returns the HVSC path of the current tune if it came from the base URL or a mirror.
+    fn current_hvsc_path(&self) -> Option<&str> {
+        let source = self.current_source.as_deref()?;
+        std::iter::once(&self.hvsc_browser.base_url)
+            .chain(&self.hvsc_browser.mirrors)
+            .find_map(|url| source.strip_prefix(url.as_str()))
+    }
+
+    /// Returns the STIL comment for the current subsong if the tune came from HVSC.
+    pub fn stil_subsong_comment(&self) -> Option<&str> {
+        let path = self.current_hvsc_path()?;
+        self.hvsc_browser
+            .stil
+            .as_ref()?
+            .comment_for_subsong(path, self.current_song)
+    }
+
+    /// Returns the STIL genre tags of the current tune if it came from HVSC.
+    pub fn current_genres(&self) -> Vec<String> {
+        let (Some(path), Some(stil)) = (self.current_hvsc_path(), &self.hvsc_browser.stil) else {
+            return Vec::new();
+        };
+        stil.genres_for_path(path)
+    }
+
     /// Returns total elapsed play time (excludes paused time).
     pub fn song_elapsed_total(&self) -> Duration {
         if self.paused {
@@ -170,6 +719,7 @@ impl<'a> App<'a> {
     fn reset_song_timer(&mut self) {
         self.song_elapsed = Duration::ZERO;
         self.song_resumed_at = Instant::now();
+        self.silent_since = None;
     }
 
     /// Updates song_timeout from Songlengths database, falling back to default_timeout.
@@ -180,17 +730,48 @@ impl<'a> App<'a> {
             .unwrap_or(self.default_timeout);
     }
 
-    pub fn update(&mut self) {
+    /// Refreshes state from the player, returning `Action::Quit` once `--exit-after` expires.
+    /// A modified playlist is then saved without asking.
+    pub fn update(&mut self) -> Action {
+        expire_notifications(&mut self.notification_queue, Instant::now());
+        let mut silent = false;
         let playback_error = if let Ok(mut player) = self.player.lock() {
-            self.vu_meter.update(&player.voice_levels());
+            self.vu_meter.update(&player.voice_levels_grouped());
             self.voice_scopes.update(&player.envelope_samples());
             self.paused = player.is_paused();
+            self.recording = player.is_recording();
+            self.recording_duration = player.recording_duration();
             self.chip_models = player.chip_models().to_vec();
+            player.set_solo_voice(self.solo_voice);
+            self.perf_summary = player.perf_summary(self.config.buffer_size);
+            self.underrun_count = player.underrun_count();
+            self.frame_counter = player.position_as_frame_count();
+            self.voice_frequencies = player.voice_frequencies();
+            self.last_init_cpu_snapshot = Some(player.init_cpu_snapshot());
+            if matches!(self.popup, Popup::CpuDebug) {
+                self.cpu_snapshot = Some(player.snapshot_cpu_registers());
+            }
+            if matches!(self.popup, Popup::WriteTrace { .. }) {
+                self.write_trace.extend(player.take_write_trace());
+                let excess = self.write_trace.len().saturating_sub(WRITE_TRACE_LEN);
+                self.write_trace.drain(..excess);
+            }
+            silent = player.never_sounded(self.config.silence_threshold) && !player.is_step_mode();
+            self.filter_bypass_active =
+                (0..player.sid_count()).any(|i| player.is_filter_bypassed(i));
             player.take_error()
         } else {
             None
         };
 
+        self.rec_blink = self.recording && !self.rec_blink;
+
+        self.hvsc_browser.poll_pending_nav();
+        self.hvsc_browser.poll_pending_databases();
+        if self.hvsc_browser.loading {
+            self.spinner_tick = self.spinner_tick.wrapping_add(1);
+        }
+
         // Show playback error after releasing player lock
         if let Some(err) = playback_error {
             self.show_error(format!("Playback error: {err}"));
@@ -201,6 +782,149 @@ impl<'a> App<'a> {
         if !self.paused && !has_error_popup && self.song_elapsed_total() >= self.song_timeout {
             self.advance_song();
         }
+
+        // Skip tunes that initialize but never produce any output (0 turns this off)
+        let timeout = Duration::from_secs(self.config.silence_timeout_secs);
+        let silent = silent && !self.paused && !has_error_popup;
+        if !timeout.is_zero()
+            && silence_timed_out(&mut self.silent_since, silent, Instant::now(), timeout)
+        {
+            self.advance_song();
+        }
+
+        self.poll_remote();
+        #[cfg(feature = "mpris")]
+        self.poll_mpris();
+        #[cfg(feature = "watch")]
+        self.poll_playlist_watch();
+
+        if !exit_due(self.exit_after, self.started_at.elapsed()) {
+            return Action::None;
+        }
+        // No one may be around to answer the save prompt, so keep the changes
+        if self.playlist_modified {
+            self.save_playlist();
+        }
+        Action::Quit
+    }
+
+    /// Publishes status to WebSocket clients and handles pending remote commands.
+    fn poll_remote(&mut self) {
+        let Some(remote) = &self.remote else {
+            return;
+        };
+        remote.publish(RemoteStatus {
+            title: self.display_sid().name.clone(),
+            song: self.current_song,
+            paused: self.paused,
+            elapsed: self.song_elapsed_total().as_secs_f64(),
+        });
+        let commands: Vec<_> = std::iter::from_fn(|| remote.try_recv()).collect();
+        for command in commands {
+            match command {
+                RemoteCommand::PlayPause => self.toggle_pause(),
+                RemoteCommand::NextSong => self.next_song(),
+                RemoteCommand::Load { url } => self.load_source(&url),
+            }
+        }
+    }
+
+    /// Loads and plays a SID file from a path or URL (optionally with @N subsong).
+    fn load_source(&mut self, source: &str) {
+        let Some(entry) = PlaylistEntry::new(source) else {
+            return;
+        };
+        match entry.load() {
+            Ok(sid_file) => {
+                let song = entry.subsong.unwrap_or(sid_file.start_song);
+                self.play_sid_file(sid_file, song, entry.source);
+            }
+            Err(e) => self.show_error(format!("Skipped: {e}")),
+        }
+    }
+
+    /// Starts or stops reloading the playlist when its file changes on disk.
+    pub fn toggle_playlist_watch(&mut self) {
+        #[cfg(feature = "watch")]
+        {
+            if self.playlist_watch.take().is_none() {
+                let (tx, rx) = std::sync::mpsc::channel();
+                match Playlist::watch(&self.playlist_path, tx) {
+                    Ok(watcher) => self.playlist_watch = Some((watcher, rx)),
+                    Err(e) => self.show_warning(format!("Cannot watch playlist: {e}")),
+                }
+            }
+            self.playlist_watching = self.playlist_watch.is_some();
+        }
+        #[cfg(not(feature = "watch"))]
+        self.show_warning("Playlist watching requires the 'watch' feature".to_string());
+    }
+
+    /// Replaces the playlist with the latest version from disk, keeping the selected entry.
+    #[cfg(feature = "watch")]
+    fn poll_playlist_watch(&mut self) {
+        use crate::playlist::PlaylistChange;
+
+        let Some((_, rx)) = &self.playlist_watch else {
+            return;
+        };
+        let Some(PlaylistChange::Reloaded(playlist)) = rx.try_iter().last() else {
+            return;
+        };
+        let browser = &mut self.playlist_browser;
+        let selected = browser
+            .playlist
+            .entries
+            .get(browser.selected_index())
+            .map(|entry| entry.source.clone());
+        let index = selected
+            .and_then(|source| playlist.entries.iter().position(|e| e.source == source))
+            .or_else(|| (!playlist.is_empty()).then_some(0));
+        browser.playlist = playlist;
+        browser.state.select(index);
+        self.playlist_modified = false;
+    }
+
+    /// Publishes track status to MPRIS and handles pending remote requests.
+    #[cfg(feature = "mpris")]
+    fn poll_mpris(&mut self) {
+        use crate::mpris::MprisEvent;
+
+        let Some(mpris) = &self.mpris else {
+            return;
+        };
+        mpris.publish(self.track_status());
+        let events: Vec<_> = std::iter::from_fn(|| mpris.try_recv()).collect();
+        for event in events {
+            match event {
+                MprisEvent::PlayPause => self.toggle_pause(),
+                MprisEvent::Next => self.next_song(),
+                MprisEvent::Previous => self.prev_song(),
+                MprisEvent::Stop => self.stop(),
+            }
+        }
+    }
+
+    /// Current track information for MPRIS metadata.
+    #[cfg(feature = "mpris")]
+    fn track_status(&self) -> crate::mpris::TrackStatus {
+        let sid = self.display_sid();
+        crate::mpris::TrackStatus {
+            title: sid.name.clone(),
+            artist: sid.author.clone(),
+            length: self.hvsc_browser.song_duration(&sid.md5, self.current_song),
+            current_song: self.current_song,
+            total_songs: self.total_songs,
+        }
+    }
+
+    /// Restarts the current subsong and leaves it paused.
+    #[cfg(feature = "mpris")]
+    fn stop(&mut self) {
+        self.goto_song(self.current_song);
+        if !self.paused {
+            self.toggle_pause();
+        }
     }
 
     /// Advances to next subsong, or next playlist/HVSC entry if at last subsong.
@@ -212,9 +936,14 @@ impl<'a> App<'a> {
         } else {
             // Reset timer before attempting load to prevent infinite loop if all files fail
             self.reset_song_timer();
+            self.advancing = true;
             match self.browser_focus {
                 BrowserFocus::Playlist => {
-                    self.playlist_browser.select_next();
+                    if self.smart_shuffle {
+                        self.select_random_playlist_entry();
+                    } else {
+                        self.playlist_browser.select_next();
+                    }
                     self.load_playlist_selected();
                 }
                 BrowserFocus::Hvsc => {
@@ -225,6 +954,7 @@ impl<'a> App<'a> {
                     }
                 }
             }
+            self.advancing = false;
         }
     }
 
@@ -268,15 +998,19 @@ impl<'a> App<'a> {
 
     fn load_song_on_player(&mut self, song: u16) {
         let error = match self.player.lock() {
-            Ok(mut player) => player
-                .load_song(song)
-                .err()
-                .map(|e| format!("Init error: {e}")),
+            Ok(mut player) => {
+                player.clear_underrun_count();
+                player
+                    .load_song(song)
+                    .err()
+                    .map(|e| format!("Init error: {e}"))
+            }
             Err(_) => Some("Init error: player lock poisoned".to_string()),
         };
         if let Some(msg) = error {
             self.show_error(msg);
         }
+        self.apply_subsong_chip(song);
 
         let md5 = self
             .current_browser_sid
@@ -307,7 +1041,20 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Switches focus between the playlist and HVSC browser. In the compact layout
+    /// Tab instead cycles hidden → playlist → HVSC → hidden.
     pub fn toggle_browser_focus(&mut self) {
+        if self.compact_layout {
+            if !self.browser_visible {
+                self.browser_visible = true;
+                self.browser_focus = BrowserFocus::Playlist;
+                return;
+            }
+            if self.browser_focus == BrowserFocus::Hvsc {
+                self.browser_visible = false;
+                return;
+            }
+        }
         self.browser_focus = match self.browser_focus {
             BrowserFocus::Playlist => BrowserFocus::Hvsc,
             BrowserFocus::Hvsc => BrowserFocus::Playlist,
@@ -315,6 +1062,13 @@ impl<'a> App<'a> {
     }
 
     pub fn browser_next(&mut self) {
+        if let Some(visible) = self.focused_filter_indices() {
+            let (current, _) = self.browser_cursor();
+            if let Some(&index) = visible.iter().find(|&&i| i > current) {
+                self.select_in_focused(index);
+            }
+            return;
+        }
         match self.browser_focus {
             BrowserFocus::Playlist => self.playlist_browser.select_next(),
             BrowserFocus::Hvsc => self.hvsc_browser.select_next(),
@@ -322,19 +1076,194 @@ impl<'a> App<'a> {
     }
 
     pub fn browser_prev(&mut self) {
+        if let Some(visible) = self.focused_filter_indices() {
+            let (current, _) = self.browser_cursor();
+            if let Some(&index) = visible.iter().rev().find(|&&i| i < current) {
+                self.select_in_focused(index);
+            }
+            return;
+        }
         match self.browser_focus {
             BrowserFocus::Playlist => self.playlist_browser.select_prev(),
             BrowserFocus::Hvsc => self.hvsc_browser.select_prev(),
         }
     }
 
+    /// Switches vim-style navigation on or off.
+    pub fn toggle_vim_mode(&mut self) {
+        self.vim_mode = !self.vim_mode;
+        self.vim_key_buffer.clear();
+        self.vim = VimState::default();
+    }
+
+    /// Applies a completed vim sequence to the focused browser.
+    pub fn run_vim_command(&mut self, command: VimCommand) {
+        let (current, len) = self.browser_cursor();
+        let target = match command {
+            VimCommand::First => Some(0),
+            VimCommand::Last => Some(len.saturating_sub(1)),
+            VimCommand::Down(n) => Some(step_index(current, len, i64::from(n))),
+            VimCommand::Up(n) => Some(step_index(current, len, -i64::from(n))),
+            VimCommand::Search(pattern) => {
+                self.vim_search = Some(pattern.to_lowercase());
+                self.find_vim_match(current, len, 0, true)
+            }
+            VimCommand::NextMatch => self.find_vim_match(current, len, 1, true),
+            VimCommand::PrevMatch => self.find_vim_match(current, len, 1, false),
+        };
+        if let Some(index) = target.filter(|_| len > 0) {
+            match self.browser_focus {
+                BrowserFocus::Playlist => self.playlist_browser.state.select(Some(index)),
+                BrowserFocus::Hvsc => self.hvsc_browser.selected = index,
+            }
+        }
+    }
+
+    /// Starts filtering the focused browser by typed text (`f`).
+    pub fn start_browser_filter(&mut self) {
+        if self.browser_focus == BrowserFocus::Hvsc && self.hvsc_search.is_some() {
+            return;
+        }
+        self.browser_filter = Some((self.browser_focus, String::new()));
+    }
+
+    /// True while typed characters go to the focused browser's filter.
+    pub fn is_filtering(&self) -> bool {
+        self.browser_filter
+            .as_ref()
+            .is_some_and(|(focus, _)| *focus == self.browser_focus)
+    }
+
+    /// Appends a character to the filter query.
+    pub fn push_filter_char(&mut self, c: char) {
+        if let Some((_, query)) = &mut self.browser_filter {
+            query.push(c);
+            self.select_first_visible();
+        }
+    }
+
+    /// Removes the last character from the filter query.
+    pub fn pop_filter_char(&mut self) {
+        if let Some((_, query)) = &mut self.browser_filter {
+            query.pop();
+            self.select_first_visible();
+        }
+    }
+
+    /// Drops the filter so all entries are shown again.
+    pub fn clear_browser_filter(&mut self) {
+        self.browser_filter = None;
+    }
+
+    /// Indices of the entries `focus`'s browser shows, or `None` if it isn't filtered.
+    pub fn filter_indices_for(&self, focus: BrowserFocus) -> Option<Vec<usize>> {
+        let (_, query) = self.browser_filter.as_ref().filter(|(f, _)| *f == focus)?;
+        Some(match focus {
+            BrowserFocus::Playlist => {
+                filter_indices(&self.playlist_browser.playlist.entries, query)
+            }
+            BrowserFocus::Hvsc => filter_indices(&self.hvsc_browser.entries, query),
+        })
+    }
+
+    /// Visible entries of the focused browser while it is filtered.
+    fn focused_filter_indices(&self) -> Option<Vec<usize>> {
+        self.filter_indices_for(self.browser_focus)
+    }
+
+    /// Moves the filtered browser's selection to its first visible entry.
+    fn select_first_visible(&mut self) {
+        let Some((focus, _)) = self.browser_filter else {
+            return;
+        };
+        let first = self
+            .filter_indices_for(focus)
+            .and_then(|visible| visible.first().copied());
+        match focus {
+            BrowserFocus::Playlist => self.playlist_browser.state.select(first),
+            BrowserFocus::Hvsc => self.hvsc_browser.selected = first.unwrap_or(0),
+        }
+    }
+
+    /// Selects `index` in the focused browser.
+    fn select_in_focused(&mut self, index: usize) {
+        match self.browser_focus {
+            BrowserFocus::Playlist => self.playlist_browser.state.select(Some(index)),
+            BrowserFocus::Hvsc => self.hvsc_browser.selected = index,
+        }
+    }
+
+    /// Jumps to the next visible entry in the focused browser starting with `letter`.
+    pub fn jump_to_letter(&mut self, letter: char) {
+        let (current, _) = self.browser_cursor();
+        let visible = self.focused_filter_indices();
+        let visible = visible.as_deref();
+        match self.browser_focus {
+            BrowserFocus::Playlist => {
+                let entries = &self.playlist_browser.playlist.entries;
+                if let Some(index) = visible_jump_target(entries, visible, current, letter) {
+                    self.playlist_browser.state.select(Some(index));
+                }
+            }
+            // Search results replace the listing, so there is nothing to jump in
+            BrowserFocus::Hvsc if self.hvsc_search.is_some() => {}
+            BrowserFocus::Hvsc => {
+                let entries = &self.hvsc_browser.entries;
+                if let Some(index) = visible_jump_target(entries, visible, current, letter) {
+                    self.hvsc_browser.selected = index;
+                }
+            }
+        }
+    }
+
+    /// Returns the focused browser's selected index and entry count.
+    fn browser_cursor(&self) -> (usize, usize) {
+        match self.browser_focus {
+            BrowserFocus::Playlist => (
+                self.playlist_browser.selected_index(),
+                self.playlist_browser.playlist.len(),
+            ),
+            BrowserFocus::Hvsc => (self.hvsc_browser.selected, self.hvsc_browser.entries.len()),
+        }
+    }
+
+    /// Finds the next entry (wrapping) whose name starts with the last vim search,
+    /// starting `skip` entries away from `current`.
+    fn find_vim_match(
+        &self,
+        current: usize,
+        len: usize,
+        skip: usize,
+        forward: bool,
+    ) -> Option<usize> {
+        let pattern = self.vim_search.as_deref()?;
+        let name = |i: usize| match self.browser_focus {
+            BrowserFocus::Playlist => &self.playlist_browser.playlist.entries[i].display_name,
+            BrowserFocus::Hvsc => &self.hvsc_browser.entries[i].name,
+        };
+        (0..len)
+            .map(|offset| {
+                let step = offset + skip;
+                if forward {
+                    (current + step) % len
+                } else {
+                    (current + len * 2 - step % len) % len
+                }
+            })
+            .find(|&i| name(i).to_lowercase().starts_with(pattern))
+    }
+
     pub fn browser_back(&mut self) {
         if self.browser_focus == BrowserFocus::Hvsc {
+            self.clear_browser_filter();
             self.hvsc_browser.go_up();
         }
     }
 
     pub fn load_selected(&mut self) {
+        if self.focused_filter_indices().is_some_and(|v| v.is_empty()) {
+            return;
+        }
         match self.browser_focus {
             BrowserFocus::Playlist => self.load_playlist_selected(),
             BrowserFocus::Hvsc => self.load_hvsc_selected(),
@@ -357,8 +1286,9 @@ impl<'a> App<'a> {
             match entry.load() {
                 Ok(sid_file) => {
                     let song = subsong.unwrap_or(sid_file.start_song);
-                    if self.play_sid_file(sid_file, song, source) {
+                    if self.play_sid_file(sid_file, song, source.clone()) {
                         self.playlist_browser.state.select(Some(idx));
+                        self.remember_played(source);
                         return;
                     }
                 }
@@ -372,13 +1302,39 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Selects a random playlist entry, avoiding recently played ones while others remain.
+    fn select_random_playlist_entry(&mut self) {
+        let playlist = &self.playlist_browser.playlist;
+        let recent = recent_indices(playlist, &self.recently_played);
+        let index = playlist
+            .random_entry_excluding(&recent)
+            .or_else(|| playlist.random_entry_excluding(&[]))
+            .map(|(index, _)| index);
+        if index.is_some() {
+            self.playlist_browser.state.select(index);
+        }
+    }
+
+    /// Records a played playlist entry by source, keeping the last `RECENTLY_PLAYED_LEN`.
+    fn remember_played(&mut self, source: String) {
+        self.recently_played.retain(|s| *s != source);
+        if self.recently_played.len() == RECENTLY_PLAYED_LEN {
+            self.recently_played.pop_front();
+        }
+        self.recently_played.push_back(source);
+    }
+
     fn load_hvsc_selected(&mut self) {
+        // A filter typed for one directory doesn't carry over into the next
+        if self.hvsc_browser.selected_entry().is_some_and(|e| e.is_dir) {
+            self.clear_browser_filter();
+        }
         let Some(entry) = self.hvsc_browser.enter() else {
             return;
         };
 
         let source = entry.url(&self.hvsc_browser.base_url);
-        match entry.load(&self.hvsc_browser.base_url) {
+        match entry.load(&self.hvsc_browser.base_url, self.hvsc_browser.fetch_timeout) {
             Ok(sid_file) => {
                 let start_song = sid_file.start_song;
                 self.play_sid_file(sid_file, start_song, source);
@@ -404,7 +1360,7 @@ impl<'a> App<'a> {
 
             self.hvsc_browser.selected = idx;
             let source = entry.url(&base_url);
-            match entry.load(&base_url) {
+            match entry.load(&base_url, self.hvsc_browser.fetch_timeout) {
                 Ok(sid_file) => {
                     let start_song = sid_file.start_song;
                     if self.play_sid_file(sid_file, start_song, source) {
@@ -422,6 +1378,19 @@ impl<'a> App<'a> {
 
     /// Attempts to play a SID file. Returns true on success, false on failure.
     fn play_sid_file(&mut self, sid_file: SidFile, song: u16, source: String) -> bool {
+        if self.advancing {
+            return self.crossfade_to(sid_file, song, source);
+        }
+        self.start_tune(sid_file, song, source, false)
+    }
+
+    /// Like `play_sid_file`, but the current tune fades out under the new one
+    /// instead of stopping abruptly.
+    pub fn crossfade_to(&mut self, sid_file: SidFile, song: u16, source: String) -> bool {
+        self.start_tune(sid_file, song, source, true)
+    }
+
+    fn start_tune(&mut self, sid_file: SidFile, song: u16, source: String, fade: bool) -> bool {
         if sid_file.requires_full_emulation() {
             self.show_error("Skipped: Unsupported RSID-like format".to_string());
             return false;
@@ -432,7 +1401,12 @@ impl<'a> App<'a> {
 
         let error = match self.player.lock() {
             Ok(mut player) => {
-                let res = player.load_sid_file(&sid_file, song);
+                player.clear_underrun_count();
+                let res = if fade {
+                    player.crossfade_to(&sid_file, song)
+                } else {
+                    player.load_sid_file(&sid_file, song)
+                };
                 match res {
                     Ok(_) => {
                         self.chip_models = player.chip_models().to_vec();
@@ -452,6 +1426,9 @@ impl<'a> App<'a> {
 
         self.update_song_timeout(&sid_file.md5, song);
         self.current_browser_sid = Some(sid_file);
+        self.load_subsong_overrides();
+        self.chip_override_active = false;
+        self.apply_subsong_chip(song);
         self.current_source = Some(source);
         self.song_elapsed = Duration::ZERO;
         self.song_resumed_at = Instant::now();
@@ -481,58 +1458,318 @@ impl<'a> App<'a> {
         self.playlist_modified = true;
     }
 
-    pub fn save_playlist(&self) {
-        if let Err(e) = self.playlist_browser.playlist.save(&self.playlist_path) {
-            eprintln!("Failed to save playlist: {e}");
+    /// Opens the prompt for a playlist file to merge, if the playlist is focused (Ctrl+O).
+    pub fn open_merge_playlist(&mut self) {
+        if self.browser_focus == BrowserFocus::Playlist {
+            self.popup = Popup::MergePlaylist {
+                path: String::new(),
+            };
         }
     }
 
-    // HVSC methods
-    pub fn refresh_hvsc_cache(&mut self) {
-        self.hvsc_browser.refresh_cache();
-    }
-
-    pub fn start_hvsc_search(&mut self) {
-        if self.browser_focus == BrowserFocus::Hvsc {
-            self.hvsc_search = Some(String::new());
-            self.hvsc_search_results.clear();
-            self.hvsc_search_index = 0;
-            self.popup = Popup::HvscSearch;
+    /// Merges the playlist file at `path` into the current playlist.
+    pub fn merge_playlist_file(&mut self, path: &str) {
+        self.popup = Popup::None;
+        let path = path.trim();
+        if path.is_empty() {
+            return;
+        }
+        match Playlist::load(path) {
+            Ok(other) => {
+                if self.playlist_browser.playlist.merge(&other) > 0 {
+                    self.playlist_modified = true;
+                    if self.playlist_browser.state.selected().is_none() {
+                        self.playlist_browser.state.select(Some(0));
+                    }
+                }
+                self.playlist_merged_at = Some(Instant::now());
+            }
+            Err(e) => self.show_warning(format!("Cannot merge {path}: {e}")),
         }
     }
 
-    pub fn cancel_hvsc_search(&mut self) {
-        self.hvsc_search = None;
-        self.hvsc_search_results.clear();
+    /// Playlist size to show in the panel title shortly after a merge.
+    pub fn merged_playlist_len(&self) -> Option<usize> {
+        self.playlist_merged_at
+            .filter(|at| at.elapsed() < MERGE_TITLE_DURATION)
+            .map(|_| self.playlist_browser.playlist.len())
     }
 
-    pub fn hvsc_search_input(&mut self, ch: char) {
-        if let Some(ref mut query) = self.hvsc_search {
-            query.push(ch);
+    pub fn open_sort_popup(&mut self) {
+        if self.browser_focus == BrowserFocus::Playlist {
+            self.popup = Popup::SortPlaylist {
+                ignore_articles: true,
+            };
         }
     }
 
-    pub fn hvsc_search_backspace(&mut self) {
-        if let Some(ref mut query) = self.hvsc_search {
-            query.pop();
+    /// Sorts the playlist, keeping the selected entry selected.
+    pub fn sort_playlist(&mut self, key: SortKey) {
+        self.popup = Popup::None;
+        let index = self.playlist_browser.selected_index();
+        let playlist = &mut self.playlist_browser.playlist;
+        let selected = playlist
+            .entries
+            .get(index)
+            .map(|e| (e.source.clone(), e.subsong));
+        playlist.sort(key);
+        if let Some((source, subsong)) = selected {
+            let index = playlist
+                .entries
+                .iter()
+                .position(|e| e.source == source && e.subsong == subsong);
+            self.playlist_browser.state.select(index);
         }
+        self.playlist_modified = true;
     }
 
-    pub fn update_search_results(&mut self) {
-        let query = match &self.hvsc_search {
-            Some(q) if !q.is_empty() => q.clone(),
-            _ => {
-                self.hvsc_search_results.clear();
-                return;
-            }
+    /// Moves the selected playlist entry by one position (Ctrl+Up/Down).
+    pub fn move_playlist_entry(&mut self, down: bool) {
+        if self.browser_focus != BrowserFocus::Playlist || self.playlist_browser.playlist.is_empty()
+        {
+            return;
+        }
+        let from = self.playlist_browser.selected_index();
+        let to = if down {
+            (from + 1).min(self.playlist_browser.playlist.len() - 1)
+        } else {
+            from.saturating_sub(1)
         };
-
-        if let Some(ref stil) = self.hvsc_browser.stil {
-            self.hvsc_search_results = stil.search(&query).into_iter().map(String::from).collect();
-            self.hvsc_search_results.sort();
-            self.hvsc_search_results.truncate(100);
-            self.hvsc_search_index = 0;
+        if from == to {
+            return;
         }
+        self.playlist_browser.playlist.move_entry(from, to);
+        self.playlist_browser.state.select(Some(to));
+        self.playlist_modified = true;
+    }
+
+    /// Asks to replace the playlist entry at `idx` with one entry per subsong (`X`).
+    /// The file is loaded to learn its subsong count.
+    pub fn duplicate_playlist_entry_as_all_subsongs(&mut self, idx: usize) {
+        let Some(entry) = self.playlist_browser.playlist.entries.get(idx).cloned() else {
+            return;
+        };
+        if entry.subsong.is_some() {
+            self.show_warning(format!("{} already plays one subsong", entry.display_name));
+            return;
+        }
+        match entry.load_with_timeout(self.hvsc_browser.fetch_timeout) {
+            Ok(sid_file) if sid_file.songs > 1 => {
+                self.popup = Popup::ExpandConfirm {
+                    index: idx,
+                    songs: sid_file.songs,
+                };
+            }
+            Ok(_) => self.show_warning(format!("{} has a single subsong", entry.display_name)),
+            Err(e) => self.show_error(format!("Cannot expand {}: {e}", entry.display_name)),
+        }
+    }
+
+    /// Expands the selected playlist entry into its subsongs, after confirmation.
+    pub fn expand_selected_playlist_entry(&mut self) {
+        if self.browser_focus == BrowserFocus::Playlist {
+            self.duplicate_playlist_entry_as_all_subsongs(self.playlist_browser.selected_index());
+        }
+    }
+
+    /// Performs the expansion asked for by `Popup::ExpandConfirm`, selecting the first copy.
+    pub fn confirm_playlist_expansion(&mut self) {
+        let Popup::ExpandConfirm { index, songs } = self.popup else {
+            return;
+        };
+        self.popup = Popup::None;
+        let playlist = &mut self.playlist_browser.playlist;
+        let Some(entry) = playlist.entries.get(index) else {
+            return;
+        };
+        let expanded = (1..=songs)
+            .map(|song| PlaylistEntry {
+                subsong: Some(song),
+                ..entry.clone()
+            })
+            .collect();
+        if playlist.replace_with_expansion(index, expanded) {
+            self.playlist_browser.state.select(Some(index));
+            self.playlist_modified = true;
+        }
+    }
+
+    /// Shuffles the playlist and selects its first entry (Ctrl+R).
+    pub fn randomize_playlist(&mut self) {
+        if self.browser_focus != BrowserFocus::Playlist || self.playlist_browser.playlist.is_empty()
+        {
+            return;
+        }
+        self.playlist_browser.playlist.shuffle(&mut rand::rng());
+        self.playlist_browser.state.select(Some(0));
+        self.playlist_modified = true;
+    }
+
+    /// Restores the order from before the last shuffle (Ctrl+Z).
+    pub fn undo_playlist_shuffle(&mut self) {
+        if self.browser_focus != BrowserFocus::Playlist {
+            return;
+        }
+        if self.playlist_browser.playlist.undo_shuffle() {
+            self.playlist_browser.state.select(Some(0));
+            self.playlist_modified = true;
+        }
+    }
+
+    pub fn save_playlist(&self) {
+        if self.portable_playlist {
+            self.save_playlist_portable();
+        } else if let Err(e) = self.playlist_browser.playlist.save(&self.playlist_path) {
+            eprintln!("Failed to save playlist: {e}");
+        }
+    }
+
+    /// Saves the playlist with local paths relative to the playlist's directory.
+    pub fn save_playlist_portable(&self) {
+        let base = self.playlist_path.parent().unwrap_or(Path::new("."));
+        if let Err(e) = self
+            .playlist_browser
+            .playlist
+            .save_relative(base, &self.playlist_path)
+        {
+            eprintln!("Failed to save playlist: {e}");
+        }
+    }
+
+    /// Starts recording, or stops and writes `<title>_<song>.wav` to the current directory.
+    pub fn toggle_recording(&mut self) {
+        let Ok(mut player) = self.player.lock() else {
+            return;
+        };
+        if !player.is_recording() {
+            player.start_recording();
+            self.recording = true;
+            return;
+        }
+
+        let samples = player.stop_recording().unwrap_or_default();
+        let sample_rate = player.sample_rate();
+        drop(player);
+        self.recording = false;
+
+        let path = PathBuf::from(recording_filename(
+            &self.display_sid().name,
+            self.current_song,
+        ));
+        match crate::wav::write_wav(&path, &samples, sample_rate) {
+            Ok(()) => {
+                self.notify(format!("Saved {}", path.display()), NOTIFICATION_DURATION);
+            }
+            Err(e) => self.show_error(format!("Recording failed: {e}")),
+        }
+    }
+
+    // HVSC methods
+    pub fn refresh_hvsc_cache(&mut self) {
+        self.hvsc_browser.refresh_cache();
+        self.notify("HVSC cache refreshed".to_string(), NOTIFICATION_DURATION);
+    }
+
+    pub fn start_hvsc_search(&mut self) {
+        if self.browser_focus == BrowserFocus::Hvsc {
+            self.hvsc_search = Some(String::new());
+            self.hvsc_search_results.clear();
+            self.hvsc_search_index = 0;
+            self.popup = Popup::HvscSearch;
+        }
+    }
+
+    pub fn cancel_hvsc_search(&mut self) {
+        self.hvsc_search = None;
+        self.hvsc_search_results.clear();
+    }
+
+    pub fn hvsc_search_input(&mut self, ch: char) {
+        if let Some(ref mut query) = self.hvsc_search {
+            query.push(ch);
+        }
+    }
+
+    pub fn hvsc_search_backspace(&mut self) {
+        if let Some(ref mut query) = self.hvsc_search {
+            query.pop();
+        }
+    }
+
+    pub fn update_search_results(&mut self) {
+        let query = match &self.hvsc_search {
+            Some(q) if !q.is_empty() => q.clone(),
+            _ => {
+                self.hvsc_search_results.clear();
+                return;
+            }
+        };
+
+        self.hvsc_search_results = match &self.hvsc_browser.stil {
+            Some(stil) => {
+                let results = if let Some(artist) = query.strip_prefix("artist:") {
+                    stil.files_by_artist(artist)
+                } else if let Some(genre) = query.strip_prefix("genre:") {
+                    stil.paths_for_genre(genre)
+                } else {
+                    stil.search(&query)
+                };
+                results.into_iter().map(String::from).collect()
+            }
+            // Without STIL, fall back to file names in the current directory
+            None => self
+                .hvsc_browser
+                .search_by_filename(&query)
+                .into_iter()
+                .filter(|entry| !entry.is_dir)
+                .map(|entry| entry.path)
+                .collect(),
+        };
+        self.hvsc_search_results.sort();
+        self.hvsc_search_results.truncate(100);
+        self.hvsc_search_index = 0;
+    }
+
+    /// Opens the list of STIL comment tags.
+    pub fn open_genre_browser(&mut self) {
+        match &self.hvsc_browser.stil {
+            None => self.show_warning("STIL not loaded".to_string()),
+            Some(stil) if stil.genre_index().is_empty() => {
+                self.show_warning("No genre tags in STIL".to_string());
+            }
+            Some(_) => self.popup = Popup::GenreBrowser { selected: 0 },
+        }
+    }
+
+    /// Moves the genre browser selection by `delta`, clamped to the list.
+    pub fn genre_browser_step(&mut self, delta: isize) {
+        let count = self
+            .hvsc_browser
+            .stil
+            .as_ref()
+            .map_or(0, |s| s.genre_index().len());
+        if let Popup::GenreBrowser { selected } = &mut self.popup {
+            *selected = selected
+                .saturating_add_signed(delta)
+                .min(count.saturating_sub(1));
+        }
+    }
+
+    /// Shows the HVSC tunes tagged with the selected genre as search results.
+    pub fn search_selected_genre(&mut self) {
+        let Popup::GenreBrowser { selected } = self.popup else {
+            return;
+        };
+        let Some(stil) = &self.hvsc_browser.stil else {
+            return;
+        };
+        let Some(genre) = stil.genres().get(selected).map(|g| g.to_string()) else {
+            return;
+        };
+        self.popup = Popup::None;
+        self.browser_focus = BrowserFocus::Hvsc;
+        self.hvsc_search = Some(format!("genre:{genre}"));
+        self.update_search_results();
     }
 
     pub fn hvsc_search_next(&mut self) {
@@ -569,14 +1806,10 @@ impl<'a> App<'a> {
         for offset in start_offset..len {
             let idx = (start + offset) % len;
             let path = &self.hvsc_search_results[idx];
-            let entry = HvscEntry {
-                name: path.rsplit('/').next().unwrap_or(path).to_string(),
-                path: path.clone(),
-                is_dir: false,
-            };
+            let entry = HvscEntry::new_file(path.rsplit('/').next().unwrap_or(path), path);
             let source = entry.url(&self.hvsc_browser.base_url);
 
-            match entry.load(&self.hvsc_browser.base_url) {
+            match entry.load(&self.hvsc_browser.base_url, self.hvsc_browser.fetch_timeout) {
                 Ok(sid_file) => {
                     let start_song = sid_file.start_song;
                     if self.play_sid_file(sid_file, start_song, source) {
@@ -594,20 +1827,397 @@ impl<'a> App<'a> {
         }
     }
 
+    // Subsong chip override methods
+    /// Loads the saved chip overrides for the displayed tune from the config.
+    fn load_subsong_overrides(&mut self) {
+        let md5 = self.display_sid().md5.clone();
+        self.subsong_chip_overrides = (1..=self.total_songs)
+            .filter_map(|song| {
+                let model = self
+                    .config
+                    .subsong_chip_overrides
+                    .get(&subsong_key(&md5, song))?;
+                Some((song, chip_model_from_number(*model)))
+            })
+            .collect();
+    }
+
+    /// Switches the first SID to the subsong's override, or back to the default model.
+    fn apply_subsong_chip(&mut self, song: u16) {
+        let Ok(mut player) = self.player.lock() else {
+            return;
+        };
+        // Songs without an override keep whatever chip the user last chose
+        if !self.chip_override_active
+            && let Some(&model) = player.chip_models().first()
+        {
+            self.default_chip_model = model;
+        }
+        let target = self.subsong_chip_overrides.get(&song).copied();
+        self.chip_override_active = target.is_some();
+        player.set_chip_model(0, target.unwrap_or(self.default_chip_model));
+        self.chip_models = player.chip_models().to_vec();
+    }
+
+    /// Shows the playing tune in the HVSC browser, using the path Songlengths
+    /// records for its MD5 (G).
+    pub fn goto_current_tune(&mut self) {
+        let md5 = self
+            .current_browser_sid
+            .as_ref()
+            .map_or(&self.sid_file.md5, |s| &s.md5);
+        let Some(path) = self
+            .hvsc_browser
+            .songlengths
+            .as_ref()
+            .and_then(|db| db.path_for_md5(md5))
+            .map(str::to_string)
+        else {
+            self.show_warning("Current tune not found in Songlengths".to_string());
+            return;
+        };
+        self.cancel_hvsc_search();
+        self.browser_focus = BrowserFocus::Hvsc;
+        if !self.hvsc_browser.reveal_file(&path) {
+            self.show_warning(format!("{path} not found in HVSC listing"));
+        }
+    }
+
+    /// Cycles the HVSC listing order when HVSC is focused, else opens the
+    /// per-subsong chip overrides (Ctrl+S).
+    pub fn cycle_hvsc_sort_or_subsong_overrides(&mut self) {
+        if self.browser_focus != BrowserFocus::Hvsc {
+            self.open_subsong_override_popup();
+        } else if !self.hvsc_browser.cycle_sort_mode() {
+            self.show_warning("Sorting by title needs STIL".to_string());
+        }
+    }
+
+    pub fn open_subsong_override_popup(&mut self) {
+        self.subsong_override_index = usize::from(self.current_song.saturating_sub(1));
+        self.popup = Popup::SubsongChipOverride;
+    }
+
+    pub fn subsong_override_next(&mut self) {
+        let last = usize::from(self.total_songs.saturating_sub(1));
+        self.subsong_override_index = (self.subsong_override_index + 1).min(last);
+    }
+
+    pub fn subsong_override_prev(&mut self) {
+        self.subsong_override_index = self.subsong_override_index.saturating_sub(1);
+    }
+
+    /// Cycles the highlighted subsong through default -> 6581 -> 8580 -> default.
+    pub fn cycle_subsong_override(&mut self) {
+        #[allow(clippy::cast_possible_truncation)]
+        let song = self.subsong_override_index as u16 + 1;
+        let next = match self.subsong_chip_overrides.get(&song) {
+            None => Some(ChipModel::Mos6581),
+            Some(ChipModel::Mos6581) => Some(ChipModel::Mos8580),
+            Some(ChipModel::Mos8580) => None,
+        };
+
+        let key = subsong_key(&self.display_sid().md5, song);
+        match next {
+            Some(model) => {
+                self.subsong_chip_overrides.insert(song, model);
+                self.config
+                    .subsong_chip_overrides
+                    .insert(key, chip_model_number(model));
+            }
+            None => {
+                self.subsong_chip_overrides.remove(&song);
+                self.config.subsong_chip_overrides.remove(&key);
+            }
+        }
+
+        if song == self.current_song {
+            self.apply_subsong_chip(song);
+        }
+    }
+
+    // Memory dump methods
+    /// Opens a hex dump of 256 bytes around the tune's load address.
+    pub fn open_memory_dump(&mut self) {
+        let Ok(player) = self.player.lock() else {
+            return;
+        };
+        let start = player.load_address().saturating_sub(MEM_DUMP_LEN / 2) & 0xFFF0;
+        drop(player);
+        self.show_memory_dump(start);
+    }
+
+    /// Scrolls the memory dump by `delta` bytes, wrapping around the address space.
+    pub fn scroll_memory_dump(&mut self, delta: i16) {
+        if let Popup::MemDump { start, .. } = self.popup {
+            self.show_memory_dump(start.wrapping_add_signed(delta));
+        }
+    }
+
+    fn show_memory_dump(&mut self, start: u16) {
+        if let Ok(mut player) = self.player.lock() {
+            let data = player.read_memory(start, MEM_DUMP_LEN);
+            self.popup = Popup::MemDump { start, data };
+        }
+    }
+
+    // Sampling method methods
+    pub fn open_sampling_method_picker(&mut self) {
+        self.sampling_method_index = self.sampling_method;
+        self.popup = Popup::SamplingMethod;
+    }
+
+    pub fn sampling_method_next(&mut self) {
+        self.sampling_method_index =
+            (self.sampling_method_index + 1).min(SAMPLING_METHODS.len() - 1);
+    }
+
+    pub fn sampling_method_prev(&mut self) {
+        self.sampling_method_index = self.sampling_method_index.saturating_sub(1);
+    }
+
+    /// Applies the highlighted sampling method to the player and config.
+    pub fn apply_sampling_method(&mut self) {
+        let (name, method) = SAMPLING_METHODS[self.sampling_method_index];
+        if let Ok(mut player) = self.player.lock() {
+            player.set_sampling_method(method);
+        }
+        self.sampling_method = self.sampling_method_index;
+        self.config.sampling_method = name.to_string();
+        self.popup = Popup::None;
+    }
+
+    /// Switches to the next SID mix mode and saves it to the config (Alt+M).
+    pub fn cycle_mix_mode(&mut self) {
+        self.mix_mode = (self.mix_mode + 1) % MIX_MODES.len();
+        let (name, mode) = MIX_MODES[self.mix_mode];
+        if let Ok(mut player) = self.player.lock() {
+            player.set_mix_mode(mode);
+        }
+        self.config.mix_mode = name.to_string();
+        self.notify(format!("Mix mode: {name}"), NOTIFICATION_DURATION);
+    }
+
+    /// Switches the oscilloscopes to the next marker and saves it to the config.
+    pub fn cycle_scope_marker(&mut self) {
+        let next = SCOPE_MARKERS
+            .iter()
+            .position(|&(_, marker)| marker == self.scope_marker)
+            .map_or(0, |i| (i + 1) % SCOPE_MARKERS.len());
+        let (name, marker) = SCOPE_MARKERS[next];
+        self.scope_marker = marker;
+        self.config.scope_marker = name.to_string();
+    }
+
+    /// Solos a voice (ignored if the tune has fewer voices), or clears solo with `None`.
+    pub fn set_solo_voice(&mut self, voice: Option<usize>) {
+        if voice.is_none_or(|v| v < self.vu_meter.voice_count()) {
+            self.solo_voice = voice;
+        }
+    }
+
+    /// Toggles the filter bypass of a SID (0-indexed).
+    pub fn toggle_filter_bypass(&mut self, sid_index: usize) {
+        if let Ok(mut player) = self.player.lock()
+            && sid_index < player.sid_count()
+        {
+            let bypassed = !player.is_filter_bypassed(sid_index);
+            player.set_filter_override(sid_index, bypassed);
+        }
+    }
+
+    /// Changes playback speed by `delta`, rounded to one decimal.
+    pub fn change_speed(&mut self, delta: f32) {
+        if let Ok(mut player) = self.player.lock() {
+            let target = ((player.speed_factor() + delta) * 10.0).round() / 10.0;
+            player.set_speed_factor(target);
+            self.speed_factor = player.speed_factor();
+        }
+    }
+
+    // Mirror list methods
+    /// Opens the HVSC mirror list (Ctrl+L).
+    pub fn open_mirror_list(&mut self) {
+        self.mirror_list_index = self.hvsc_browser.active_mirror;
+        self.popup = Popup::MirrorList;
+    }
+
+    pub fn mirror_list_next(&mut self) {
+        let len = self.hvsc_browser.mirrors.len();
+        if len > 0 {
+            self.mirror_list_index = (self.mirror_list_index + 1).min(len - 1);
+        }
+    }
+
+    pub fn mirror_list_prev(&mut self) {
+        self.mirror_list_index = self.mirror_list_index.saturating_sub(1);
+    }
+
+    /// Moves the selected mirror up (earlier) in the fallback order.
+    pub fn move_mirror_up(&mut self) {
+        let idx = self.mirror_list_index;
+        if idx > 0 {
+            self.hvsc_browser.move_mirror(idx, idx - 1);
+            self.mirror_list_index = idx - 1;
+            self.sync_mirrors_to_config();
+        }
+    }
+
+    /// Moves the selected mirror down (later) in the fallback order.
+    pub fn move_mirror_down(&mut self) {
+        let idx = self.mirror_list_index;
+        if idx + 1 < self.hvsc_browser.mirrors.len() {
+            self.hvsc_browser.move_mirror(idx, idx + 1);
+            self.mirror_list_index = idx + 1;
+            self.sync_mirrors_to_config();
+        }
+    }
+
+    pub fn remove_selected_mirror(&mut self) {
+        self.hvsc_browser.remove_mirror(self.mirror_list_index);
+        self.mirror_list_index = self
+            .mirror_list_index
+            .min(self.hvsc_browser.mirrors.len().saturating_sub(1));
+        self.sync_mirrors_to_config();
+    }
+
+    fn sync_mirrors_to_config(&mut self) {
+        self.config.hvsc_mirrors = self.hvsc_browser.mirrors.clone();
+    }
+
     // Color scheme methods
     pub fn open_color_picker(&mut self) {
         self.popup = Popup::ColorScheme;
     }
 
     pub fn next_color_scheme(&mut self) {
-        self.color_scheme = (self.color_scheme + 1) % SCHEMES.len();
+        self.color_scheme = (self.color_scheme + 1) % self.schemes.len();
+        self.refresh_scheme();
     }
 
     pub fn prev_color_scheme(&mut self) {
         self.color_scheme = self
             .color_scheme
             .checked_sub(1)
-            .unwrap_or(SCHEMES.len() - 1);
+            .unwrap_or(self.schemes.len() - 1);
+        self.refresh_scheme();
+    }
+
+    /// Rebuilds the active scheme from the selection and its voice color overrides.
+    fn refresh_scheme(&mut self) {
+        let scheme = &self.schemes[self.color_scheme];
+        self.custom_scheme = scheme
+            .clone()
+            .with_voice_colors(self.config.custom_voice_colors.get(scheme.name.as_ref()));
+    }
+
+    /// Opens the RGB editor for a voice color (0-indexed), seeded with its current color.
+    pub fn open_voice_color_editor(&mut self, voice_idx: usize) {
+        let Some(&[r, g, b]) = self.custom_scheme.voice_rgb().get(voice_idx) else {
+            return;
+        };
+        self.popup = Popup::VoiceColorEditor {
+            voice_idx,
+            r,
+            g,
+            b,
+            field: 0,
+        };
+    }
+
+    /// Moves the voice color editor to the next RGB field.
+    pub fn voice_color_next_field(&mut self) {
+        if let Popup::VoiceColorEditor { field, .. } = &mut self.popup {
+            *field = (*field + 1) % 3;
+        }
+    }
+
+    /// Returns the RGB channel being edited in the voice color editor.
+    fn voice_color_field(&mut self) -> Option<&mut u8> {
+        let Popup::VoiceColorEditor { r, g, b, field, .. } = &mut self.popup else {
+            return None;
+        };
+        match field {
+            0 => Some(r),
+            1 => Some(g),
+            _ => Some(b),
+        }
+    }
+
+    /// Types a digit into the active RGB field, starting over once it would exceed 255.
+    pub fn voice_color_digit(&mut self, digit: u8) {
+        if let Some(value) = self.voice_color_field() {
+            let typed = u16::from(*value) * 10 + u16::from(digit);
+            *value = u8::try_from(typed).unwrap_or(digit);
+        }
+    }
+
+    /// Removes the last digit of the active RGB field.
+    pub fn voice_color_backspace(&mut self) {
+        if let Some(value) = self.voice_color_field() {
+            *value /= 10;
+        }
+    }
+
+    /// Saves the edited voice color as an override for the current scheme and closes the editor.
+    pub fn apply_voice_color(&mut self) {
+        if let Popup::VoiceColorEditor {
+            voice_idx, r, g, b, ..
+        } = self.popup
+        {
+            let name = self.schemes[self.color_scheme].name.to_string();
+            let colors = self
+                .config
+                .custom_voice_colors
+                .entry(name)
+                .or_insert_with(|| self.custom_scheme.voice_rgb());
+            colors[voice_idx] = [r, g, b];
+            self.refresh_scheme();
+        }
+        self.popup = Popup::None;
+    }
+
+    /// Opens the output filter sliders.
+    pub fn open_equalizer(&mut self) {
+        self.popup = Popup::Equalizer { field: 0 };
+    }
+
+    /// Switches between the cutoff and resonance sliders.
+    pub fn equalizer_next_field(&mut self) {
+        if let Popup::Equalizer { field } = &mut self.popup {
+            *field = (*field + 1) % 2;
+        }
+    }
+
+    /// Moves the selected slider by `delta`, enabling the filter.
+    pub fn adjust_equalizer(&mut self, delta: f32) {
+        let Popup::Equalizer { field } = self.popup else {
+            return;
+        };
+        let value = if field == 0 {
+            &mut self.eq_cutoff
+        } else {
+            &mut self.eq_resonance
+        };
+        *value = (*value + delta).clamp(0.0, 1.0);
+        self.eq_enabled = true;
+        self.sync_equalizer();
+    }
+
+    /// Turns the output filter on or off, keeping the slider positions.
+    pub fn toggle_equalizer(&mut self) {
+        self.eq_enabled = !self.eq_enabled;
+        self.sync_equalizer();
+    }
+
+    fn sync_equalizer(&self) {
+        if let Ok(mut player) = self.player.lock() {
+            if self.eq_enabled {
+                player.apply_global_filter(self.eq_cutoff, self.eq_resonance);
+            } else {
+                player.disable_global_filter();
+            }
+        }
     }
 
     // Popup methods
@@ -615,8 +2225,23 @@ impl<'a> App<'a> {
         self.popup = Popup::Help;
     }
 
+    /// Shows `msg` in the corner for `duration` without blocking input.
+    pub fn notify(&mut self, msg: String, duration: Duration) {
+        if self.notification_queue.len() == MAX_NOTIFICATIONS {
+            self.notification_queue.pop_front();
+        }
+        self.notification_queue
+            .push_back((msg, Instant::now() + duration));
+    }
+
+    /// Reports a problem that doesn't stop playback, as a notification.
+    /// `show_error` is for failures the user has to acknowledge.
+    pub fn show_warning(&mut self, msg: String) {
+        self.notify(msg, NOTIFICATION_DURATION);
+    }
+
     pub fn show_error(&mut self, msg: String) {
-        self.popup = Popup::Error(msg);
+        queue_error(&mut self.popup, &mut self.error_queue, msg);
         // Pause playback so user can read the error
         if let Ok(mut player) = self.player.lock()
             && !player.is_paused()
@@ -627,10 +2252,186 @@ impl<'a> App<'a> {
         }
     }
 
-    pub fn close_popup(&mut self) {
+    /// Returns header validation warnings for the displayed SID file.
+    pub fn validation_warnings(&self) -> Vec<ValidationWarning> {
+        self.display_sid().validate()
+    }
+
+    pub fn show_validation_warnings(&mut self) {
+        if !self.validation_warnings().is_empty() {
+            self.popup = Popup::ValidationWarnings;
+        }
+    }
+
+    pub fn show_hvsc_stats(&mut self) {
+        if self.hvsc_browser.songlengths.is_some() {
+            self.popup = Popup::HvscStats;
+        } else {
+            self.show_warning("Songlengths database not loaded".to_string());
+        }
+    }
+
+    /// Enters or leaves step mode, where emulation only advances with `step_frame`.
+    pub fn toggle_step_mode(&mut self) {
+        let Ok(mut player) = self.player.lock() else {
+            return;
+        };
+        let enabled = !player.is_step_mode();
+        player.set_step_mode(enabled);
+        self.step_frames = player.step_count();
+        self.step_envelopes = player.voice_levels();
+        drop(player);
+        self.popup = if enabled {
+            Popup::StepMode
+        } else {
+            Popup::None
+        };
+    }
+
+    /// Runs the play routine once and captures the resulting envelope levels.
+    pub fn step_frame(&mut self) {
+        let Ok(mut player) = self.player.lock() else {
+            return;
+        };
+        let result = player.step_frame(&mut self.step_buffer);
+        self.step_frames = player.step_count();
+        self.step_envelopes = player.voice_levels();
+        drop(player);
+        if let Err(e) = result {
+            self.toggle_step_mode();
+            self.show_error(format!("Step failed: {e}"));
+        }
+    }
+
+    pub fn show_perf_stats(&mut self) {
+        self.popup = Popup::PerfStats;
+    }
+
+    /// Opens the CPU register view (F4).
+    pub fn show_cpu_debug(&mut self) {
+        if let Ok(player) = self.player.lock() {
+            self.cpu_snapshot = Some(player.snapshot_cpu_registers());
+            self.last_init_cpu_snapshot = Some(player.init_cpu_snapshot());
+        }
+        self.popup = Popup::CpuDebug;
+    }
+
+    pub fn show_file_info(&mut self) {
+        self.popup = Popup::FileInfo { scroll: 0 };
+    }
+
+    /// Scrolls the file info popup by `delta` lines.
+    pub fn scroll_file_info(&mut self, delta: i16) {
+        if let Popup::FileInfo { scroll } = &mut self.popup {
+            *scroll = scroll.saturating_add_signed(delta);
+        }
+    }
+
+    /// Opens the write trace popup and starts recording SID writes.
+    pub fn open_write_trace(&mut self) {
+        if let Ok(mut player) = self.player.lock() {
+            player.enable_write_trace();
+        }
+        self.write_trace.clear();
+        self.popup = Popup::WriteTrace { scroll: 0 };
+    }
+
+    /// Stops recording SID writes and closes the write trace popup.
+    pub fn close_write_trace(&mut self) {
+        if let Ok(mut player) = self.player.lock() {
+            player.disable_write_trace();
+        }
         self.popup = Popup::None;
     }
 
+    /// Scrolls the write trace popup by `delta` lines.
+    pub fn scroll_write_trace(&mut self, delta: i16) {
+        if let Popup::WriteTrace { scroll } = &mut self.popup {
+            *scroll = scroll.saturating_add_signed(delta);
+        }
+    }
+
+    /// Opens the command line (`:`).
+    pub fn start_command_mode(&mut self) {
+        self.input_mode = InputMode::CommandMode {
+            input: String::new(),
+        };
+        self.command_history_pos = None;
+    }
+
+    /// Leaves command mode without running anything (Esc).
+    pub fn cancel_command_mode(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn push_command_char(&mut self, c: char) {
+        if let InputMode::CommandMode { input } = &mut self.input_mode {
+            input.push(c);
+        }
+    }
+
+    pub fn pop_command_char(&mut self) {
+        if let InputMode::CommandMode { input } = &mut self.input_mode {
+            input.pop();
+        }
+    }
+
+    /// Replaces the command line with an older (Up) or newer (Down) history entry.
+    pub fn browse_command_history(&mut self, older: bool) {
+        let history = &self.config.command_history;
+        let InputMode::CommandMode { input } = &mut self.input_mode else {
+            return;
+        };
+        let pos = match (self.command_history_pos, older) {
+            (None, true) => history.len().checked_sub(1),
+            (None, false) => None,
+            (Some(pos), true) => Some(pos.saturating_sub(1)),
+            (Some(pos), false) => Some(pos + 1).filter(|&pos| pos < history.len()),
+        };
+        self.command_history_pos = pos;
+        *input = pos.map(|pos| history[pos].clone()).unwrap_or_default();
+    }
+
+    /// Runs the typed command line and leaves command mode (Enter).
+    pub fn run_command_line(&mut self) -> Action {
+        let InputMode::CommandMode { input } = std::mem::take(&mut self.input_mode) else {
+            return Action::None;
+        };
+        if input.trim().is_empty() {
+            return Action::None;
+        }
+        push_history(&mut self.config.command_history, input.trim());
+        match parse_command(&input) {
+            Ok(command) => return self.run_command(command),
+            Err(e) => self.command_error = Some(e),
+        }
+        Action::None
+    }
+
+    fn run_command(&mut self, command: Command) -> Action {
+        match command {
+            Command::Load(source) => self.load_source(&source),
+            Command::Goto(song) if song > self.total_songs => {
+                self.command_error = Some(format!("No song {song} (1-{})", self.total_songs));
+            }
+            Command::Goto(song) => self.goto_song(song),
+            Command::Volume(volume) => {
+                if let Ok(mut player) = self.player.lock() {
+                    player.set_master_volume(volume);
+                }
+                self.config.master_volume = volume;
+            }
+            Command::Quit if self.request_quit() => return Action::Quit,
+            Command::Quit => {}
+        }
+        Action::None
+    }
+
+    /// Closes the popup, moving on to the next queued error if there is one.
+    pub fn close_popup(&mut self) {
+        self.popup = popup_after_close(&self.popup, &mut self.error_queue);
+    }
+
     pub fn request_quit(&mut self) -> bool {
         if self.playlist_modified {
             self.popup = Popup::SaveConfirm;
@@ -640,3 +2441,423 @@ impl<'a> App<'a> {
         }
     }
 }
+
+/// Maps a config chip number (6581/8580) to a chip model.
+fn chip_model_from_number(model: u16) -> ChipModel {
+    if model == 8580 {
+        ChipModel::Mos8580
+    } else {
+        ChipModel::Mos6581
+    }
+}
+
+fn chip_model_number(model: ChipModel) -> u16 {
+    match model {
+        ChipModel::Mos6581 => 6581,
+        ChipModel::Mos8580 => 8580,
+    }
+}
+
+/// Builds a filesystem-safe `<title>_<song>.wav` name.
+fn recording_filename(title: &str, song: u16) -> String {
+    let safe: String = title
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let stem = if safe.is_empty() { "recording" } else { &safe };
+    format!("{stem}_{song}.wav")
+}
+
+/// Formats peak history as CSV with one row per frame and one column per voice.
+fn vu_history_csv(history: &[Vec<f32>]) -> String {
+    let voices = history.iter().map(Vec::len).max().unwrap_or(0);
+    let header: Vec<_> = (1..=voices).map(|v| format!("voice{v}")).collect();
+    let mut csv = header.join(",") + "\n";
+    for peaks in history {
+        let row: Vec<_> = peaks.iter().map(|peak| format!("{peak:.4}")).collect();
+        csv += &row.join(",");
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::player::{SamplingMethod, StepLimits, create_shared_player};
+    use crate::playlist::encode_sid_as_data_uri;
+    use ratatui::style::Color;
+
+    /// Builds an app playing a silent tune, with the HVSC databases left unloaded.
+    pub(crate) fn test_app(playlist: Playlist) -> App<'static> {
+        test_app_with_config(playlist, Config::default())
+    }
+
+    /// Like `test_app`, starting from the given user config.
+    fn test_app_with_config(playlist: Playlist, user_config: Config) -> App<'static> {
+        let sid_file: &'static SidFile = Box::leak(Box::new(crate::create_silent_sid()));
+        let player = create_shared_player(
+            sid_file,
+            1,
+            44_100,
+            None,
+            SamplingMethod::Fast,
+            StepLimits::default(),
+        )
+        .expect("player");
+        App::new(TuiConfig {
+            player,
+            sid_file,
+            source: None,
+            song: 1,
+            playlist,
+            playlist_path: PathBuf::new(),
+            portable: false,
+            focus_hvsc: false,
+            playlist_modified: false,
+            hvsc_url: "",
+            playtime_secs: 0,
+            user_config,
+            remote: None,
+            #[cfg(feature = "mpris")]
+            mpris: None,
+            exit_after: None,
+            smart_shuffle: false,
+            export_vu_history: None,
+            screenshot: None,
+            hvsc_listing: None,
+        })
+    }
+
+    /// A playlist of silent tunes embedded as data URIs, so they load without files.
+    fn silent_playlist(names: &[&str]) -> Playlist {
+        let mut playlist = Playlist::new();
+        for name in names {
+            let mut sid = crate::create_silent_sid();
+            sid.name = (*name).to_string();
+            playlist.add(&encode_sid_as_data_uri(&sid), None);
+        }
+        playlist
+    }
+
+    #[test]
+    fn exit_after_saves_modified_playlist() {
+        let dir = std::env::temp_dir().join(format!("crabsid-exit-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("playlist.m3u");
+
+        let mut app = test_app(silent_playlist(&["a"]));
+        app.playlist_path.clone_from(&path);
+        app.playlist_modified = true;
+        app.exit_after = Some(Duration::ZERO);
+        assert_eq!(app.update(), Action::Quit);
+        assert!(matches!(app.popup, Popup::None));
+        assert_eq!(Playlist::load(&path).unwrap().len(), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn voice_color_edit_applies_only_to_its_scheme() {
+        let mut app = test_app(Playlist::new());
+        let original = app.custom_scheme.voices;
+        app.popup = Popup::VoiceColorEditor {
+            voice_idx: 0,
+            r: 255,
+            g: 0,
+            b: 0,
+            field: 0,
+        };
+        app.apply_voice_color();
+        assert_eq!(app.custom_scheme.voices[0], Color::Rgb(255, 0, 0));
+        assert_eq!(app.custom_scheme.voices[1..], original[1..]);
+
+        app.next_color_scheme();
+        assert_eq!(
+            app.custom_scheme.voices,
+            app.schemes[app.color_scheme].voices
+        );
+        app.prev_color_scheme();
+        assert_eq!(app.custom_scheme.voices[0], Color::Rgb(255, 0, 0));
+        assert_eq!(app.into_config().custom_voice_colors.len(), 1);
+    }
+
+    #[test]
+    fn smart_shuffle_avoids_recent_tunes_after_reordering() {
+        let mut app = test_app(silent_playlist(&["a", "b", "c"]));
+        app.smart_shuffle = true;
+        for idx in [0, 1] {
+            app.playlist_browser.state.select(Some(idx));
+            app.load_playlist_selected();
+        }
+        let fresh = app.playlist_browser.playlist.entries[2].source.clone();
+
+        app.playlist_browser.playlist.entries.reverse();
+        for _ in 0..20 {
+            app.select_random_playlist_entry();
+            let idx = app.playlist_browser.selected_index();
+            assert_eq!(app.playlist_browser.playlist.entries[idx].source, fresh);
+        }
+
+        app.load_playlist_selected();
+        assert_eq!(app.recently_played.back(), Some(&fresh));
+        assert_eq!(app.into_config().recently_played.len(), 3);
+    }
+
+    #[test]
+    fn subsong_chip_override_applies_only_to_its_song() {
+        let mut app = test_app(Playlist::new());
+        app.total_songs = 3;
+        app.subsong_override_index = 1;
+        app.cycle_subsong_override();
+        app.cycle_subsong_override();
+        assert_eq!(
+            app.subsong_chip_overrides.get(&2),
+            Some(&ChipModel::Mos8580)
+        );
+
+        let player_chip = |app: &App| app.player.lock().unwrap().chip_models()[0];
+        let mut models = vec![player_chip(&app)];
+        for _ in 2..=3 {
+            app.next_song();
+            models.push(player_chip(&app));
+        }
+        app.prev_song();
+        models.push(player_chip(&app));
+        assert_eq!(
+            models,
+            [
+                ChipModel::Mos6581,
+                ChipModel::Mos8580,
+                ChipModel::Mos6581,
+                ChipModel::Mos8580
+            ]
+        );
+    }
+
+    #[test]
+    fn imported_scheme_is_selected_by_name() {
+        let imported = |name: &'static str| ColorScheme {
+            name: name.into(),
+            ..SCHEMES[1].clone()
+        };
+        let mut config = Config::default();
+        config.custom_schemes = vec![imported("Mine"), imported("Yours")];
+        config.custom_color_scheme = Some("Yours".to_string());
+        let app = test_app_with_config(Playlist::new(), config);
+        assert_eq!(app.custom_scheme.name, "Yours");
+
+        // Dropping an earlier scheme must not shift the selection
+        let mut config = app.into_config();
+        config.custom_schemes.remove(0);
+        let mut app = test_app_with_config(Playlist::new(), config);
+        assert_eq!(app.custom_scheme.name, "Yours");
+
+        app.color_scheme = 2;
+        app.refresh_scheme();
+        let config = app.into_config();
+        assert_eq!((config.color_scheme, config.custom_color_scheme), (2, None));
+    }
+
+    #[test]
+    fn vim_search_selects_matches_without_filtering() {
+        let mut playlist = silent_playlist(&["a", "b", "c"]);
+        for (entry, name) in playlist.entries.iter_mut().zip(["Alpha", "Beta", "Alto"]) {
+            entry.display_name = name.to_string();
+        }
+        let mut app = test_app(playlist);
+        app.run_vim_command(VimCommand::Search("al".to_string()));
+        assert_eq!(app.playlist_browser.selected_index(), 0);
+        app.run_vim_command(VimCommand::NextMatch);
+        assert_eq!(app.playlist_browser.selected_index(), 2);
+        app.run_vim_command(VimCommand::PrevMatch);
+        assert_eq!(app.playlist_browser.selected_index(), 0);
+        assert!(app.focused_filter_indices().is_none());
+    }
+
+    #[test]
+    fn session_remembers_source_of_playing_tune() {
+        let mut app = test_app(silent_playlist(&["a", "b"]));
+        app.playlist_browser.state.select(Some(1));
+        app.load_playlist_selected();
+        let entry = app.playlist_browser.playlist.entries[1].clone();
+        let session = app.into_config().last_session.expect("session");
+        assert_eq!(session.source, entry.source);
+        assert_eq!(session.md5, entry.load().unwrap().md5);
+    }
+
+    #[test]
+    fn exit_after_fires_after_n_updates() {
+        let frame = Duration::from_millis(100);
+        let exit_after = Some(Duration::from_secs(2));
+        let cycles = (1..=100)
+            .find(|&n| exit_due(exit_after, frame * n))
+            .expect("exit fires");
+        assert_eq!(cycles, 20);
+        assert!(!exit_due(None, Duration::MAX));
+    }
+
+    #[test]
+    fn notifications_expire_after_their_duration() {
+        let start = Instant::now();
+        let tick = Duration::from_millis(60);
+        let mut queue = VecDeque::from([
+            ("short".to_string(), start + Duration::from_millis(100)),
+            ("long".to_string(), start + Duration::from_secs(3)),
+        ]);
+        expire_notifications(&mut queue, start + tick);
+        assert_eq!(queue.len(), 2);
+        expire_notifications(&mut queue, start + 2 * tick);
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue[0].0, "long");
+    }
+
+    #[test]
+    fn queued_errors_show_one_after_another() {
+        let mut popup = Popup::None;
+        let mut queue = VecDeque::new();
+        for msg in ["first", "second", "third"] {
+            queue_error(&mut popup, &mut queue, msg.to_string());
+        }
+        for expected in ["first", "second", "third"] {
+            assert!(matches!(&popup, Popup::Error(msg) if msg == expected));
+            popup = popup_after_close(&popup, &mut queue);
+        }
+        assert!(matches!(popup, Popup::None));
+    }
+
+    #[test]
+    fn silence_advances_after_timeout() {
+        let timeout = Duration::from_secs(3);
+        let start = Instant::now();
+        let mut since = None;
+        assert!(!silence_timed_out(&mut since, true, start, timeout));
+        assert!(!silence_timed_out(
+            &mut since,
+            true,
+            start + Duration::from_secs(2),
+            timeout
+        ));
+        assert!(silence_timed_out(
+            &mut since,
+            true,
+            start + timeout,
+            timeout
+        ));
+
+        assert!(!silence_timed_out(
+            &mut since,
+            false,
+            start + timeout,
+            timeout
+        ));
+        assert_eq!(since, None);
+    }
+
+    #[test]
+    fn letter_jumps_to_first_match_and_cycles() {
+        let entries: Vec<HvscEntry> = ["Alpha", "Beta", "Rob", "Sid", "rock"]
+            .into_iter()
+            .map(|name| HvscEntry {
+                name: name.to_string(),
+                path: format!("/{name}.sid"),
+                is_dir: false,
+            })
+            .collect();
+        assert_eq!(find_first_with_prefix(&entries, 'r'), Some(2));
+        assert_eq!(jump_target(&entries, 0, 'r'), Some(2));
+        assert_eq!(jump_target(&entries, 2, 'R'), Some(4));
+        assert_eq!(jump_target(&entries, 4, 'r'), Some(2));
+        assert_eq!(jump_target(&entries, 0, 'z'), None);
+    }
+
+    #[test]
+    fn playlist_filter_moves_selection_and_clears() {
+        let mut playlist = Playlist::new();
+        for name in [
+            "Commando_Rob",
+            "Delta",
+            "robocop",
+            "Last_Ninja",
+            "Rob_Hubbard_Mix",
+        ] {
+            playlist.add(&format!("/sids/{name}.sid"), None);
+        }
+        let mut app = test_app(playlist);
+        app.playlist_browser.state.select(Some(1));
+        app.start_browser_filter();
+        for c in "Rob".chars() {
+            app.push_filter_char(c);
+        }
+        assert_eq!(
+            app.filter_indices_for(BrowserFocus::Playlist),
+            Some(vec![0, 2, 4])
+        );
+        assert_eq!(app.playlist_browser.state.selected(), Some(0));
+        app.browser_next();
+        assert_eq!(app.playlist_browser.selected_index(), 2);
+
+        // Nothing matches: no selection, and navigation stays put
+        app.push_filter_char('x');
+        assert_eq!(app.playlist_browser.state.selected(), None);
+        app.browser_next();
+        assert_eq!(app.playlist_browser.state.selected(), None);
+        app.pop_filter_char();
+        assert_eq!(app.playlist_browser.state.selected(), Some(0));
+        app.browser_prev();
+        assert_eq!(app.playlist_browser.selected_index(), 0);
+
+        app.browser_next();
+        app.clear_browser_filter();
+        assert_eq!(app.filter_indices_for(BrowserFocus::Playlist), None);
+        assert_eq!(app.playlist_browser.selected_index(), 2);
+        app.browser_next();
+        assert_eq!(app.playlist_browser.selected_index(), 3);
+    }
+
+    #[test]
+    fn letter_jump_skips_entries_hidden_by_filter() {
+        let mut playlist = Playlist::new();
+        for name in ["Rambo", "Delta", "Robocop", "Rob_Hubbard_Mix", "Last_Ninja"] {
+            playlist.add(&format!("/sids/{name}.sid"), None);
+        }
+        let mut app = test_app(playlist);
+        app.browser_filter = Some((BrowserFocus::Playlist, "ob".to_string()));
+        app.playlist_browser.state.select(Some(1));
+
+        app.jump_to_letter('r');
+        assert_eq!(app.playlist_browser.selected_index(), 2);
+        app.jump_to_letter('r');
+        assert_eq!(app.playlist_browser.selected_index(), 3);
+        app.jump_to_letter('r');
+        assert_eq!(app.playlist_browser.selected_index(), 2);
+        app.jump_to_letter('l');
+        assert_eq!(app.playlist_browser.selected_index(), 2);
+
+        app.clear_browser_filter();
+        app.jump_to_letter('l');
+        assert_eq!(app.playlist_browser.selected_index(), 4);
+    }
+
+    #[test]
+    fn playlist_filter_keeps_matching_entries() {
+        let entries: Vec<PlaylistEntry> = [
+            "Commando_Rob.sid",
+            "Delta.sid",
+            "robocop.sid",
+            "Last_Ninja.sid",
+            "Rob_Hubbard_Mix.sid",
+        ]
+        .into_iter()
+        .filter_map(PlaylistEntry::new)
+        .collect();
+        let visible = filter_indices(&entries, "Rob");
+        assert_eq!(visible, [0, 2, 4]);
+        assert!(
+            visible
+                .iter()
+                .all(|&i| entries[i].display_name.to_lowercase().contains("rob"))
+        );
+        assert_eq!(filter_indices(&entries, ""), [0, 1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Mikael Lund
+
+//! Command mode (`:`): a prompt for commands like `:goto 5` or `:volume 0.8`.
+
+/// Command lines remembered across runs
+pub const MAX_COMMAND_HISTORY: usize = 20;
+
+/// A parsed command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// `:load <path or URL>[@N]`
+    Load(String),
+    /// `:goto <song>`
+    Goto(u16),
+    /// `:volume <0.0-1.0>`
+    Volume(f32),
+    /// `:quit` or `:q`
+    Quit,
+}
+
+/// Parses a command line typed after `:`.
+pub fn parse_command(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    let (name, arg) = line
+        .split_once(char::is_whitespace)
+        .map_or((line, ""), |(name, arg)| (name, arg.trim()));
+    match name {
+        "load" if !arg.is_empty() => Ok(Command::Load(arg.to_string())),
+        "load" => Err("Usage: load <path>".to_string()),
+        "goto" => arg
+            .parse()
+            .ok()
+            .filter(|&song| song > 0)
+            .map(Command::Goto)
+            .ok_or_else(|| "Usage: goto <song>".to_string()),
+        "volume" => arg
+            .parse()
+            .ok()
+            .filter(|volume| (0.0..=1.0).contains(volume))
+            .map(Command::Volume)
+            .ok_or_else(|| "Usage: volume <0.0-1.0>".to_string()),
+        "quit" | "q" => Ok(Command::Quit),
+        _ => Err(format!("Unknown command: {name}")),
+    }
+}
+
+/// Appends `line` to the history, moving a repeated line to the end and
+/// keeping the last `MAX_COMMAND_HISTORY` entries.
+pub fn push_history(history: &mut Vec<String>, line: &str) {
+    history.retain(|entry| entry != line);
+    history.push(line.to_string());
+    let excess = history.len().saturating_sub(MAX_COMMAND_HISTORY);
+    history.drain(..excess);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_commands() {
+        assert_eq!(parse_command("goto 5"), Ok(Command::Goto(5)));
+        assert_eq!(parse_command(" volume 0.8 "), Ok(Command::Volume(0.8)));
+        assert_eq!(
+            parse_command("load /tunes/My Tune.sid"),
+            Ok(Command::Load("/tunes/My Tune.sid".to_string()))
+        );
+        assert_eq!(parse_command("q"), Ok(Command::Quit));
+    }
+
+    #[test]
+    fn rejects_bad_commands() {
+        assert_eq!(
+            parse_command("dance"),
+            Err("Unknown command: dance".to_string())
+        );
+        assert!(parse_command("goto 0").is_err());
+        assert!(parse_command("volume 2").is_err());
+        assert!(parse_command("load").is_err());
+    }
+
+    #[test]
+    fn history_is_capped_and_deduplicated() {
+        let mut history = Vec::new();
+        for i in 0..25 {
+            push_history(&mut history, &format!("goto {i}"));
+        }
+        push_history(&mut history, "goto 10");
+        assert_eq!(history.len(), MAX_COMMAND_HISTORY);
+        assert_eq!(history[0], "goto 5");
+        assert_eq!(history.last().map(String::as_str), Some("goto 10"));
+    }
+}
@@ -10,16 +10,85 @@ use ratatui::{
     symbols::Marker,
     text::{Line, Span},
     widgets::{
-        Bar, BarChart, BarGroup, Block, Borders, Clear, List, ListItem, ListState, Paragraph,
-        canvas::{Canvas, Line as CanvasLine},
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph,
+        canvas::{Canvas, Line as CanvasLine, Rectangle},
     },
 };
 use residfp::ChipModel;
 
-use super::app::{App, BrowserFocus, Popup};
+use crate::config::{MIX_MODES, SAMPLING_METHODS};
+use crate::hvsc::{HvscBrowser, SortMode};
+use crate::player::chip_model_name;
+use crate::sid_file::format_flags;
+
+use super::app::{App, BrowserFocus, InputMode, Popup, WRITE_TRACE_LEN};
 use super::theme::{ColorScheme, SCHEMES, c64};
 
-pub fn draw(frame: &mut Frame, app: &mut App) {
+/// Opacity of non-solo voices in the VU meters and scopes
+const SOLO_DIM_ALPHA: f32 = 0.3;
+
+/// Maximum characters of the subsong STIL comment shown in the header
+const STIL_COMMENT_WIDTH: usize = 40;
+
+/// Braille frames for the HVSC loading spinner
+const SPINNER: [char; 8] = ['⣾', '⣽', '⣻', '⢿', '⡿', '⣟', '⣯', '⣷'];
+
+/// Smallest terminal the full UI is laid out for
+pub const MIN_WIDTH: u16 = 80;
+pub const MIN_HEIGHT: u16 = 24;
+
+/// Terminals narrower than this hide the browser panel unless toggled with Tab
+pub const COMPACT_WIDTH: u16 = 100;
+
+/// Width of the browser panel in the full layout
+const BROWSER_WIDTH: u16 = 32;
+
+/// True if a terminal of this size can show the full UI.
+pub const fn fits_min_size(width: u16, height: u16) -> bool {
+    width >= MIN_WIDTH && height >= MIN_HEIGHT
+}
+
+/// Draws the UI, or the size warning if the frame is too small for it.
+pub fn draw_app(frame: &mut Frame, app: &mut App) {
+    let area = frame.area();
+    if fits_min_size(area.width, area.height) {
+        draw_responsive_layout(frame, app);
+    } else {
+        draw_too_small(frame);
+    }
+}
+
+/// Replaces the UI with a size warning on terminals below `MIN_WIDTH`×`MIN_HEIGHT`.
+pub fn draw_too_small(frame: &mut Frame) {
+    let area = frame.area();
+    let [_, center, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(1),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    let message = format!("Terminal too small. Minimum {MIN_WIDTH}×{MIN_HEIGHT}.");
+    frame.render_widget(Paragraph::new(message).centered(), center);
+}
+
+/// Draws the UI, collapsing the browser panel on terminals narrower than `COMPACT_WIDTH`.
+pub fn draw_responsive_layout(frame: &mut Frame, app: &mut App) {
+    let area = frame.area();
+    app.compact_layout = area.width < COMPACT_WIDTH;
+    let browser_width = if app.compact_layout && !app.browser_visible {
+        0
+    } else {
+        BROWSER_WIDTH
+    };
+    draw(frame, app, browser_width);
+}
+
+/// Splits off the browser panel on the left; the player takes the rest.
+fn split_browser(area: Rect, browser_width: u16) -> [Rect; 2] {
+    Layout::horizontal([Constraint::Length(browser_width), Constraint::Min(60)]).areas(area)
+}
+
+fn draw(frame: &mut Frame, app: &mut App, browser_width: u16) {
     let full_area = frame.area();
     let scheme = app.scheme();
 
@@ -29,19 +98,27 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
         full_area,
     );
 
-    let [browser_area, player_area] =
-        Layout::horizontal([Constraint::Length(32), Constraint::Min(60)]).areas(full_area);
+    let [browser_area, player_area] = split_browser(full_area, browser_width);
 
-    let [playlist_area, hvsc_area] =
-        Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).areas(browser_area);
+    if browser_area.width > 0 {
+        let [playlist_area, hvsc_area] =
+            Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+                .areas(browser_area);
+        draw_playlist_browser(frame, playlist_area, app);
+        draw_hvsc_browser(frame, hvsc_area, app);
+    }
 
-    draw_playlist_browser(frame, playlist_area, app);
-    draw_hvsc_browser(frame, hvsc_area, app);
+    // Database status line only fits on taller terminals
+    let footer_height = if app.browser_focus == BrowserFocus::Hvsc && full_area.height > 24 {
+        2
+    } else {
+        1
+    };
 
     let [header_area, main_area, footer_area] = Layout::vertical([
-        Constraint::Length(6),
+        Constraint::Length(8),
         Constraint::Min(10),
-        Constraint::Length(1),
+        Constraint::Length(footer_height),
     ])
     .areas(player_area);
 
@@ -51,12 +128,103 @@ pub fn draw(frame: &mut Frame, app: &mut App) {
     draw_header(frame, header_area, app);
     draw_vu_meters(frame, vu_area, app);
     draw_voice_scopes(frame, scope_area, app);
-    draw_footer(frame, footer_area, app);
+    draw_context_footer(frame, footer_area, app);
+    draw_vim_keys(frame, full_area, app);
+    draw_debug_overlay(frame, full_area, app);
     draw_popup(frame, app);
+    draw_notifications(frame, full_area, app);
+}
+
+/// Stacks the pending notifications in the top-right corner, below the debug overlay.
+fn draw_notifications(frame: &mut Frame, area: Rect, app: &App) {
+    let scheme = app.scheme();
+    let first_slot = u16::from(app.show_debug_overlay);
+    for (slot, (msg, _)) in (first_slot..).zip(&app.notification_queue) {
+        let text = format!(" {msg} ");
+        #[allow(clippy::cast_possible_truncation)]
+        let corner = corner_rect(text.chars().count() as u16 + 2, 3, slot, area);
+        if corner.height < 3 {
+            break;
+        }
+        frame.render_widget(Clear, corner);
+        frame.render_widget(
+            Paragraph::new(text)
+                .style(
+                    Style::default()
+                        .fg(scheme.text_primary)
+                        .bg(scheme.background),
+                )
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(scheme.accent)),
+                ),
+            corner,
+        );
+    }
+}
+
+/// Shows the play call counter in the top-right corner (F3).
+fn draw_debug_overlay(frame: &mut Frame, area: Rect, app: &App) {
+    if !app.show_debug_overlay {
+        return;
+    }
+    let scheme = app.scheme();
+    let text = format!(" Frame: {} ", app.frame_counter);
+    #[allow(clippy::cast_possible_truncation)]
+    let corner = corner_rect(text.chars().count() as u16 + 2, 3, 0, area);
+    frame.render_widget(Clear, corner);
+    frame.render_widget(
+        Paragraph::new(text)
+            .style(
+                Style::default()
+                    .fg(scheme.text_primary)
+                    .bg(scheme.background),
+            )
+            .block(
+                Block::default()
+                    .title(" Debug ")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(scheme.border_dim)),
+            ),
+        corner,
+    );
+}
+
+/// Shows the unfinished vim key sequence in the bottom-right corner.
+fn draw_vim_keys(frame: &mut Frame, area: Rect, app: &App) {
+    if app.vim_key_buffer.is_empty() {
+        return;
+    }
+    let scheme = app.scheme();
+    #[allow(clippy::cast_possible_truncation)]
+    let width = (app.vim_key_buffer.chars().count() as u16 + 4).min(area.width);
+    let height = 3.min(area.height);
+    let corner = Rect {
+        x: area.right().saturating_sub(width),
+        y: area.bottom().saturating_sub(height + 1),
+        width,
+        height,
+    };
+    frame.render_widget(Clear, corner);
+    frame.render_widget(
+        Paragraph::new(format!(" {}", app.vim_key_buffer))
+            .style(
+                Style::default()
+                    .fg(scheme.text_primary)
+                    .bg(scheme.background),
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(scheme.border_dim)),
+            ),
+        corner,
+    );
 }
 
 fn draw_playlist_browser(frame: &mut Frame, area: Rect, app: &mut App) {
-    let scheme = *app.scheme();
+    let scheme = app.scheme().clone();
     let is_focused = app.browser_focus == BrowserFocus::Playlist;
     let border_color = if is_focused {
         scheme.border_focus
@@ -64,18 +232,28 @@ fn draw_playlist_browser(frame: &mut Frame, area: Rect, app: &mut App) {
         scheme.border_dim
     };
 
+    let count = app
+        .merged_playlist_len()
+        .map(|len| format!("[{len} entries] "))
+        .unwrap_or_default();
+    let title = format!(
+        " Playlist {count}{}",
+        filter_title(app, BrowserFocus::Playlist)
+    );
     let block = Block::default()
-        .title(" Playlist ")
+        .title(title)
         .title_style(Style::default().fg(scheme.title).bold())
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color));
 
-    let items: Vec<ListItem> = app
-        .playlist_browser
-        .playlist
-        .entries
+    let entries = &app.playlist_browser.playlist.entries;
+    let visible = app
+        .filter_indices_for(BrowserFocus::Playlist)
+        .unwrap_or_else(|| (0..entries.len()).collect());
+    let items: Vec<ListItem> = visible
         .iter()
-        .map(|entry| {
+        .map(|&i| {
+            let entry = &entries[i];
             let mut name = entry.display_name.clone();
             if let Some(sub) = entry.subsong {
                 name.push_str(&format!(" @{sub}"));
@@ -84,10 +262,17 @@ fn draw_playlist_browser(frame: &mut Frame, area: Rect, app: &mut App) {
         })
         .collect();
 
+    let mut list_state = ListState::default();
+    let selected = app
+        .playlist_browser
+        .state
+        .selected()
+        .and_then(|s| visible.iter().position(|&i| i == s));
+    list_state.select(selected);
+
     let inner_height = area.height.saturating_sub(2) as usize;
-    let selected = app.playlist_browser.selected_index();
-    let offset = selected.saturating_sub(inner_height / 2);
-    *app.playlist_browser.state.offset_mut() = offset;
+    let offset = selected.unwrap_or(0).saturating_sub(inner_height / 2);
+    *list_state.offset_mut() = offset;
 
     let list = List::new(items)
         .block(block)
@@ -99,7 +284,15 @@ fn draw_playlist_browser(frame: &mut Frame, area: Rect, app: &mut App) {
         )
         .highlight_symbol(if is_focused { "> " } else { "  " });
 
-    frame.render_stateful_widget(list, area, &mut app.playlist_browser.state);
+    frame.render_stateful_widget(list, area, &mut list_state);
+}
+
+/// `[filter: query_] ` title suffix while `focus`'s browser is filtered.
+fn filter_title(app: &App, focus: BrowserFocus) -> String {
+    match &app.browser_filter {
+        Some((f, query)) if *f == focus => format!("[filter: {query}_] "),
+        _ => String::new(),
+    }
 }
 
 /// Formats HVSC entry for display, enriching with STIL metadata when available.
@@ -128,7 +321,7 @@ fn format_hvsc_entry(
 }
 
 fn draw_hvsc_browser(frame: &mut Frame, area: Rect, app: &mut App) {
-    let scheme = *app.scheme();
+    let scheme = app.scheme().clone();
     let is_focused = app.browser_focus == BrowserFocus::Hvsc;
     let border_color = if is_focused {
         scheme.border_focus
@@ -143,6 +336,18 @@ fn draw_hvsc_browser(frame: &mut Frame, area: Rect, app: &mut App) {
     }
 }
 
+/// Title of the search results, saying why STIL is missing when filename search stands in.
+fn search_results_title(query: &str, count: usize, browser: &HvscBrowser) -> String {
+    match (&browser.stil, &browser.stil_error) {
+        (Some(stil), _) => format!(" Search: {}_ ({} of {} entries) ", query, count, stil.len()),
+        (None, Some(err)) => format!(
+            " Search: {}_ ({} found) [filename search: {}] ",
+            query, count, err
+        ),
+        (None, None) => format!(" Search: {}_ ({} found) [filename search] ", query, count),
+    }
+}
+
 fn draw_hvsc_search_results(
     frame: &mut Frame,
     area: Rect,
@@ -151,15 +356,7 @@ fn draw_hvsc_search_results(
     border_color: Color,
 ) {
     let query = app.hvsc_search.as_deref().unwrap_or("");
-    let count = app.hvsc_search_results.len();
-    let title = if let Some(err) = &app.hvsc_browser.stil_error {
-        format!(" Search: {}_ [{}] ", query, err)
-    } else {
-        match &app.hvsc_browser.stil {
-            None => format!(" Search: {}_ [STIL not loaded] ", query),
-            Some(stil) => format!(" Search: {}_ ({} of {} entries) ", query, count, stil.len()),
-        }
-    };
+    let title = search_results_title(query, app.hvsc_search_results.len(), &app.hvsc_browser);
 
     let block = Block::default()
         .title(title)
@@ -198,6 +395,40 @@ fn draw_hvsc_search_results(
     frame.render_stateful_widget(list, area, &mut list_state);
 }
 
+/// Title of the HVSC listing; file and directory counts cover only the `filtered` entries, if given.
+fn hvsc_directory_title(app: &App, filtered: Option<&[usize]>) -> String {
+    let browser = &app.hvsc_browser;
+    let mut title = if browser.current_path == "/" {
+        " HVSC (/ to search) ".to_string()
+    } else {
+        format!(" HVSC: {} ", browser.current_path)
+    };
+    if browser.loading {
+        title.push_str(&format!(
+            "[loading...] {} ",
+            SPINNER[app.spinner_tick % SPINNER.len()]
+        ));
+    } else {
+        let (files, dirs) = match filtered {
+            Some(visible) => {
+                let dirs = visible
+                    .iter()
+                    .filter(|&&i| browser.entries[i].is_dir)
+                    .count();
+                (visible.len() - dirs, dirs)
+            }
+            None => (browser.entry_file_count, browser.entry_dir_count),
+        };
+        title.push_str(&format!("[{} files, {} dirs] ", files, dirs));
+    }
+    title.push_str(match browser.sort_mode {
+        SortMode::ByName => "[by name] ",
+        SortMode::ByStilTitle => "[by title] ",
+    });
+    title.push_str(&filter_title(app, BrowserFocus::Hvsc));
+    title
+}
+
 fn draw_hvsc_directory(
     frame: &mut Frame,
     area: Rect,
@@ -206,34 +437,31 @@ fn draw_hvsc_directory(
     is_focused: bool,
     border_color: Color,
 ) {
-    let title = if app.hvsc_browser.current_path == "/" {
-        " HVSC (/ to search) ".to_string()
-    } else {
-        format!(" HVSC: {} ", app.hvsc_browser.current_path)
-    };
-
+    let filtered = app.filter_indices_for(BrowserFocus::Hvsc);
+    let title = hvsc_directory_title(app, filtered.as_deref());
     let block = Block::default()
         .title(title)
         .title_style(Style::default().fg(scheme.title).bold())
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color));
 
-    let items: Vec<ListItem> = app
-        .hvsc_browser
-        .entries
+    let entries = &app.hvsc_browser.entries;
+    let visible = filtered.unwrap_or_else(|| (0..entries.len()).collect());
+    let items: Vec<ListItem> = visible
         .iter()
-        .map(|entry| {
-            let (name, style) = format_hvsc_entry(entry, app.hvsc_browser.stil.as_ref(), scheme);
+        .map(|&i| {
+            let (name, style) =
+                format_hvsc_entry(&entries[i], app.hvsc_browser.stil.as_ref(), scheme);
             ListItem::new(name).style(style)
         })
         .collect();
 
     let mut list_state = ListState::default();
-    list_state.select(Some(app.hvsc_browser.selected));
+    let selected = visible.iter().position(|&i| i == app.hvsc_browser.selected);
+    list_state.select(selected);
 
     let inner_height = area.height.saturating_sub(2) as usize;
-    let selected = app.hvsc_browser.selected;
-    let offset = selected.saturating_sub(inner_height / 2);
+    let offset = selected.unwrap_or(0).saturating_sub(inner_height / 2);
     *list_state.offset_mut() = offset;
 
     let list = List::new(items)
@@ -288,6 +516,42 @@ fn sid_info_lines(app: &App) -> Vec<Line<'static>> {
     };
 
     let chip_str = format_chip_models(&app.chip_models);
+    let rec_style = if app.rec_blink {
+        Style::default().fg(scheme.title).bold()
+    } else {
+        Style::default().fg(scheme.text_secondary)
+    };
+    let recording = if app.recording {
+        let secs = app.recording_duration.as_secs();
+        Span::styled(format!("  [REC {}:{:02}]", secs / 60, secs % 60), rec_style)
+    } else {
+        Span::raw("")
+    };
+    let speed = if (app.speed_factor - 1.0).abs() > f32::EPSILON {
+        Span::styled(
+            format!("  [×{:.1}]", app.speed_factor),
+            Style::default().fg(scheme.title).bold(),
+        )
+    } else {
+        Span::raw("")
+    };
+    let filter = if app.filter_bypass_active {
+        Span::styled("  [FILT OFF]", Style::default().fg(scheme.title).bold())
+    } else {
+        Span::raw("")
+    };
+    let warning = if app.validation_warnings().is_empty() {
+        Span::raw("")
+    } else {
+        Span::styled("  [!]", Style::default().fg(scheme.title).bold())
+    };
+
+    let comment: String = app
+        .stil_subsong_comment()
+        .unwrap_or_default()
+        .chars()
+        .take(STIL_COMMENT_WIDTH)
+        .collect();
 
     vec![
         Line::from(vec![
@@ -317,29 +581,70 @@ fn sid_info_lines(app: &App) -> Vec<Line<'static>> {
             Span::styled("  ", Style::default()),
             Span::styled(chip_str, Style::default().fg(scheme.text_secondary)),
             status,
+            speed,
+            filter,
+            recording,
+            warning,
+        ]),
+        Line::from(vec![
+            Span::styled("STIL:     ", label),
+            Span::styled(comment, Style::default().fg(scheme.text_secondary)),
+        ]),
+        Line::from(vec![
+            Span::styled("Freq:     ", label),
+            Span::styled(
+                format_frequencies(&app.voice_frequencies),
+                Style::default().fg(scheme.text_secondary),
+            ),
         ]),
     ]
 }
 
-/// Formats chip models for display: "[6581]", "[2x SID: 6581+8580]", etc.
-fn format_chip_models(models: &[ChipModel]) -> String {
-    let model_strs: Vec<&str> = models
+/// Note names within an octave, starting at C
+const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+/// Nearest note to `hz` in scientific pitch notation (440 Hz = "A4"), or
+/// `---` for silence and pitches below C-1.
+fn hz_to_note_name(hz: f32) -> String {
+    if !hz.is_finite() || hz <= 0.0 {
+        return "---".to_string();
+    }
+    let midi = 12.0f32.mul_add((hz / 440.0).log2(), 69.0).round();
+    if midi < 0.0 {
+        return "---".to_string();
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let midi = midi as usize;
+    #[allow(clippy::cast_possible_wrap)]
+    let octave = (midi / 12) as i32 - 1;
+    format!("{}{octave}", NOTE_NAMES[midi % 12])
+}
+
+/// Note name of `voice` for its scope title, once frequencies are known.
+fn voice_note(app: &App, voice: usize) -> Option<String> {
+    app.voice_frequencies
+        .get(voice)
+        .map(|hz| hz_to_note_name(hz.unwrap_or(0.0)))
+}
+
+/// Formats voice pitches as "440Hz 880Hz ---", with `---` for silent voices.
+fn format_frequencies(frequencies: &[Option<f32>]) -> String {
+    frequencies
         .iter()
-        .map(|m| match m {
-            ChipModel::Mos6581 => "6581",
-            ChipModel::Mos8580 => "8580",
-        })
-        .collect();
+        .map(|f| f.map_or_else(|| "---".to_string(), |hz| format!("{hz:.0}Hz")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
-    match models.len() {
-        1 => format!("[{}]", model_strs[0]),
-        2 => format!("[2x SID: {}+{}]", model_strs[0], model_strs[1]),
-        3 => format!(
-            "[3x SID: {}+{}+{}]",
-            model_strs[0], model_strs[1], model_strs[2]
-        ),
-        _ => "[SID]".to_string(),
+/// Formats chip models for display: "[6581]", "[6581|8580]", etc.
+fn format_chip_models(models: &[ChipModel]) -> String {
+    if models.is_empty() {
+        return "[SID]".to_string();
     }
+    let names: Vec<&str> = models.iter().map(|&m| chip_model_name(m)).collect();
+    format!("[{}]", names.join("|"))
 }
 
 /// Returns the CrabSid logo with fixed C64 rainbow colors.
@@ -399,30 +704,41 @@ fn logo_lines() -> Vec<Line<'static>> {
     ]
 }
 
+/// Segments each VU bar is divided into.
+const VU_SEGMENTS: u32 = 10;
+/// Voices drawn as VU bars and scopes; chips beyond the third are only counted
+const MAX_SHOWN_VOICES: usize = 9;
+
+/// Outline of a voice's VU bar at `level` (0.0-1.0), in canvas cell coordinates.
+fn vu_bar(x: f64, width: f64, level: f32, height: f64, color: Color) -> Rectangle {
+    Rectangle {
+        x,
+        y: 0.0,
+        width,
+        height: f64::from(level.clamp(0.0, 1.0)) * height,
+        color,
+    }
+}
+
 fn draw_vu_meters(frame: &mut Frame, area: Rect, app: &App) {
     let scheme = app.scheme();
-    let voice_count = app.vu_meter.voice_count();
+    let voice_count = app.vu_meter.voice_count().min(MAX_SHOWN_VOICES);
+    let title = match app.vu_meter.voice_count() - voice_count {
+        0 => " Voice Levels ".to_string(),
+        hidden => format!(" Voice Levels (+{hidden} more) "),
+    };
 
     let block = Block::default()
-        .title(" Voice Levels ")
+        .title(title)
         .title_style(Style::default().fg(scheme.title))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(scheme.border_dim));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
-
-    let bars: Vec<Bar> = (0..voice_count)
-        .map(|i| {
-            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-            let level = (app.vu_meter.levels[i] * 100.0) as u64;
-            let color_idx = i % scheme.voices.len();
-            Bar::default()
-                .value(level)
-                .text_value(String::new())
-                .style(Style::default().fg(scheme.voices[color_idx]))
-        })
-        .collect();
+    if inner.width == 0 || inner.height == 0 {
+        return;
+    }
 
     // Adjust bar width based on voice count to fit in ~38 char inner width
     let (bar_width, bar_gap) = match voice_count {
@@ -431,42 +747,94 @@ fn draw_vu_meters(frame: &mut Frame, area: Rect, app: &App) {
         _ => (3, 1),     // 9*3 + 8*1 = 35
     };
 
-    let chart = BarChart::default()
-        .data(BarGroup::default().bars(&bars))
-        .bar_width(bar_width)
-        .bar_gap(bar_gap)
-        .max(100)
-        .direction(ratatui::layout::Direction::Vertical);
-
-    // Calculate exact width needed: bars + gaps between them
+    // One bar group per SID, with an extra column between chips for the separator
+    let group_gap = 1;
+    let sid_count = app.vu_meter.sid_count().min(MAX_SHOWN_VOICES / 3);
+    #[allow(clippy::cast_possible_truncation)]
+    let chart_width = (voice_count as u16) * bar_width
+        + (voice_count.saturating_sub(1) as u16) * bar_gap
+        + (sid_count.saturating_sub(1) as u16) * group_gap;
+    let left_pad = f64::from(inner.width.saturating_sub(chart_width) / 2);
     #[allow(clippy::cast_possible_truncation)]
-    let chart_width =
-        (voice_count as u16) * bar_width + (voice_count.saturating_sub(1) as u16) * bar_gap;
-
-    // Center by offsetting x, but give chart enough width to render properly
-    let left_pad = inner.width.saturating_sub(chart_width) / 2;
-    let centered = Rect {
-        x: inner.x + left_pad,
-        y: inner.y,
-        width: inner.width - left_pad, // Don't constrain right side
-        height: inner.height,
+    let bar_x = |i: usize| {
+        let (i, sid) = (i as u16, (i / 3) as u16);
+        left_pad + f64::from(i * (bar_width + bar_gap) + sid * group_gap)
     };
-    frame.render_widget(chart, centered);
+
+    let width = f64::from(inner.width);
+    let height = f64::from(inner.height);
+    let bar_w = f64::from(bar_width);
+    let canvas = Canvas::default()
+        .marker(Marker::HalfBlock)
+        .background_color(scheme.background)
+        .x_bounds([0.0, width])
+        .y_bounds([0.0, height])
+        .paint(|ctx| {
+            for i in 0..voice_count {
+                let x = bar_x(i);
+                let color = voice_color(app, i);
+                let bar = vu_bar(x, bar_w, app.vu_meter.levels[i], height, color);
+
+                // Lit segments are filled with horizontal strokes inside the outline
+                for segment in 0..VU_SEGMENTS {
+                    let y = (f64::from(segment) + 0.5) / f64::from(VU_SEGMENTS) * height;
+                    if y > bar.height {
+                        break;
+                    }
+                    ctx.draw(&CanvasLine {
+                        x1: x,
+                        y1: y,
+                        x2: x + bar_w,
+                        y2: y,
+                        color,
+                    });
+                }
+                if bar.height > 0.0 {
+                    ctx.draw(&bar);
+                }
+
+                let peak_y = f64::from(app.vu_meter.peaks[i].clamp(0.0, 1.0)) * height;
+                let peak_color = if app.vu_meter.peak_flash(i) {
+                    Color::Red
+                } else {
+                    color
+                };
+                ctx.draw(&CanvasLine {
+                    x1: x,
+                    y1: peak_y,
+                    x2: x + bar_w,
+                    y2: peak_y,
+                    color: peak_color,
+                });
+            }
+
+            // Thin vertical line in the gap between SID chips
+            for sid in 1..sid_count {
+                let x = bar_x(sid * 3) - f64::from(bar_gap + group_gap) / 2.0;
+                ctx.draw(&CanvasLine {
+                    x1: x,
+                    y1: 0.0,
+                    x2: x,
+                    y2: height,
+                    color: scheme.border_dim,
+                });
+            }
+        });
+    frame.render_widget(canvas, inner);
 }
 
 fn draw_voice_scopes(frame: &mut Frame, area: Rect, app: &App) {
-    let scheme = app.scheme();
-    let voice_count = app.voice_scopes.voice_count();
+    let voice_count = app.voice_scopes.voice_count().min(MAX_SHOWN_VOICES);
 
     if voice_count <= 3 {
-        draw_voice_scopes_vertical(frame, area, app, scheme);
+        draw_voice_scopes_vertical(frame, area, app);
     } else {
-        draw_voice_scopes_grid(frame, area, app, scheme, voice_count);
+        draw_voice_scopes_grid(frame, area, app, voice_count);
     }
 }
 
 /// Single SID: vertical stack of 3 scopes
-fn draw_voice_scopes_vertical(frame: &mut Frame, area: Rect, app: &App, scheme: &ColorScheme) {
+fn draw_voice_scopes_vertical(frame: &mut Frame, area: Rect, app: &App) {
     let voice_count = app.voice_scopes.voice_count();
     let row_constraints: Vec<Constraint> = (0..voice_count)
         .map(|_| Constraint::Ratio(1, voice_count as u32))
@@ -475,34 +843,34 @@ fn draw_voice_scopes_vertical(frame: &mut Frame, area: Rect, app: &App, scheme:
 
     for (i, samples) in app.voice_scopes.samples.iter().enumerate() {
         let label = format!("Voice {}", i + 1);
-        let color_idx = i % scheme.voices.len();
         draw_single_scope(
             frame,
             row_areas[i],
             samples,
             &label,
-            scheme.voices[color_idx],
-            scheme.border_dim,
-            scheme.background,
+            voice_note(app, i).as_deref(),
+            voice_color(app, i),
+            app,
         );
     }
 }
 
 /// Multi-SID: grid layout with one row per SID (3 voices per row)
-fn draw_voice_scopes_grid(
-    frame: &mut Frame,
-    area: Rect,
-    app: &App,
-    scheme: &ColorScheme,
-    voice_count: usize,
-) {
+fn draw_voice_scopes_grid(frame: &mut Frame, area: Rect, app: &App, voice_count: usize) {
     let sid_count = voice_count.div_ceil(3);
     let row_constraints: Vec<Constraint> = (0..sid_count)
         .map(|_| Constraint::Ratio(1, sid_count as u32))
         .collect();
     let row_areas = Layout::vertical(row_constraints).split(area);
 
-    for (i, samples) in app.voice_scopes.samples.iter().enumerate() {
+    let hidden = app.voice_scopes.voice_count() - voice_count;
+    for (i, samples) in app
+        .voice_scopes
+        .samples
+        .iter()
+        .take(voice_count)
+        .enumerate()
+    {
         let row = i / 3;
         let col = i % 3;
         let voices_in_row = (voice_count - row * 3).min(3);
@@ -513,35 +881,70 @@ fn draw_voice_scopes_grid(
         let col_areas = Layout::horizontal(col_constraints).split(row_areas[row]);
 
         if col < col_areas.len() {
-            let label = format!("Voice {}", i + 1);
-            let color_idx = i % scheme.voices.len();
+            let label = if hidden > 0 && i + 1 == voice_count {
+                format!("Voice {} (+{hidden} more)", i + 1)
+            } else {
+                format!("Voice {}", i + 1)
+            };
             draw_single_scope(
                 frame,
                 col_areas[col],
                 samples,
                 &label,
-                scheme.voices[color_idx],
-                scheme.border_dim,
-                scheme.background,
+                voice_note(app, i).as_deref(),
+                voice_color(app, i),
+                app,
             );
         }
     }
 }
 
+/// Voice color, faded towards the background when another voice is soloed.
+fn voice_color(app: &App, voice: usize) -> Color {
+    let scheme = app.scheme();
+    let color = scheme.voices[voice % scheme.voices.len()];
+    match app.solo_voice {
+        Some(solo) if solo != voice => blend(color, scheme.background, SOLO_DIM_ALPHA),
+        _ => color,
+    }
+}
+
+/// Mixes `alpha` of `fg` over `bg`; non-RGB colors are returned unchanged.
+fn blend(fg: Color, bg: Color, alpha: f32) -> Color {
+    let (Color::Rgb(fr, fg_, fb), Color::Rgb(br, bg_, bb)) = (fg, bg) else {
+        return fg;
+    };
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let mix = |f: u8, b: u8| (f32::from(f) * alpha + f32::from(b) * (1.0 - alpha)).round() as u8;
+    Color::Rgb(mix(fr, br), mix(fg_, bg_), mix(fb, bb))
+}
+
+/// Draws one voice scope titled `title`, followed by `note` (e.g. `[A4]`) when given.
 fn draw_single_scope(
     frame: &mut Frame,
     area: Rect,
     samples: &[f32],
     title: &str,
+    note: Option<&str>,
     color: Color,
-    border: Color,
-    background: Color,
+    app: &App,
 ) {
+    let scheme = app.scheme();
+    let marker = app.scope_marker;
+    let mut title = vec![Span::styled(
+        format!(" {title} "),
+        Style::default().fg(color),
+    )];
+    if let Some(note) = note {
+        title.push(Span::styled(
+            format!("[{note}] "),
+            Style::default().fg(scheme.accent),
+        ));
+    }
     let block = Block::default()
-        .title(format!(" {title} "))
-        .title_style(Style::default().fg(color))
+        .title(Line::from(title))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(border));
+        .border_style(Style::default().fg(scheme.border_dim));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
@@ -555,8 +958,8 @@ fn draw_single_scope(
     let x_scale = width / samples.len() as f64;
 
     let canvas = Canvas::default()
-        .marker(Marker::Braille)
-        .background_color(background)
+        .marker(marker)
+        .background_color(scheme.background)
         .x_bounds([0.0, width])
         .y_bounds([0.0, 1.0])
         .paint(|ctx| {
@@ -581,33 +984,146 @@ fn draw_single_scope(
     frame.render_widget(canvas, inner);
 }
 
-fn draw_footer(frame: &mut Frame, area: Rect, app: &App) {
+/// Key/description pairs relevant to the open popup, or else to the focused browser.
+fn context_bindings(focus: BrowserFocus, popup: &Popup) -> &'static [(&'static str, &'static str)] {
+    match popup {
+        Popup::None => match focus {
+            BrowserFocus::Playlist => &[
+                ("h", "Help"),
+                ("Enter", "Play"),
+                ("a", "Add"),
+                ("BS", "Remove"),
+                ("^↑↓", "Move"),
+                ("Tab", "HVSC"),
+                ("q", "Quit"),
+            ],
+            BrowserFocus::Hvsc => &[
+                ("h", "Help"),
+                ("/", "Search"),
+                ("Enter", "Open"),
+                ("←", "Up"),
+                ("a", "Add"),
+                ("Tab", "Playlist"),
+                ("q", "Quit"),
+            ],
+        },
+        Popup::HvscSearch => &[("Esc", "Cancel"), ("Enter", "Search")],
+        Popup::MergePlaylist { .. } => &[("Esc", "Cancel"), ("Enter", "Merge")],
+        Popup::SortPlaylist { .. } => &[("Esc", "Cancel"), ("Space", "Toggle"), ("Enter", "Sort")],
+        Popup::SaveConfirm => &[("Esc", "Cancel"), ("y", "Save"), ("p", "Portable")],
+        Popup::ExpandConfirm { .. } => &[("Esc", "Cancel"), ("y", "Expand")],
+        Popup::ColorScheme => &[("Esc", "Close"), ("↑↓", "Scheme")],
+        Popup::MirrorList => &[
+            ("Esc", "Close"),
+            ("j/k", "Select"),
+            ("↑↓", "Move"),
+            ("BS", "Remove"),
+        ],
+        Popup::SamplingMethod => &[("Esc", "Close"), ("↑↓", "Select"), ("Enter", "Apply")],
+        Popup::MemDump { .. } => &[("Esc", "Close"), ("↑↓", "Scroll"), ("PgUp/Dn", "Page")],
+        Popup::SubsongChipOverride => &[("Esc", "Close"), ("↑↓", "Select"), ("Enter", "Cycle")],
+        Popup::GenreBrowser { .. } => &[("Esc", "Close"), ("↑↓", "Select"), ("Enter", "Search")],
+        Popup::VoiceColorEditor { .. } => &[("Esc", "Close"), ("Tab", "Field"), ("Enter", "Save")],
+        Popup::Help
+        | Popup::Error(_)
+        | Popup::ValidationWarnings
+        | Popup::HvscStats
+        | Popup::PerfStats
+        | Popup::CpuDebug => &[("Esc", "Close")],
+        Popup::StepMode => &[("Esc", "Resume"), ("F6", "Step")],
+        Popup::FileInfo { .. } | Popup::WriteTrace { .. } => &[("Esc", "Close"), ("↑↓", "Scroll")],
+        Popup::Equalizer { .. } => &[
+            ("Esc", "Close"),
+            ("↑↓", "Slider"),
+            ("←→", "Adjust"),
+            ("Space", "On/off"),
+        ],
+    }
+}
+
+/// Footer key hints for the focused panel or open popup, followed by the sampling method.
+fn footer_spans_for_context(app: &App) -> Vec<Span<'static>> {
     let scheme = app.scheme();
     let key = Style::default().fg(scheme.accent).bold();
     let dim = Style::default().fg(scheme.text_secondary);
     let sep = Style::default().fg(scheme.border_dim);
 
-    let spans = vec![
-        Span::styled(" h", key),
-        Span::styled(" Help ", dim),
-        Span::styled("\u{2502} ", sep),
-        Span::styled("1-9/+/-", key),
-        Span::styled(" Song ", dim),
-        Span::styled("\u{2502} ", sep),
-        Span::styled("Tab", key),
-        Span::styled(" Switch ", dim),
-        Span::styled("\u{2502} ", sep),
-        Span::styled("c", key),
-        Span::styled(" Color ", dim),
-        Span::styled("\u{2502} ", sep),
-        Span::styled("a", key),
-        Span::styled(" Add ", dim),
-        Span::styled("\u{2502} ", sep),
-        Span::styled("q", key),
-        Span::styled(" Quit", dim),
-    ];
+    let mut spans = vec![Span::raw(" ")];
+    for &(k, description) in context_bindings(app.browser_focus, &app.popup) {
+        spans.push(Span::styled(k, key));
+        spans.push(Span::styled(format!(" {description} "), dim));
+        spans.push(Span::styled("\u{2502} ", sep));
+    }
+    spans.push(Span::styled(SAMPLING_METHODS[app.sampling_method].0, dim));
+    spans.push(Span::styled(
+        format!(" · {}", MIX_MODES[app.mix_mode].0),
+        dim,
+    ));
+    spans
+}
+
+fn draw_context_footer(frame: &mut Frame, area: Rect, app: &App) {
+    let scheme = app.scheme();
+    let key = Style::default().fg(scheme.accent).bold();
+    let dim = Style::default().fg(scheme.text_secondary);
+    let sep = Style::default().fg(scheme.border_dim);
+
+    let mut spans = footer_spans_for_context(app);
+    if app.underrun_count > 0 {
+        spans.push(Span::styled(
+            format!(" [UDR:{}]", app.underrun_count),
+            Style::default().fg(c64::LIGHT_RED).bold(),
+        ));
+    }
+    if app.vim_mode {
+        spans.push(Span::styled(" [VIM]", Style::default().fg(scheme.accent)));
+    }
+    if app.playlist_watching {
+        spans.push(Span::styled(" [WATCH]", Style::default().fg(scheme.accent)));
+    }
+    if let Some(voice) = app.solo_voice {
+        spans.push(Span::styled(
+            format!(" [SOLO:V{}]", voice + 1),
+            Style::default().fg(scheme.title).bold(),
+        ));
+    }
+
+    let first = command_line(&app.input_mode, app.command_error.as_deref(), scheme)
+        .unwrap_or_else(|| Line::from(spans));
+    let mut lines = vec![first];
+    if area.height > 1 {
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {} ", app.hvsc_browser.status_line()), dim),
+            Span::styled("\u{2502} ", sep),
+            Span::styled("[Stats]", dim),
+            Span::styled(" T", key),
+        ]));
+    }
 
-    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+/// Footer line replacing the key hints while typing a command or after one failed.
+fn command_line(
+    mode: &InputMode,
+    error: Option<&str>,
+    scheme: &ColorScheme,
+) -> Option<Line<'static>> {
+    if let InputMode::CommandMode { input } = mode {
+        return Some(Line::from(vec![
+            Span::styled(":", Style::default().fg(scheme.accent).bold()),
+            Span::styled(
+                format!("{input}\u{2588}"),
+                Style::default().fg(scheme.text_primary),
+            ),
+        ]));
+    }
+    error.map(|e| {
+        Line::from(Span::styled(
+            format!(" {e}"),
+            Style::default().fg(c64::LIGHT_RED).bold(),
+        ))
+    })
 }
 
 fn draw_color_scheme_popup(frame: &mut Frame, app: &App) {
@@ -616,7 +1132,8 @@ fn draw_color_scheme_popup(frame: &mut Frame, app: &App) {
 
     frame.render_widget(Clear, area);
 
-    let items: Vec<ListItem> = SCHEMES
+    let items: Vec<ListItem> = app
+        .schemes
         .iter()
         .enumerate()
         .map(|(i, s)| {
@@ -627,7 +1144,8 @@ fn draw_color_scheme_popup(frame: &mut Frame, app: &App) {
             } else {
                 Style::default().fg(scheme.text_primary)
             };
-            ListItem::new(format!(" {} ", s.name)).style(style)
+            let suffix = if i >= SCHEMES.len() { " [custom]" } else { "" };
+            ListItem::new(format!(" {}{suffix} ", s.name)).style(style)
         })
         .collect();
 
@@ -643,18 +1161,458 @@ fn draw_color_scheme_popup(frame: &mut Frame, app: &App) {
     frame.render_widget(list, area);
 }
 
+fn draw_mirror_list_popup(frame: &mut Frame, app: &App) {
+    let scheme = app.scheme();
+    let area = centered_rect(60, 40, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = app
+        .hvsc_browser
+        .mirrors
+        .iter()
+        .enumerate()
+        .map(|(i, url)| {
+            let marker = if i == app.hvsc_browser.active_mirror {
+                "*"
+            } else {
+                " "
+            };
+            let style = if i == app.mirror_list_index {
+                Style::default()
+                    .fg(scheme.highlight_fg)
+                    .bg(scheme.highlight_bg)
+            } else {
+                Style::default().fg(scheme.text_primary)
+            };
+            ListItem::new(format!(" {marker} {url} ")).style(style)
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .title(" HVSC Mirrors (j/k select, ↑↓ move, BS delete) ")
+            .title_style(Style::default().fg(scheme.title).bold())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(scheme.border_focus))
+            .style(Style::default().bg(scheme.background)),
+    );
+
+    frame.render_widget(list, area);
+}
+
+fn draw_sampling_method_popup(frame: &mut Frame, app: &App) {
+    let scheme = app.scheme();
+    let area = centered_rect(30, 40, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = SAMPLING_METHODS
+        .iter()
+        .enumerate()
+        .map(|(i, (name, _))| {
+            let marker = if i == app.sampling_method { "*" } else { " " };
+            let style = if i == app.sampling_method_index {
+                Style::default()
+                    .fg(scheme.highlight_fg)
+                    .bg(scheme.highlight_bg)
+            } else {
+                Style::default().fg(scheme.text_primary)
+            };
+            ListItem::new(format!(" {marker} {name} ")).style(style)
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .title(" Sampling Method ")
+            .title_style(Style::default().fg(scheme.title).bold())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(scheme.border_focus))
+            .style(Style::default().bg(scheme.background)),
+    );
+
+    frame.render_widget(list, area);
+}
+
+fn draw_genre_browser_popup(frame: &mut Frame, app: &App, selected: usize) {
+    let scheme = app.scheme();
+    let area = centered_rect(30, 60, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let stil = app.hvsc_browser.stil.as_ref();
+    let items: Vec<ListItem> = stil
+        .map(|stil| stil.genres())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|genre| {
+            let count = stil.map_or(0, |stil| stil.paths_for_genre(genre).len());
+            ListItem::new(format!(" {genre} ({count}) "))
+                .style(Style::default().fg(scheme.text_primary))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(" Genres ")
+                .title_style(Style::default().fg(scheme.title).bold())
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(scheme.border_focus))
+                .style(Style::default().bg(scheme.background)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(scheme.highlight_fg)
+                .bg(scheme.highlight_bg),
+        );
+
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_subsong_chip_popup(frame: &mut Frame, app: &App) {
+    let scheme = app.scheme();
+    let area = centered_rect(30, 50, frame.area());
+
+    frame.render_widget(Clear, area);
+
+    let sid = app.display_sid();
+    let items: Vec<ListItem> = (1..=app.total_songs)
+        .map(|song| {
+            let marker = if song == app.current_song { "*" } else { " " };
+            let model = match app.subsong_chip_overrides.get(&song) {
+                Some(&model) => chip_model_name(model).to_string(),
+                None => sid
+                    .chip_model_for_subsong(song)
+                    .map_or("default".to_string(), |model| {
+                        format!("default ({})", chip_model_name(model))
+                    }),
+            };
+            ListItem::new(format!(" {marker} Song {song:<3} {model} "))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(" Subsong Chip (Enter: cycle) ")
+                .title_style(Style::default().fg(scheme.title).bold())
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(scheme.border_focus))
+                .style(Style::default().bg(scheme.background)),
+        )
+        .style(Style::default().fg(scheme.text_primary))
+        .highlight_style(
+            Style::default()
+                .fg(scheme.highlight_fg)
+                .bg(scheme.highlight_bg),
+        );
+
+    let mut state = ListState::default().with_selected(Some(app.subsong_override_index));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_mem_dump_popup(frame: &mut Frame, app: &App, start: u16, data: &[u8]) {
+    let scheme = app.scheme();
+    let addr_style = Style::default().fg(scheme.accent);
+    let dim = Style::default().fg(scheme.text_secondary);
+
+    let lines: Vec<Line> = data
+        .chunks(16)
+        .enumerate()
+        .map(|(row, bytes)| {
+            #[allow(clippy::cast_possible_truncation)]
+            let addr = start.wrapping_add((row * 16) as u16);
+            let hex: Vec<String> = bytes.iter().map(|b| format!("{b:02x}")).collect();
+            let ascii: String = bytes
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            Line::from(vec![
+                Span::styled(format!(" 0x{addr:04x}: "), addr_style),
+                Span::raw(hex.join(" ")),
+                Span::styled(format!("  {ascii}"), dim),
+            ])
+        })
+        .collect();
+
+    // Fixed width: address (9) + 16 hex bytes (47) + ASCII (18) + borders
+    let full = frame.area();
+    #[allow(clippy::cast_possible_truncation)]
+    let height = (lines.len() as u16 + 2).min(full.height);
+    let width = 78.min(full.width);
+    let area = Rect {
+        x: full.x + (full.width - width) / 2,
+        y: full.y + (full.height - height) / 2,
+        width,
+        height,
+    };
+
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .title(" Memory (j/k scroll, PgUp/PgDn page) ")
+        .title_style(Style::default().fg(scheme.title).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(scheme.border_focus))
+        .style(Style::default().bg(scheme.background));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Header fields of the displayed SID file for the `I` popup.
+fn file_info_lines(app: &App) -> Vec<Line<'static>> {
+    let scheme = app.scheme();
+    let sid = app.display_sid();
+    let label = Style::default().fg(scheme.text_secondary);
+    let field = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!(" {name:<14}"), label),
+            Span::raw(value),
+        ])
+    };
+    let sid_address =
+        |address: Option<u16>| address.map_or("none".to_string(), |a| format!("${a:04X}"));
+
+    let mut lines = vec![
+        field("Magic", sid.magic.clone()),
+        field("Version", sid.version.to_string()),
+        field("Data offset", format!("${:04X}", sid.data_offset)),
+        field("Load address", format!("${:04X}", sid.load_address)),
+        field("Init address", format!("${:04X}", sid.init_address)),
+        field("Play address", format!("${:04X}", sid.play_address)),
+        field("Songs", sid.songs.to_string()),
+        field("Start song", sid.start_song.to_string()),
+        field("Speed", format!("${:08X}", sid.speed)),
+        field("Flags", format!("${:04X}", sid.flags)),
+    ];
+    lines.extend(
+        format_flags(sid.flags, sid.version)
+            .into_iter()
+            .map(|flag| Line::from(format!("   {flag}"))),
+    );
+    lines.extend([
+        field("Second SID", sid_address(sid.second_sid_address)),
+        field("Third SID", sid_address(sid.third_sid_address)),
+        field("MD5", sid.md5.clone()),
+        field("CRC32", format!("{:08X}", sid.data_crc32)),
+    ]);
+    let genres = app.current_genres();
+    if !genres.is_empty() {
+        lines.push(field("Genres", genres.join(", ")));
+    }
+    lines
+}
+
+fn draw_file_info_popup(frame: &mut Frame, app: &App, scroll: u16) {
+    let scheme = app.scheme();
+    let lines = file_info_lines(app);
+    #[allow(clippy::cast_possible_truncation)]
+    let scroll = scroll.min(lines.len().saturating_sub(1) as u16);
+
+    let area = centered_rect(50, 75, frame.area());
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .title(" File Info (j/k scroll) ")
+        .title_style(Style::default().fg(scheme.title).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(scheme.border_focus))
+        .style(Style::default().bg(scheme.background));
+    frame.render_widget(Paragraph::new(lines).block(block).scroll((scroll, 0)), area);
+}
+
+fn draw_write_trace_popup(frame: &mut Frame, app: &App, scroll: u16) {
+    let scheme = app.scheme();
+    let label = Style::default().fg(scheme.text_secondary);
+    let mut lines = vec![Line::styled("  Cycle          Address  Value", label)];
+    lines.extend(app.write_trace.iter().map(|event| {
+        Line::from(format!(
+            "  {:<14} ${:04X}    ${:02X}",
+            event.cpu_cycle, event.address, event.value
+        ))
+    }));
+    if app.write_trace.is_empty() {
+        lines.push(Line::styled("  Waiting for SID writes...", label));
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let scroll = scroll.min(lines.len().saturating_sub(1) as u16);
+
+    let area = centered_rect(50, 75, frame.area());
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .title(format!(" SID Writes (last {WRITE_TRACE_LEN}, j/k scroll) "))
+        .title_style(Style::default().fg(scheme.title).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(scheme.border_focus))
+        .style(Style::default().bg(scheme.background));
+    frame.render_widget(Paragraph::new(lines).block(block).scroll((scroll, 0)), area);
+}
+
+/// Width of the equalizer slider bars in characters
+const EQ_SLIDER_WIDTH: usize = 20;
+
+fn draw_equalizer_popup(frame: &mut Frame, app: &App, field: usize) {
+    let scheme = app.scheme();
+    let sliders = [("Cutoff", app.eq_cutoff), ("Resonance", app.eq_resonance)];
+
+    let mut lines: Vec<Line> = sliders
+        .iter()
+        .enumerate()
+        .map(|(i, &(name, value))| {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let filled = (value * EQ_SLIDER_WIDTH as f32).round() as usize;
+            let bar = format!(
+                "[{}{}]",
+                "#".repeat(filled),
+                "-".repeat(EQ_SLIDER_WIDTH - filled.min(EQ_SLIDER_WIDTH))
+            );
+            let style = if i == field {
+                Style::default()
+                    .fg(scheme.highlight_fg)
+                    .bg(scheme.highlight_bg)
+            } else {
+                Style::default().fg(scheme.text_primary)
+            };
+            Line::from(Span::styled(
+                format!(" {name:<10} {bar} {:>3.0}% ", value * 100.0),
+                style,
+            ))
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(format!(
+        " Filter: {}",
+        if app.eq_enabled { "on" } else { "off" }
+    )));
+
+    let area = centered_rect(45, 30, frame.area());
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .title(" Output Filter ")
+        .title_style(Style::default().fg(scheme.title).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(scheme.border_focus))
+        .style(Style::default().bg(scheme.background));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn draw_voice_color_popup(
+    frame: &mut Frame,
+    app: &App,
+    voice_idx: usize,
+    rgb: [u8; 3],
+    field: usize,
+) {
+    let scheme = app.scheme();
+    let [r, g, b] = rgb;
+
+    let mut lines: Vec<Line> = ["Red", "Green", "Blue"]
+        .iter()
+        .zip(rgb)
+        .enumerate()
+        .map(|(i, (name, value))| {
+            let style = if i == field {
+                Style::default()
+                    .fg(scheme.highlight_fg)
+                    .bg(scheme.highlight_bg)
+            } else {
+                Style::default().fg(scheme.text_primary)
+            };
+            Line::from(Span::styled(format!(" {name:<6} {value:>3} "), style))
+        })
+        .collect();
+    lines.push(Line::from(vec![
+        Span::styled(" Preview ", Style::default().fg(scheme.text_secondary)),
+        Span::styled(
+            "\u{2588}".repeat(6),
+            Style::default().fg(Color::Rgb(r, g, b)),
+        ),
+    ]));
+
+    let full = frame.area();
+    let width = 30.min(full.width);
+    let height = 6.min(full.height);
+    let area = Rect {
+        x: full.x + (full.width - width) / 2,
+        y: full.y + (full.height - height) / 2,
+        width,
+        height,
+    };
+
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .title(format!(" Voice {} Color (Tab, 0-9) ", voice_idx + 1))
+        .title_style(Style::default().fg(scheme.title).bold())
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(scheme.border_focus))
+        .style(Style::default().bg(scheme.background));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
 fn draw_popup(frame: &mut Frame, app: &App) {
-    if matches!(app.popup, Popup::ColorScheme) {
-        draw_color_scheme_popup(frame, app);
-        return;
+    match app.popup {
+        Popup::ColorScheme => return draw_color_scheme_popup(frame, app),
+        Popup::MirrorList => return draw_mirror_list_popup(frame, app),
+        Popup::SamplingMethod => return draw_sampling_method_popup(frame, app),
+        Popup::MemDump { start, ref data } => return draw_mem_dump_popup(frame, app, start, data),
+        Popup::SubsongChipOverride => return draw_subsong_chip_popup(frame, app),
+        Popup::VoiceColorEditor {
+            voice_idx,
+            r,
+            g,
+            b,
+            field,
+        } => return draw_voice_color_popup(frame, app, voice_idx, [r, g, b], field),
+        Popup::FileInfo { scroll } => return draw_file_info_popup(frame, app, scroll),
+        Popup::WriteTrace { scroll } => return draw_write_trace_popup(frame, app, scroll),
+        Popup::Equalizer { field } => return draw_equalizer_popup(frame, app, field),
+        Popup::GenreBrowser { selected } => return draw_genre_browser_popup(frame, app, selected),
+        _ => {}
     }
 
     let scheme = app.scheme();
 
     let (title, content, small) = match &app.popup {
-        Popup::None | Popup::ColorScheme => return,
+        Popup::None
+        | Popup::ColorScheme
+        | Popup::MirrorList
+        | Popup::SamplingMethod
+        | Popup::MemDump { .. }
+        | Popup::SubsongChipOverride
+        | Popup::VoiceColorEditor { .. }
+        | Popup::FileInfo { .. }
+        | Popup::WriteTrace { .. }
+        | Popup::Equalizer { .. }
+        | Popup::GenreBrowser { .. } => return,
         Popup::Help => (" Help ", help_text(scheme), true),
-        Popup::Error(msg) => (" Error ", vec![Line::from(msg.as_str())], false),
+        Popup::Error(msg) => (" Error ", error_lines(app, msg), false),
+        Popup::ValidationWarnings => (
+            " SID Warnings ",
+            app.validation_warnings()
+                .iter()
+                .map(|w| Line::from(format!(" ! {w}")))
+                .collect(),
+            false,
+        ),
+        Popup::HvscStats => (
+            " HVSC Stats ",
+            app.hvsc_browser
+                .songlength_stats()
+                .unwrap_or_default()
+                .iter()
+                .map(|part| Line::from(format!(" {part}")))
+                .collect(),
+            true,
+        ),
+        Popup::PerfStats => (" Performance ", perf_stats_lines(app), true),
+        Popup::CpuDebug => (" CPU Registers ", cpu_debug_lines(app), true),
+        Popup::StepMode => (" Step Mode ", step_mode_lines(app), false),
         Popup::SaveConfirm => (
             " Save Playlist? ",
             vec![
@@ -668,9 +1626,38 @@ fn draw_popup(frame: &mut Frame, app: &App) {
                     Span::styled("N", Style::default().fg(scheme.title).bold()),
                     Span::raw(" = Discard"),
                 ]),
+                Line::from(vec![
+                    Span::raw("    "),
+                    Span::styled("P", Style::default().fg(scheme.accent).bold()),
+                    Span::raw(" = Save Portable (relative paths)"),
+                ]),
             ],
             true,
         ),
+        Popup::ExpandConfirm { index, songs } => {
+            let name = app
+                .playlist_browser
+                .playlist
+                .entries
+                .get(*index)
+                .map_or("", |entry| entry.display_name.as_str());
+            (
+                " Expand Entry? ",
+                vec![
+                    Line::from(""),
+                    Line::from(format!("  Expand [{name}] into {songs} subsong entries?")),
+                    Line::from(""),
+                    Line::from(vec![
+                        Span::raw("    "),
+                        Span::styled("Y", Style::default().fg(scheme.accent).bold()),
+                        Span::raw("/Enter = Expand    "),
+                        Span::styled("N", Style::default().fg(scheme.title).bold()),
+                        Span::raw(" = Cancel"),
+                    ]),
+                ],
+                true,
+            )
+        }
         Popup::HvscSearch => {
             let query = app.hvsc_search.as_deref().unwrap_or("");
             let line = Line::from(vec![
@@ -678,8 +1665,13 @@ fn draw_popup(frame: &mut Frame, app: &App) {
                 Span::raw(query),
                 Span::styled("_", Style::default().fg(scheme.accent)),
             ]);
+            let title = if app.hvsc_browser.stil.is_some() {
+                " STIL Search "
+            } else {
+                " Filename Search "
+            };
             (
-                " STIL Search ",
+                title,
                 vec![
                     Line::from("  Type search text, Enter to search, Esc to cancel"),
                     Line::from(""),
@@ -688,6 +1680,37 @@ fn draw_popup(frame: &mut Frame, app: &App) {
                 true,
             )
         }
+        Popup::SortPlaylist { ignore_articles } => (
+            " Sort Playlist ",
+            vec![
+                Line::from("  Sort by name, Space toggles, Enter sorts, Esc cancels"),
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled(
+                        if *ignore_articles { "  [x] " } else { "  [ ] " },
+                        Style::default().fg(scheme.accent),
+                    ),
+                    Span::raw("Ignore articles (The, A, An)"),
+                ]),
+            ],
+            true,
+        ),
+        Popup::MergePlaylist { path } => {
+            let line = Line::from(vec![
+                Span::styled(" > ", Style::default().fg(scheme.accent)),
+                Span::raw(path.as_str()),
+                Span::styled("_", Style::default().fg(scheme.accent)),
+            ]);
+            (
+                " Merge Playlist ",
+                vec![
+                    Line::from("  Path to .m3u or .xspf file, Enter to merge, Esc to cancel"),
+                    Line::from(""),
+                    line,
+                ],
+                true,
+            )
+        }
     };
 
     let area = if small {
@@ -708,6 +1731,77 @@ fn draw_popup(frame: &mut Frame, app: &App) {
     frame.render_widget(para, area);
 }
 
+/// Error message, followed by how many more errors wait behind it.
+fn error_lines(app: &App, msg: &str) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(msg.to_string())];
+    let pending = app.error_queue.len();
+    if pending > 0 {
+        let noun = if pending == 1 { "error" } else { "errors" };
+        lines.push(Line::from(""));
+        lines.push(Line::styled(
+            format!("[{pending} more {noun}]"),
+            Style::default().fg(app.scheme().text_secondary),
+        ));
+    }
+    lines
+}
+
+/// Frame count and voice envelope levels for the F5 step mode popup.
+fn step_mode_lines(app: &App) -> Vec<Line<'static>> {
+    let envelopes: Vec<String> = app
+        .step_envelopes
+        .iter()
+        .map(|level| format!("{level:02X}"))
+        .collect();
+    vec![
+        Line::from(format!(" Frame:     {}", app.step_frames)),
+        Line::from(format!(" Envelopes: {}", envelopes.join(" "))),
+        Line::from(""),
+        Line::from(" F6 = step one frame, F5/Esc = resume"),
+    ]
+}
+
+/// CPU steps per play call and emulation headroom for the F2 popup.
+fn perf_stats_lines(app: &App) -> Vec<Line<'static>> {
+    let Some(perf) = app.perf_summary else {
+        return vec![Line::from(" No frames played yet")];
+    };
+    vec![
+        Line::from(format!(" Steps/frame min: {}", perf.min_steps)),
+        Line::from(format!(" Steps/frame max: {}", perf.max_steps)),
+        Line::from(format!(" Steps/frame avg: {:.1}", perf.avg_steps)),
+        Line::from(format!(
+            " Frame time avg:  {:.1} µs",
+            perf.avg_duration.as_secs_f64() * 1e6
+        )),
+        Line::from(format!(" Emulation ratio: {:.1}", perf.emulation_ratio)),
+    ]
+}
+
+fn cpu_debug_lines(app: &App) -> Vec<Line<'static>> {
+    let Some(now) = app.cpu_snapshot else {
+        return vec![Line::from(" Player unavailable")];
+    };
+    let init = app.last_init_cpu_snapshot.unwrap_or_default();
+    let row = |name: &str, now: String, init: String| {
+        Line::from(format!(" {name:<3} {now:>6}  {init:>10}"))
+    };
+    let hex8 = |value: u8| format!("${value:02X}");
+    vec![
+        Line::from("          Now  After init"),
+        row(
+            "PC",
+            format!("${:04X}", now.pc),
+            format!("${:04X}", init.pc),
+        ),
+        row("A", hex8(now.a), hex8(init.a)),
+        row("X", hex8(now.x), hex8(init.x)),
+        row("Y", hex8(now.y), hex8(init.y)),
+        row("SP", hex8(now.sp), hex8(init.sp)),
+        row("P", hex8(now.status), hex8(init.status)),
+    ]
+}
+
 fn help_text(scheme: &ColorScheme) -> Vec<Line<'static>> {
     let key = Style::default().fg(scheme.accent);
     let hdr = Style::default().fg(scheme.title).bold();
@@ -737,7 +1831,23 @@ fn help_text(scheme: &ColorScheme) -> Vec<Line<'static>> {
         row!("s", "6581/8580", "/", "Search STIL"),
         row!("c", "Colors", "Tab", "Switch panel"),
         row!("a", "Add to list", "BS", "Remove item"),
-        row!("r", "Refresh HVSC", "", ""),
+        row!("r/^F", "Refresh HVSC", "!", "SID warnings"),
+        row!("W", "Record WAV", "^↑↓", "Move item"),
+        row!("^Q", "Sampling", "^D", "Memory dump"),
+        row!("^S", "Subsong chip", "</>", "Speed -/+"),
+        row!("T", "HVSC stats", "O 0-9", "Solo voice/off"),
+        row!("F2", "Perf stats", "V", "Vim keys"),
+        row!("F 1-8", "Filter bypass", "^V 1-9", "Voice color"),
+        row!("M", "Scope style", "F5/F6", "Step mode/step"),
+        row!("^W", "Watch list", "F3/F4", "Frames/CPU regs"),
+        row!("'a-z", "Jump to letter", "f", "Filter list"),
+        row!("I", "File info", "E", "Output filter"),
+        row!("^L", "Mirror list", "^O", "Merge playlist"),
+        row!("S", "Sort list", "^T", "SID write trace"),
+        row!("X", "Expand subsongs", "M-m", "Mix mode"),
+        row!("^R", "Shuffle list", "^Z", "Undo shuffle"),
+        row!(":", "Commands", "^G", "Browse genres"),
+        row!("G", "Show playing", "", ""),
         Line::from("─────────────────┴────────────────"),
         Line::from(vec![
             Span::styled(" h/?", key),
@@ -748,6 +1858,19 @@ fn help_text(scheme: &ColorScheme) -> Vec<Line<'static>> {
     ]
 }
 
+/// A `width` x `height` rectangle in the top-right corner of `area`, moved down
+/// by `slot` heights so several can stack. Clipped to `area`.
+fn corner_rect(width: u16, height: u16, slot: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let y = area.y.saturating_add(slot.saturating_mul(height));
+    Rect {
+        x: area.right().saturating_sub(width),
+        y: y.min(area.bottom()),
+        width,
+        height: height.min(area.bottom().saturating_sub(y)),
+    }
+}
+
 /// Creates a centered rectangle for popups.
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let [_, center, _] = Layout::vertical([
@@ -766,3 +1889,210 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
 
     center
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playlist::Playlist;
+    use crate::tui::app::Action;
+    use crate::tui::app::tests::test_app;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    #[test]
+    fn small_terminal_shows_size_warning() {
+        assert!(!fits_min_size(40, 20));
+        assert!(fits_min_size(MIN_WIDTH, MIN_HEIGHT));
+
+        let mut terminal = Terminal::new(TestBackend::new(40, 20)).unwrap();
+        terminal.draw(draw_too_small).unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("Terminal too small. Minimum 80×24."));
+    }
+
+    #[test]
+    fn vu_bar_height_follows_level() {
+        let area = Rect::new(0, 0, 38, 8);
+        let height = f64::from(area.height);
+        let bar = vu_bar(3.0, 8.0, 0.5, height, Color::Green);
+        assert!((bar.height - 0.5 * height).abs() < f64::EPSILON);
+        assert!((bar.y, bar.x, bar.width) == (0.0, 3.0, 8.0));
+        assert!(vu_bar(0.0, 8.0, 1.5, height, Color::Green).height <= height);
+    }
+
+    #[test]
+    fn footer_follows_focus_and_popup() {
+        let keys = |focus, popup| {
+            context_bindings(focus, &popup)
+                .iter()
+                .map(|&(k, _)| k)
+                .collect::<Vec<_>>()
+        };
+        let playlist = keys(BrowserFocus::Playlist, Popup::None);
+        assert!(playlist.contains(&"BS") && !playlist.contains(&"/"));
+        let hvsc = keys(BrowserFocus::Hvsc, Popup::None);
+        assert!(hvsc.contains(&"/") && hvsc.contains(&"←") && !hvsc.contains(&"BS"));
+        assert_eq!(keys(BrowserFocus::Hvsc, Popup::ColorScheme)[0], "Esc");
+        assert_eq!(keys(BrowserFocus::Playlist, Popup::Help), ["Esc"]);
+    }
+
+    #[test]
+    fn corner_rects_stack_down_from_top_right() {
+        let area = Rect::new(0, 0, 80, 24);
+        assert_eq!(corner_rect(20, 3, 0, area), Rect::new(60, 0, 20, 3));
+        assert_eq!(corner_rect(20, 3, 2, area), Rect::new(60, 6, 20, 3));
+        assert_eq!(corner_rect(100, 3, 0, area).width, 80);
+        assert_eq!(corner_rect(20, 3, 10, area).height, 0);
+    }
+
+    #[test]
+    fn note_names_follow_a440() {
+        assert_eq!(hz_to_note_name(440.0), "A4");
+        assert_eq!(hz_to_note_name(880.0), "A5");
+        assert_eq!(hz_to_note_name(261.63), "C4");
+        assert_eq!(hz_to_note_name(452.0), "A4");
+        assert_eq!(hz_to_note_name(0.0), "---");
+    }
+
+    #[test]
+    fn command_line_shows_input_and_errors() {
+        let scheme = &SCHEMES[0];
+        let text = |line: Option<Line>| line.map(|l| l.to_string());
+        let typing = InputMode::CommandMode {
+            input: "goto 5".to_string(),
+        };
+        assert_eq!(
+            text(command_line(&typing, None, scheme)),
+            Some(":goto 5\u{2588}".to_string())
+        );
+        assert_eq!(text(command_line(&InputMode::Normal, None, scheme)), None);
+
+        let error = crate::tui::command::parse_command("dance").unwrap_err();
+        let line = command_line(&InputMode::Normal, Some(&error), scheme).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(40, 1)).unwrap();
+        terminal
+            .draw(|frame| frame.render_widget(Paragraph::new(line), frame.area()))
+            .unwrap();
+        let rendered: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(rendered.contains("Unknown command: dance"));
+    }
+
+    #[test]
+    fn filename_search_title_shows_stil_error() {
+        let mut browser = HvscBrowser::new("");
+        assert!(search_results_title("rob", 2, &browser).contains("(2 found) [filename search] "));
+        browser.stil_error = Some("connection refused".to_string());
+        let title = search_results_title("rob", 2, &browser);
+        assert!(title.ends_with("[filename search: connection refused] "));
+    }
+
+    #[test]
+    fn hvsc_title_counts_only_filtered_entries() {
+        let mut app = test_app(Playlist::new());
+        app.browser_focus = BrowserFocus::Hvsc;
+        assert!(hvsc_directory_title(&app, None).contains("[0 files, 3 dirs]"));
+
+        app.start_browser_filter();
+        for c in "mu".chars() {
+            app.push_filter_char(c);
+        }
+        let filtered = app.filter_indices_for(BrowserFocus::Hvsc);
+        let title = hvsc_directory_title(&app, filtered.as_deref());
+        assert!(title.contains("[0 files, 1 dirs] [by name] [filter: mu_]"));
+    }
+
+    #[test]
+    fn unknown_command_shows_inline_error() {
+        let mut app = test_app(Playlist::new());
+        app.start_command_mode();
+        for c in "rewind".chars() {
+            app.push_command_char(c);
+        }
+        assert_eq!(app.run_command_line(), Action::None);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal
+            .draw(|frame| draw_responsive_layout(frame, &mut app))
+            .unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("Unknown command: rewind"));
+    }
+
+    #[test]
+    fn subsong_chip_popup_shows_file_model_as_default() {
+        let mut app = test_app(Playlist::new());
+        let mut sid = crate::create_silent_sid();
+        (sid.version, sid.flags, sid.songs) = (2, 0x20, 2);
+        app.current_browser_sid = Some(sid);
+        app.total_songs = 2;
+        app.subsong_chip_overrides.insert(2, ChipModel::Mos6581);
+        app.popup = Popup::SubsongChipOverride;
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal
+            .draw(|frame| draw_responsive_layout(frame, &mut app))
+            .unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("Song 1   default (8580)"));
+        assert!(text.contains("Song 2   6581"));
+    }
+
+    #[test]
+    fn narrow_terminal_collapses_browser() {
+        let mut app = test_app(Playlist::new());
+        let mut terminal = Terminal::new(TestBackend::new(79, 24)).unwrap();
+        let mut shows_playlist = |app: &mut App| {
+            terminal
+                .draw(|frame| draw_responsive_layout(frame, app))
+                .unwrap();
+            let text: String = terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            text.contains("┌ Playlist ")
+        };
+        assert!(!shows_playlist(&mut app));
+        assert!(app.compact_layout);
+
+        // Tab cycles hidden → playlist → HVSC → hidden
+        for (visible, focus) in [
+            (true, BrowserFocus::Playlist),
+            (true, BrowserFocus::Hvsc),
+            (false, BrowserFocus::Hvsc),
+            (true, BrowserFocus::Playlist),
+        ] {
+            app.toggle_browser_focus();
+            assert_eq!(shows_playlist(&mut app), visible);
+            assert_eq!(app.browser_focus, focus);
+        }
+
+        let [browser, player] = split_browser(Rect::new(0, 0, 120, 30), BROWSER_WIDTH);
+        assert_eq!((browser.width, player.width), (BROWSER_WIDTH, 88));
+    }
+}
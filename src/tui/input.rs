@@ -3,23 +3,37 @@
 
 //! Keyboard input handling.
 
-use crossterm::event::KeyCode;
-use std::io;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use super::app::{App, BrowserFocus, Popup};
+use crate::playlist::SortKey;
+
+use super::app::{Action, App, BrowserFocus, InputMode, Popup};
+use super::vim::VimInput;
+
+/// Playback speed change per `<`/`>` key press
+const SPEED_STEP: f32 = 0.1;
+/// Equalizer slider change per Left/Right key press
+const EQ_STEP: f32 = 0.05;
 
 pub enum KeyHandled {
-    Consumed(Option<io::Result<()>>),
+    Consumed(Action),
     PassThrough,
 }
 
-/// Processes key input, returning Some to exit the app.
-pub fn handle_key(app: &mut App, key: KeyCode) -> Option<io::Result<()>> {
+/// Processes key input, returning `Action::Quit` to exit the app.
+pub fn handle_key(app: &mut App, event: KeyEvent) -> Action {
+    let key = event.code;
+    app.command_error = None;
+
     // Save confirmation needs Y/N before other keys work
     if matches!(app.popup, Popup::SaveConfirm) {
         return handle_save_confirm(app, key);
     }
 
+    if matches!(app.input_mode, InputMode::CommandMode { .. }) {
+        return handle_command_key(app, key);
+    }
+
     match handle_popups(app, key) {
         KeyHandled::Consumed(res) => return res,
         KeyHandled::PassThrough => {}
@@ -30,11 +44,74 @@ pub fn handle_key(app: &mut App, key: KeyCode) -> Option<io::Result<()>> {
         && app.browser_focus == BrowserFocus::Hvsc
         && handle_hvsc_search_results(app, key)
     {
-        return None;
+        return Action::None;
+    }
+
+    if event.modifiers.contains(KeyModifiers::CONTROL) {
+        handle_ctrl_key(app, key);
+        return Action::None;
+    }
+
+    if event.modifiers.contains(KeyModifiers::ALT) && key == KeyCode::Char('m') {
+        app.cycle_mix_mode();
+        return Action::None;
+    }
+
+    // Typed text goes to the inline browser filter until Esc
+    if app.is_filtering() && handle_filter_key(app, key) {
+        return Action::None;
+    }
+
+    // Ctrl+V followed by a voice number opens its color editor
+    if app.voice_color_key_pending {
+        app.voice_color_key_pending = false;
+        if let KeyCode::Char(c @ '1'..='9') = key {
+            app.open_voice_color_editor(c as usize - '1' as usize);
+            return Action::None;
+        }
+    }
+
+    // F followed by a SID number toggles that SID's filter bypass
+    if app.filter_key_pending {
+        app.filter_key_pending = false;
+        if let KeyCode::Char(c @ '1'..='8') = key {
+            app.toggle_filter_bypass(c as usize - '1' as usize);
+            return Action::None;
+        }
+    }
+
+    // O followed by a voice number solos that voice; O then 0 clears the solo
+    if app.solo_key_pending {
+        app.solo_key_pending = false;
+        if let KeyCode::Char(c @ '0'..='9') = key {
+            app.set_solo_voice(c.to_digit(10).and_then(|d| (d as usize).checked_sub(1)));
+            return Action::None;
+        }
+    }
+
+    // ' followed by any character jumps to it, reaching letters that have bindings
+    if app.jump_key_pending {
+        app.jump_key_pending = false;
+        if let KeyCode::Char(c) = key {
+            app.jump_to_letter(c);
+            return Action::None;
+        }
+    }
+
+    // Vim sequences take priority; other keys keep their regular bindings
+    if app.vim_mode {
+        match app.vim.feed(&mut app.vim_key_buffer, key) {
+            VimInput::Pending => return Action::None,
+            VimInput::Command(command) => {
+                app.run_vim_command(command);
+                return Action::None;
+            }
+            VimInput::NotHandled => {}
+        }
     }
 
     match key {
-        KeyCode::Char('q') if app.request_quit() => return Some(Ok(())),
+        KeyCode::Char('q') if app.request_quit() => return Action::Quit,
         KeyCode::Esc => app.close_popup(),
         KeyCode::Char(' ') => app.toggle_pause(),
         KeyCode::Char('s') => app.switch_chip(),
@@ -43,6 +120,26 @@ pub fn handle_key(app: &mut App, key: KeyCode) -> Option<io::Result<()>> {
         KeyCode::Char('r') => app.refresh_hvsc_cache(),
         KeyCode::Tab => app.toggle_browser_focus(),
         KeyCode::Char('/') => app.start_hvsc_search(),
+        KeyCode::Char('!') => app.show_validation_warnings(),
+        KeyCode::Char('W') => app.toggle_recording(),
+        KeyCode::Char('T') => app.show_hvsc_stats(),
+        KeyCode::Char('V') => app.toggle_vim_mode(),
+        KeyCode::Char('F') => app.filter_key_pending = true,
+        KeyCode::Char('O') => app.solo_key_pending = true,
+        KeyCode::Char('\'') => app.jump_key_pending = true,
+        KeyCode::Char('M') => app.cycle_scope_marker(),
+        KeyCode::Char('I') => app.show_file_info(),
+        KeyCode::Char('E') => app.open_equalizer(),
+        KeyCode::Char('S') => app.open_sort_popup(),
+        KeyCode::Char('X') => app.expand_selected_playlist_entry(),
+        KeyCode::Char('G') => app.goto_current_tune(),
+        KeyCode::Char(':') => app.start_command_mode(),
+        KeyCode::F(2) => app.show_perf_stats(),
+        KeyCode::F(3) => app.show_debug_overlay = !app.show_debug_overlay,
+        KeyCode::F(4) => app.show_cpu_debug(),
+        KeyCode::F(5) => app.toggle_step_mode(),
+        KeyCode::Char('<') => app.change_speed(-SPEED_STEP),
+        KeyCode::Char('>') => app.change_speed(SPEED_STEP),
 
         KeyCode::Char(c @ '1'..='9') => app.goto_song(c.to_digit(10).unwrap() as u16),
         KeyCode::Char('+' | 'n') => app.next_song(),
@@ -53,27 +150,68 @@ pub fn handle_key(app: &mut App, key: KeyCode) -> Option<io::Result<()>> {
         KeyCode::Left => app.browser_back(),
         KeyCode::Enter => app.load_selected(),
         KeyCode::Char('a') => app.add_current_to_playlist(),
+        KeyCode::Char('f') => app.start_browser_filter(),
         KeyCode::Backspace => handle_backspace(app),
+        KeyCode::Char(c) if c.is_alphanumeric() => app.jump_to_letter(c),
 
         _ => {}
     }
-    None
+    Action::None
 }
 
 fn handle_popups(app: &mut App, key: KeyCode) -> KeyHandled {
     match app.popup {
         Popup::HvscSearch => KeyHandled::Consumed(handle_hvsc_search_popup(app, key)),
         Popup::SaveConfirm => KeyHandled::Consumed(handle_save_confirm(app, key)),
-        Popup::Help | Popup::Error(_) => {
+        Popup::Help
+        | Popup::Error(_)
+        | Popup::ValidationWarnings
+        | Popup::HvscStats
+        | Popup::PerfStats
+        | Popup::CpuDebug => {
             app.close_popup();
-            KeyHandled::Consumed(None)
+            KeyHandled::Consumed(Action::None)
         }
         Popup::ColorScheme => KeyHandled::Consumed(handle_color_scheme_popup(app, key)),
+        Popup::MirrorList => KeyHandled::Consumed(handle_mirror_list_popup(app, key)),
+        Popup::SamplingMethod => KeyHandled::Consumed(handle_sampling_method_popup(app, key)),
+        Popup::MemDump { .. } => KeyHandled::Consumed(handle_mem_dump_popup(app, key)),
+        Popup::SubsongChipOverride => KeyHandled::Consumed(handle_subsong_chip_popup(app, key)),
+        Popup::VoiceColorEditor { .. } => KeyHandled::Consumed(handle_voice_color_popup(app, key)),
+        Popup::StepMode => KeyHandled::Consumed(handle_step_mode_popup(app, key)),
+        Popup::FileInfo { .. } => KeyHandled::Consumed(handle_file_info_popup(app, key)),
+        Popup::WriteTrace { .. } => KeyHandled::Consumed(handle_write_trace_popup(app, key)),
+        Popup::Equalizer { .. } => KeyHandled::Consumed(handle_equalizer_popup(app, key)),
+        Popup::SortPlaylist { .. } => KeyHandled::Consumed(handle_sort_popup(app, key)),
+        Popup::MergePlaylist { .. } => KeyHandled::Consumed(handle_merge_playlist_popup(app, key)),
+        Popup::GenreBrowser { .. } => KeyHandled::Consumed(handle_genre_browser_popup(app, key)),
+        Popup::ExpandConfirm { .. } => KeyHandled::Consumed(handle_expand_confirm(app, key)),
         Popup::None => KeyHandled::PassThrough,
     }
 }
 
-fn handle_hvsc_search_popup(app: &mut App, key: KeyCode) -> Option<io::Result<()>> {
+/// Handles Ctrl-modified key combinations.
+fn handle_ctrl_key(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Char('f') => app.refresh_hvsc_cache(),
+        KeyCode::Char('o') => app.open_merge_playlist(),
+        KeyCode::Char('l') => app.open_mirror_list(),
+        KeyCode::Char('q') => app.open_sampling_method_picker(),
+        KeyCode::Char('d') => app.open_memory_dump(),
+        KeyCode::Char('s') => app.cycle_hvsc_sort_or_subsong_overrides(),
+        KeyCode::Char('v') => app.voice_color_key_pending = true,
+        KeyCode::Char('w') => app.toggle_playlist_watch(),
+        KeyCode::Char('t') => app.open_write_trace(),
+        KeyCode::Char('r') => app.randomize_playlist(),
+        KeyCode::Char('z') => app.undo_playlist_shuffle(),
+        KeyCode::Char('g') => app.open_genre_browser(),
+        KeyCode::Up => app.move_playlist_entry(false),
+        KeyCode::Down => app.move_playlist_entry(true),
+        _ => {}
+    }
+}
+
+fn handle_hvsc_search_popup(app: &mut App, key: KeyCode) -> Action {
     match key {
         KeyCode::Esc => {
             app.popup = Popup::None;
@@ -87,7 +225,55 @@ fn handle_hvsc_search_popup(app: &mut App, key: KeyCode) -> Option<io::Result<()
         KeyCode::Char(ch) => app.hvsc_search_input(ch),
         _ => {}
     }
-    None
+    Action::None
+}
+
+fn handle_sort_popup(app: &mut App, key: KeyCode) -> Action {
+    let Popup::SortPlaylist { ignore_articles } = &mut app.popup else {
+        return Action::None;
+    };
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') => app.popup = Popup::None,
+        KeyCode::Char(' ') => *ignore_articles = !*ignore_articles,
+        KeyCode::Enter => {
+            let ignore_articles = *ignore_articles;
+            app.sort_playlist(SortKey::DisplayName { ignore_articles });
+        }
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_merge_playlist_popup(app: &mut App, key: KeyCode) -> Action {
+    let Popup::MergePlaylist { path } = &mut app.popup else {
+        return Action::None;
+    };
+    match key {
+        KeyCode::Esc => app.popup = Popup::None,
+        KeyCode::Enter => {
+            let path = std::mem::take(path);
+            app.merge_playlist_file(&path);
+        }
+        KeyCode::Backspace => {
+            path.pop();
+        }
+        KeyCode::Char(ch) => path.push(ch),
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_genre_browser_popup(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Esc => app.popup = Popup::None,
+        KeyCode::Enter => app.search_selected_genre(),
+        KeyCode::Up | KeyCode::Char('k') => app.genre_browser_step(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.genre_browser_step(1),
+        KeyCode::PageUp => app.genre_browser_step(-10),
+        KeyCode::PageDown => app.genre_browser_step(10),
+        _ => {}
+    }
+    Action::None
 }
 
 fn handle_hvsc_search_results(app: &mut App, key: KeyCode) -> bool {
@@ -102,30 +288,171 @@ fn handle_hvsc_search_results(app: &mut App, key: KeyCode) -> bool {
     true
 }
 
-fn handle_color_scheme_popup(app: &mut App, key: KeyCode) -> Option<io::Result<()>> {
+fn handle_command_key(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Esc => app.cancel_command_mode(),
+        KeyCode::Enter => return app.run_command_line(),
+        KeyCode::Backspace => app.pop_command_char(),
+        KeyCode::Up => app.browse_command_history(true),
+        KeyCode::Down => app.browse_command_history(false),
+        KeyCode::Char(c) => app.push_command_char(c),
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_filter_key(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Esc => app.clear_browser_filter(),
+        KeyCode::Backspace => app.pop_filter_char(),
+        KeyCode::Char(c) => app.push_filter_char(c),
+        _ => return false,
+    }
+    true
+}
+
+fn handle_color_scheme_popup(app: &mut App, key: KeyCode) -> Action {
     match key {
         KeyCode::Esc | KeyCode::Enter | KeyCode::Char('c') => app.popup = Popup::None,
         KeyCode::Up | KeyCode::Char('k') => app.prev_color_scheme(),
         KeyCode::Down | KeyCode::Char('j') => app.next_color_scheme(),
         _ => {}
     }
-    None
+    Action::None
 }
 
-fn handle_save_confirm(app: &mut App, key: KeyCode) -> Option<io::Result<()>> {
+fn handle_mirror_list_popup(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Esc | KeyCode::Enter => app.popup = Popup::None,
+        KeyCode::Char('k') => app.mirror_list_prev(),
+        KeyCode::Char('j') => app.mirror_list_next(),
+        KeyCode::Up => app.move_mirror_up(),
+        KeyCode::Down => app.move_mirror_down(),
+        KeyCode::Backspace | KeyCode::Delete => app.remove_selected_mirror(),
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_sampling_method_popup(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Esc => app.popup = Popup::None,
+        KeyCode::Enter => app.apply_sampling_method(),
+        KeyCode::Up | KeyCode::Char('k') => app.sampling_method_prev(),
+        KeyCode::Down | KeyCode::Char('j') => app.sampling_method_next(),
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_mem_dump_popup(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Esc | KeyCode::Enter => app.popup = Popup::None,
+        KeyCode::Up | KeyCode::Char('k') => app.scroll_memory_dump(-16),
+        KeyCode::Down | KeyCode::Char('j') => app.scroll_memory_dump(16),
+        KeyCode::PageUp => app.scroll_memory_dump(-256),
+        KeyCode::PageDown => app.scroll_memory_dump(256),
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_equalizer_popup(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('E') => app.popup = Popup::None,
+        KeyCode::Up | KeyCode::Down | KeyCode::Tab | KeyCode::Char('j' | 'k') => {
+            app.equalizer_next_field();
+        }
+        KeyCode::Left | KeyCode::Char('h') => app.adjust_equalizer(-EQ_STEP),
+        KeyCode::Right | KeyCode::Char('l') => app.adjust_equalizer(EQ_STEP),
+        KeyCode::Char(' ') => app.toggle_equalizer(),
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_file_info_popup(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('I') => app.popup = Popup::None,
+        KeyCode::Up | KeyCode::Char('k') => app.scroll_file_info(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.scroll_file_info(1),
+        KeyCode::PageUp => app.scroll_file_info(-10),
+        KeyCode::PageDown => app.scroll_file_info(10),
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_write_trace_popup(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Esc | KeyCode::Enter => app.close_write_trace(),
+        KeyCode::Up | KeyCode::Char('k') => app.scroll_write_trace(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.scroll_write_trace(1),
+        KeyCode::PageUp => app.scroll_write_trace(-10),
+        KeyCode::PageDown => app.scroll_write_trace(10),
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_subsong_chip_popup(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Esc => app.popup = Popup::None,
+        KeyCode::Enter | KeyCode::Char(' ') => app.cycle_subsong_override(),
+        KeyCode::Up | KeyCode::Char('k') => app.subsong_override_prev(),
+        KeyCode::Down | KeyCode::Char('j') => app.subsong_override_next(),
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_voice_color_popup(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Esc => app.popup = Popup::None,
+        KeyCode::Enter => app.apply_voice_color(),
+        KeyCode::Tab => app.voice_color_next_field(),
+        KeyCode::Backspace => app.voice_color_backspace(),
+        KeyCode::Char(c @ '0'..='9') => app.voice_color_digit(c as u8 - b'0'),
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_step_mode_popup(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Esc | KeyCode::F(5) => app.toggle_step_mode(),
+        KeyCode::F(6) => app.step_frame(),
+        _ => {}
+    }
+    Action::None
+}
+
+fn handle_save_confirm(app: &mut App, key: KeyCode) -> Action {
     match key {
         KeyCode::Char('y' | 'Y') | KeyCode::Enter => {
             app.save_playlist();
-            Some(Ok(()))
+            Action::Quit
+        }
+        KeyCode::Char('p' | 'P') => {
+            app.save_playlist_portable();
+            Action::Quit
         }
-        KeyCode::Char('n' | 'N') => Some(Ok(())),
+        KeyCode::Char('n' | 'N') => Action::Quit,
         _ => {
             app.close_popup();
-            None
+            Action::None
         }
     }
 }
 
+fn handle_expand_confirm(app: &mut App, key: KeyCode) -> Action {
+    match key {
+        KeyCode::Char('y' | 'Y') | KeyCode::Enter => app.confirm_playlist_expansion(),
+        _ => app.popup = Popup::None,
+    }
+    Action::None
+}
+
 fn handle_backspace(app: &mut App) {
     if app.browser_focus == BrowserFocus::Playlist {
         app.remove_from_playlist();
@@ -133,3 +460,86 @@ fn handle_backspace(app: &mut App) {
         app.browser_back();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playlist::Playlist;
+    use crate::tui::app::tests::test_app;
+
+    fn press(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            handle_key(app, KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn goto_command_switches_song() {
+        let mut app = test_app(Playlist::new());
+        app.total_songs = 8;
+        press(&mut app, ":goto 5");
+        handle_key(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.current_song, 5);
+        assert!(app.command_error.is_none());
+        assert_eq!(app.into_config().command_history, ["goto 5"]);
+    }
+
+    #[test]
+    fn filter_bypass_reaches_extra_sids() {
+        let mut app = test_app(Playlist::new());
+        let extra = [0xD420, 0xD440, 0xD460].map(|addr| (addr, residfp::ChipModel::Mos8580));
+        app.player
+            .lock()
+            .unwrap()
+            .set_extra_sids(extra.to_vec())
+            .unwrap();
+        press(&mut app, "F4F8");
+        let player = app.player.lock().unwrap();
+        assert!(player.is_filter_bypassed(3));
+        assert!(!player.is_filter_bypassed(7));
+    }
+
+    #[test]
+    fn ctrl_l_opens_mirror_list() {
+        let mut app = test_app(Playlist::new());
+        handle_key(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL),
+        );
+        assert!(matches!(app.popup, Popup::MirrorList));
+    }
+
+    #[test]
+    fn ctrl_o_opens_merge_prompt_for_playlist() {
+        let mut app = test_app(Playlist::new());
+        handle_key(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL),
+        );
+        assert!(matches!(app.popup, Popup::MergePlaylist { .. }));
+    }
+
+    #[test]
+    fn quote_prefix_jumps_to_bound_letters() {
+        let mut playlist = Playlist::new();
+        for name in ["Alpha", "Beta", "Rob", "Sid"] {
+            playlist.add(&format!("/sids/{name}.sid"), None);
+        }
+        let mut app = test_app(playlist);
+        press(&mut app, "'r");
+        assert_eq!(app.playlist_browser.selected_index(), 2);
+        press(&mut app, "B");
+        assert_eq!(app.playlist_browser.selected_index(), 1);
+        press(&mut app, "'s");
+        assert_eq!(app.playlist_browser.selected_index(), 3);
+    }
+
+    #[test]
+    fn o_then_digit_solos_a_voice() {
+        let mut app = test_app(Playlist::new());
+        press(&mut app, "O2");
+        assert_eq!(app.solo_voice, Some(1));
+        press(&mut app, "O0");
+        assert_eq!(app.solo_voice, None);
+    }
+}
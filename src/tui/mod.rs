@@ -4,26 +4,32 @@
 //! Terminal user interface for the SID player.
 
 mod app;
+mod command;
 mod draw;
 mod input;
+mod screenshot;
 pub mod theme;
+mod vim;
 mod widgets;
 
-use app::App;
+use app::{Action, App};
 use crossterm::{
     ExecutableCommand,
     event::{self, Event, KeyEventKind},
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use draw::draw;
+use draw::draw_app;
 use input::handle_key;
 use ratatui::DefaultTerminal;
 use std::io::{self, stdout};
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
+use crate::config::Config;
+use crate::hvsc::cache_size_warning;
 use crate::player::SharedPlayer;
 use crate::playlist::Playlist;
+use crate::remote::RemoteHandle;
 use crate::sid_file::SidFile;
 
 const TARGET_FPS: u64 = 30;
@@ -32,50 +38,108 @@ const TARGET_FPS: u64 = 30;
 pub struct TuiConfig<'a> {
     pub player: SharedPlayer,
     pub sid_file: &'a SidFile,
+    /// Path or URL `sid_file` was loaded from, if any
+    pub source: Option<String>,
     pub song: u16,
     pub playlist: Playlist,
     pub playlist_path: PathBuf,
+    /// Write playlist paths relative to the playlist directory
+    pub portable: bool,
     pub focus_hvsc: bool,
     pub playlist_modified: bool,
     pub hvsc_url: &'a str,
     pub playtime_secs: u64,
-    pub color_scheme: usize,
+    pub user_config: Config,
+    /// WebSocket remote control, if `--ws-port` was given
+    pub remote: Option<RemoteHandle>,
+    /// MPRIS service on the session bus, if it could be registered
+    #[cfg(feature = "mpris")]
+    pub mpris: Option<crate::mpris::MprisHandle>,
+    /// Quit after this much time (`--exit-after`)
+    pub exit_after: Option<Duration>,
+    /// Pick the next playlist entry at random, avoiding recent ones (`--smart-shuffle`)
+    pub smart_shuffle: bool,
+    /// Write the VU peak history here as CSV on quit (`--export-vu-history`)
+    pub export_vu_history: Option<PathBuf>,
+    /// Save the first frame as ANSI text here and quit (`--screenshot`)
+    pub screenshot: Option<PathBuf>,
+    /// Show this saved HVSC listing instead of fetching the root (`--import-hvsc-dir`)
+    pub hvsc_listing: Option<PathBuf>,
 }
 
-/// Main entry point for the TUI. Returns the final color scheme index.
-pub fn run_tui(config: TuiConfig) -> io::Result<usize> {
+/// Main entry point for the TUI. Returns the user config with any changes made.
+pub fn run_tui(config: TuiConfig) -> io::Result<Config> {
     stdout().execute(EnterAlternateScreen)?;
     enable_raw_mode()?;
 
     let terminal = ratatui::init();
-    let app = App::new(config);
-    let result = run_app(terminal, app);
+    let screenshot = config.screenshot.clone();
+    let hvsc_listing = config.hvsc_listing.clone();
+    let mut app = App::new(config);
+    app.hvsc_browser.load_stil();
+    if let Some(path) = hvsc_listing
+        && let Err(e) = app.hvsc_browser.import_directory_from_file(&path)
+    {
+        app.show_error(format!("Failed to import {}: {e}", path.display()));
+    }
+    if let Some(warning) = cache_size_warning() {
+        app.show_warning(warning);
+    }
+    let result = run_app(terminal, app, screenshot.is_some());
 
     disable_raw_mode()?;
     stdout().execute(LeaveAlternateScreen)?;
 
-    result
+    // Reported once the terminal is restored; a failed export must not lose the config
+    let mut app = result?;
+    if let Some(path) = &screenshot
+        && let Err(e) = app.export_current_screenshot(path)
+    {
+        eprintln!("Failed to save screenshot: {e}");
+    }
+    if let Err(e) = app.export_vu_history() {
+        eprintln!("Failed to export VU history: {e}");
+    }
+    Ok(app.into_config())
 }
 
-fn run_app(mut terminal: DefaultTerminal, mut app: App) -> io::Result<usize> {
+/// Runs the event loop until the user quits, or after the first frame when
+/// taking a screenshot, returning the app for saving.
+fn run_app<'a>(
+    mut terminal: DefaultTerminal,
+    mut app: App<'a>,
+    screenshot: bool,
+) -> io::Result<App<'a>> {
     let frame_duration = Duration::from_millis(1000 / TARGET_FPS);
 
     loop {
         let frame_start = Instant::now();
 
-        app.update();
-        terminal.draw(|frame| draw(frame, &mut app))?;
+        if app.update() == Action::Quit {
+            return Ok(app);
+        }
+        terminal.draw(|frame| draw_app(frame, &mut app))?;
+        if screenshot {
+            return Ok(app);
+        }
 
         let elapsed = frame_start.elapsed();
         let timeout = frame_duration.saturating_sub(elapsed);
 
-        if event::poll(timeout)?
-            && let Event::Key(key) = event::read()?
-            && key.kind == KeyEventKind::Press
-            && let Some(action) = handle_key(&mut app, key.code)
-        {
-            action?;
-            return Ok(app.color_scheme);
+        if !event::poll(timeout)? {
+            continue;
+        }
+        let action = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => handle_key(&mut app, key),
+            // Redraw right away so the size check follows the new dimensions
+            Event::Resize(..) => {
+                terminal.autoresize()?;
+                Action::None
+            }
+            _ => Action::None,
+        };
+        if action == Action::Quit {
+            return Ok(app);
         }
     }
 }
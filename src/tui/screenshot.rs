@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Mikael Lund
+
+//! Screenshots of the TUI as ANSI escape text (`--screenshot`).
+
+use ratatui::backend::TestBackend;
+use ratatui::buffer::CellWidth;
+use ratatui::style::{Color, Modifier};
+use std::fmt::Write;
+
+const RESET: &str = "\x1b[0m";
+
+/// Converts the rendered buffer to ANSI text, one line per row.
+pub fn render_to_ansi(backend: &TestBackend) -> String {
+    let buffer = backend.buffer();
+    let mut out = String::from(RESET);
+    for row in buffer.content.chunks(buffer.area.width as usize) {
+        let mut style = None;
+        let mut skip = 0;
+        for cell in row {
+            // Cells covered by a wide character to their left
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            skip = cell.cell_width().saturating_sub(1);
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(cell_style) {
+                style = Some(cell_style);
+                push_style(&mut out, cell.fg, cell.bg, cell.modifier);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str(RESET);
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Emits a reset followed by the codes for the given style.
+fn push_style(out: &mut String, fg: Color, bg: Color, modifier: Modifier) {
+    out.push_str(RESET);
+    for (flag, code) in [
+        (Modifier::BOLD, 1),
+        (Modifier::DIM, 2),
+        (Modifier::ITALIC, 3),
+        (Modifier::UNDERLINED, 4),
+        (Modifier::REVERSED, 7),
+    ] {
+        if modifier.contains(flag) {
+            let _ = write!(out, "\x1b[{code}m");
+        }
+    }
+    push_color(out, fg, 30);
+    push_color(out, bg, 40);
+}
+
+/// Emits the escape code for `color`; `base` is 30 for foreground and 40 for background.
+fn push_color(out: &mut String, color: Color, base: u8) {
+    let _ = match color {
+        Color::Reset => return,
+        Color::Rgb(r, g, b) => write!(out, "\x1b[{};2;{r};{g};{b}m", base + 8),
+        Color::Indexed(i) => write!(out, "\x1b[{};5;{i}m", base + 8),
+        named => {
+            let (offset, bright) = match named {
+                Color::Black => (0, false),
+                Color::Red => (1, false),
+                Color::Green => (2, false),
+                Color::Yellow => (3, false),
+                Color::Blue => (4, false),
+                Color::Magenta => (5, false),
+                Color::Cyan => (6, false),
+                Color::Gray => (7, false),
+                Color::DarkGray => (0, true),
+                Color::LightRed => (1, true),
+                Color::LightGreen => (2, true),
+                Color::LightYellow => (3, true),
+                Color::LightBlue => (4, true),
+                Color::LightMagenta => (5, true),
+                Color::LightCyan => (6, true),
+                _ => (7, true),
+            };
+            // Bright colors live at 90-97 and 100-107
+            let code = base + offset + if bright { 60 } else { 0 };
+            write!(out, "\x1b[{code}m")
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::style::Style;
+    use ratatui::text::Span;
+
+    fn render(span: Span) -> String {
+        let Ok(mut terminal) = Terminal::new(TestBackend::new(4, 1));
+        let Ok(_) = terminal.draw(|frame| frame.render_widget(span, frame.area()));
+        render_to_ansi(terminal.backend())
+    }
+
+    #[test]
+    fn output_starts_with_reset() {
+        assert!(render(Span::raw("hi")).starts_with("\x1b[0m"));
+    }
+
+    #[test]
+    fn cell_colors_map_to_escape_codes() {
+        let style = Style::new()
+            .fg(Color::Rgb(0x6c, 0x5e, 0xb5))
+            .bg(Color::Blue);
+        let ansi = render(Span::styled("ab", style));
+        assert!(ansi.contains("\x1b[38;2;108;94;181m\x1b[44mab"), "{ansi:?}");
+        // The unstyled remainder of the row starts over from a reset
+        assert!(ansi.ends_with("\x1b[0m  \x1b[0m\r\n"), "{ansi:?}");
+    }
+}
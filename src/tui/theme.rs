@@ -4,6 +4,10 @@
 //! Color schemes and palettes for TUI theming.
 
 use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::io;
+use std::path::Path;
 
 /// C64 palette colors.
 #[allow(dead_code)]
@@ -74,27 +78,258 @@ mod gruvbox {
     pub const ORANGE: Color = Color::Rgb(0xfe, 0x80, 0x19);
 }
 
+/// Nord theme colors (Polar Night, Snow Storm, Frost, Aurora).
+mod nord {
+    use ratatui::style::Color;
+    pub const NORD0: Color = Color::Rgb(0x2e, 0x34, 0x40);
+    pub const NORD2: Color = Color::Rgb(0x43, 0x4c, 0x5e);
+    pub const NORD3: Color = Color::Rgb(0x4c, 0x56, 0x6a);
+    pub const NORD4: Color = Color::Rgb(0xd8, 0xde, 0xe9);
+    pub const NORD5: Color = Color::Rgb(0xe5, 0xe9, 0xf0);
+    pub const NORD6: Color = Color::Rgb(0xec, 0xef, 0xf4);
+    pub const NORD7: Color = Color::Rgb(0x8f, 0xbc, 0xbb);
+    pub const NORD8: Color = Color::Rgb(0x88, 0xc0, 0xd0);
+    pub const NORD9: Color = Color::Rgb(0x81, 0xa1, 0xc1);
+    pub const NORD10: Color = Color::Rgb(0x5e, 0x81, 0xac);
+    pub const NORD11: Color = Color::Rgb(0xbf, 0x61, 0x6a);
+    pub const NORD12: Color = Color::Rgb(0xd0, 0x87, 0x70);
+    pub const NORD13: Color = Color::Rgb(0xeb, 0xcb, 0x8b);
+    pub const NORD14: Color = Color::Rgb(0xa3, 0xbe, 0x8c);
+    pub const NORD15: Color = Color::Rgb(0xb4, 0x8e, 0xad);
+}
+
+/// Solarized theme colors (shared by the dark and light variants).
+mod solarized {
+    use ratatui::style::Color;
+    pub const BASE03: Color = Color::Rgb(0x00, 0x2b, 0x36);
+    pub const BASE02: Color = Color::Rgb(0x07, 0x36, 0x42);
+    pub const BASE01: Color = Color::Rgb(0x58, 0x6e, 0x75);
+    pub const BASE00: Color = Color::Rgb(0x65, 0x7b, 0x83);
+    pub const BASE0: Color = Color::Rgb(0x83, 0x94, 0x96);
+    pub const BASE1: Color = Color::Rgb(0x93, 0xa1, 0xa1);
+    pub const BASE2: Color = Color::Rgb(0xee, 0xe8, 0xd5);
+    pub const BASE3: Color = Color::Rgb(0xfd, 0xf6, 0xe3);
+    pub const YELLOW: Color = Color::Rgb(0xb5, 0x89, 0x00);
+    pub const ORANGE: Color = Color::Rgb(0xcb, 0x4b, 0x16);
+    pub const RED: Color = Color::Rgb(0xdc, 0x32, 0x2f);
+    pub const MAGENTA: Color = Color::Rgb(0xd3, 0x36, 0x82);
+    pub const VIOLET: Color = Color::Rgb(0x6c, 0x71, 0xc4);
+    pub const BLUE: Color = Color::Rgb(0x26, 0x8b, 0xd2);
+    pub const CYAN: Color = Color::Rgb(0x2a, 0xa1, 0x98);
+    pub const GREEN: Color = Color::Rgb(0x85, 0x99, 0x00);
+}
+
+/// One Dark Pro theme colors (Atom/VS Code).
+mod one_dark {
+    use ratatui::style::Color;
+    pub const BACKGROUND: Color = Color::Rgb(0x28, 0x2c, 0x34);
+    pub const FOREGROUND: Color = Color::Rgb(0xab, 0xb2, 0xbf);
+    pub const RED: Color = Color::Rgb(0xe0, 0x6c, 0x75);
+    pub const GREEN: Color = Color::Rgb(0x98, 0xc3, 0x79);
+    pub const BLUE: Color = Color::Rgb(0x61, 0xaf, 0xef);
+    pub const YELLOW: Color = Color::Rgb(0xe5, 0xc0, 0x7b);
+    pub const PURPLE: Color = Color::Rgb(0xc6, 0x78, 0xdd);
+    pub const TEAL: Color = Color::Rgb(0x56, 0xb6, 0xc2);
+    pub const ORANGE: Color = Color::Rgb(0xd1, 0x9a, 0x66);
+    pub const GREY: Color = Color::Rgb(0x5c, 0x63, 0x70);
+    pub const GUTTER: Color = Color::Rgb(0x3e, 0x44, 0x51);
+}
+
+/// High contrast theme colors (WCAG AA on black).
+mod high_contrast {
+    use ratatui::style::Color;
+    pub const RED: Color = Color::Rgb(0xFF, 0x00, 0x00);
+    pub const GREEN: Color = Color::Rgb(0x00, 0xC8, 0x00);
+    pub const BLUE: Color = Color::Rgb(0x66, 0x99, 0xFF);
+    pub const CYAN: Color = Color::Rgb(0x00, 0xFF, 0xFF);
+    pub const YELLOW: Color = Color::Rgb(0xFF, 0xFF, 0x00);
+    pub const MAGENTA: Color = Color::Rgb(0xFF, 0x00, 0xFF);
+    pub const ORANGE: Color = Color::Rgb(0xFF, 0xA5, 0x00);
+    pub const LIME: Color = Color::Rgb(0xBF, 0xFF, 0x00);
+    pub const GRAY: Color = Color::Rgb(0x99, 0x99, 0x99);
+    pub const LIGHT_GRAY: Color = Color::Rgb(0xCC, 0xCC, 0xCC);
+}
+
+/// Approximate RGB values of a terminal color (`None` for indexed/reset colors).
+const fn color_to_rgb(color: Color) -> Option<[u8; 3]> {
+    match color {
+        Color::Rgb(r, g, b) => Some([r, g, b]),
+        Color::Black => Some([0x00; 3]),
+        Color::White => Some([0xFF; 3]),
+        Color::Gray => Some([0xC0; 3]),
+        Color::DarkGray => Some([0x80; 3]),
+        Color::Red | Color::LightRed => Some([0xFF, 0x00, 0x00]),
+        Color::Green | Color::LightGreen => Some([0x00, 0xFF, 0x00]),
+        Color::Blue | Color::LightBlue => Some([0x00, 0x00, 0xFF]),
+        Color::Yellow | Color::LightYellow => Some([0xFF, 0xFF, 0x00]),
+        Color::Magenta | Color::LightMagenta => Some([0xFF, 0x00, 0xFF]),
+        Color::Cyan | Color::LightCyan => Some([0x00, 0xFF, 0xFF]),
+        _ => None,
+    }
+}
+
+/// WCAG relative luminance of an sRGB color.
+#[cfg(test)]
+fn relative_luminance([r, g, b]: [u8; 3]) -> f64 {
+    let linear = |c: u8| {
+        let c = f64::from(c) / 255.0;
+        if c <= 0.040_45 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126f64.mul_add(linear(r), 0.7152f64.mul_add(linear(g), 0.0722 * linear(b)))
+}
+
+/// WCAG contrast ratio between two colors (1.0-21.0; AA text needs 4.5).
+/// Colors without a known RGB value report 1.0.
+#[cfg(test)]
+fn wcag_contrast_ratio(fg: Color, bg: Color) -> f64 {
+    let (Some(fg), Some(bg)) = (color_to_rgb(fg), color_to_rgb(bg)) else {
+        return 1.0;
+    };
+    let (fg, bg) = (relative_luminance(fg), relative_luminance(bg));
+    (fg.max(bg) + 0.05) / (fg.min(bg) + 0.05)
+}
+
+/// Serde helpers storing colors as `#rrggbb` strings.
+mod hex_color {
+    use super::color_to_rgb;
+    use ratatui::style::Color;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn to_hex(color: Color) -> String {
+        let [r, g, b] = color_to_rgb(color).unwrap_or([0xFF; 3]);
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+
+    pub fn parse(s: &str) -> Option<Color> {
+        let hex = s.strip_prefix('#')?;
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    fn parse_or_error<E: Error>(s: &str) -> Result<Color, E> {
+        parse(s).ok_or_else(|| E::custom(format!("invalid color {s:?}, expected #rrggbb")))
+    }
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex(*color))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        parse_or_error(&String::deserialize(deserializer)?)
+    }
+
+    /// Same as the parent module, for the 9 voice colors.
+    pub mod voices {
+        use super::{parse_or_error, to_hex};
+        use ratatui::style::Color;
+        use serde::de::Error;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            colors: &[Color; 9],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            colors.map(to_hex).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<[Color; 9], D::Error> {
+            let hex = Vec::<String>::deserialize(deserializer)?;
+            let colors = hex
+                .iter()
+                .map(|s| parse_or_error(s))
+                .collect::<Result<Vec<_>, D::Error>>()?;
+            let count = colors.len();
+            colors
+                .try_into()
+                .map_err(|_| D::Error::invalid_length(count, &"9 voice colors"))
+        }
+    }
+}
+
 /// Complete color scheme for TUI theming.
 /// Voice colors support up to 9 voices (3 SIDs × 3 voices).
-#[derive(Clone, Copy)]
+/// Serialized with colors as `#rrggbb` strings (theme files and `Config`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ColorScheme {
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
+    #[serde(with = "hex_color")]
     pub background: Color,
     /// Colors for up to 9 voices (3 per SID chip)
+    #[serde(with = "hex_color::voices")]
     pub voices: [Color; 9],
+    #[serde(with = "hex_color")]
     pub accent: Color,
+    #[serde(with = "hex_color")]
     pub title: Color,
+    #[serde(with = "hex_color")]
     pub border_focus: Color,
+    #[serde(with = "hex_color")]
     pub border_dim: Color,
+    #[serde(with = "hex_color")]
     pub text_primary: Color,
+    #[serde(with = "hex_color")]
     pub text_secondary: Color,
+    #[serde(with = "hex_color")]
     pub highlight_bg: Color,
+    #[serde(with = "hex_color")]
     pub highlight_fg: Color,
 }
 
+impl ColorScheme {
+    /// Returns a copy with voice colors replaced by RGB overrides, if any.
+    pub fn with_voice_colors(mut self, colors: Option<&[[u8; 3]; 9]>) -> Self {
+        if let Some(colors) = colors {
+            for (voice, &[r, g, b]) in self.voices.iter_mut().zip(colors) {
+                *voice = Color::Rgb(r, g, b);
+            }
+        }
+        self
+    }
+
+    /// Returns the voice colors as RGB triples (non-RGB colors map to white).
+    pub fn voice_rgb(&self) -> [[u8; 3]; 9] {
+        self.voices.map(|color| match color {
+            Color::Rgb(r, g, b) => [r, g, b],
+            _ => [0xFF; 3],
+        })
+    }
+}
+
+/// Writes the built-in or custom scheme called `name` to `path` as JSON.
+pub fn export_theme(name: &str, custom: &[ColorScheme], path: &Path) -> io::Result<()> {
+    let scheme = SCHEMES
+        .iter()
+        .chain(custom)
+        .find(|s| s.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no color scheme named '{name}'"),
+            )
+        })?;
+    let json = serde_json::to_string_pretty(scheme).map_err(io::Error::other)?;
+    std::fs::write(path, json + "\n")
+}
+
+/// Reads a JSON theme file, rejecting missing fields and malformed colors.
+pub fn import_theme(path: &Path) -> io::Result<ColorScheme> {
+    let json = std::fs::read_to_string(path)?;
+    serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
 pub const SCHEMES: &[ColorScheme] = &[
     ColorScheme {
-        name: "Dark Primary",
+        name: Cow::Borrowed("Dark Primary"),
         background: c64::BLACK,
         voices: [
             c64::RED,
@@ -117,7 +352,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: c64::CYAN,
     },
     ColorScheme {
-        name: "Warm",
+        name: Cow::Borrowed("Warm"),
         background: c64::BLACK,
         voices: [
             c64::ORANGE,
@@ -140,7 +375,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: c64::YELLOW,
     },
     ColorScheme {
-        name: "Cool",
+        name: Cow::Borrowed("Cool"),
         background: c64::BLACK,
         voices: [
             c64::PURPLE,
@@ -163,7 +398,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: c64::CYAN,
     },
     ColorScheme {
-        name: "Monochrome",
+        name: Cow::Borrowed("Monochrome"),
         background: c64::BLACK,
         voices: [
             c64::LIGHT_GREY,
@@ -186,7 +421,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: c64::GREEN,
     },
     ColorScheme {
-        name: "Neon",
+        name: Cow::Borrowed("Neon"),
         background: c64::BLACK,
         voices: [
             c64::LIGHT_RED,
@@ -209,7 +444,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: c64::CYAN,
     },
     ColorScheme {
-        name: "C64",
+        name: Cow::Borrowed("C64"),
         background: c64::BLUE,
         voices: [
             c64::LIGHT_BLUE,
@@ -232,7 +467,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: c64::WHITE,
     },
     ColorScheme {
-        name: "Frost",
+        name: Cow::Borrowed("Frost"),
         background: c64::BLUE,
         voices: [
             c64::WHITE,
@@ -255,7 +490,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: c64::CYAN,
     },
     ColorScheme {
-        name: "VIC-20",
+        name: Cow::Borrowed("VIC-20"),
         background: c64::CYAN,
         voices: [
             c64::BLUE,
@@ -278,7 +513,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: c64::CYAN,
     },
     ColorScheme {
-        name: "C128",
+        name: Cow::Borrowed("C128"),
         background: c64::DARK_GREY,
         voices: [
             c64::LIGHT_GREEN,
@@ -301,7 +536,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: c64::DARK_GREY,
     },
     ColorScheme {
-        name: "PET",
+        name: Cow::Borrowed("PET"),
         background: c64::BLACK,
         voices: [
             c64::GREEN,
@@ -324,7 +559,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: c64::BLACK,
     },
     ColorScheme {
-        name: "Dracula",
+        name: Cow::Borrowed("Dracula"),
         background: dracula::BACKGROUND,
         voices: [
             dracula::PINK,
@@ -347,7 +582,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: dracula::CYAN,
     },
     ColorScheme {
-        name: "Monokai",
+        name: Cow::Borrowed("Monokai"),
         background: monokai::BACKGROUND,
         voices: [
             monokai::RED,
@@ -370,7 +605,7 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_fg: monokai::CYAN,
     },
     ColorScheme {
-        name: "Gruvbox Dark Hard",
+        name: Cow::Borrowed("Gruvbox Dark Hard"),
         background: gruvbox::BG,
         voices: [
             gruvbox::RED,
@@ -392,4 +627,209 @@ pub const SCHEMES: &[ColorScheme] = &[
         highlight_bg: gruvbox::GRAY,
         highlight_fg: gruvbox::YELLOW,
     },
+    ColorScheme {
+        name: Cow::Borrowed("High Contrast"),
+        background: Color::Black,
+        voices: [
+            high_contrast::RED,
+            high_contrast::GREEN,
+            high_contrast::BLUE,
+            high_contrast::CYAN,
+            high_contrast::YELLOW,
+            high_contrast::MAGENTA,
+            Color::White,
+            high_contrast::ORANGE,
+            high_contrast::LIME,
+        ],
+        accent: high_contrast::YELLOW,
+        title: high_contrast::YELLOW,
+        border_focus: Color::White,
+        border_dim: high_contrast::GRAY,
+        text_primary: Color::White,
+        text_secondary: high_contrast::LIGHT_GRAY,
+        highlight_bg: high_contrast::YELLOW,
+        highlight_fg: Color::Black,
+    },
+    ColorScheme {
+        name: Cow::Borrowed("Nord"),
+        background: nord::NORD0,
+        voices: [
+            nord::NORD11,
+            nord::NORD14,
+            nord::NORD9,
+            nord::NORD13,
+            nord::NORD12,
+            nord::NORD10,
+            nord::NORD15,
+            nord::NORD5,
+            nord::NORD6,
+        ],
+        accent: nord::NORD8,
+        title: nord::NORD7,
+        border_focus: nord::NORD8,
+        border_dim: nord::NORD3,
+        text_primary: nord::NORD4,
+        text_secondary: nord::NORD9,
+        highlight_bg: nord::NORD2,
+        highlight_fg: nord::NORD6,
+    },
+    ColorScheme {
+        name: Cow::Borrowed("Solarized Dark"),
+        background: solarized::BASE03,
+        voices: [
+            solarized::RED,
+            solarized::GREEN,
+            solarized::YELLOW,
+            solarized::BLUE,
+            solarized::MAGENTA,
+            solarized::CYAN,
+            solarized::ORANGE,
+            solarized::VIOLET,
+            solarized::BASE1,
+        ],
+        accent: solarized::CYAN,
+        title: solarized::YELLOW,
+        border_focus: solarized::CYAN,
+        border_dim: solarized::BASE01,
+        text_primary: solarized::BASE0,
+        text_secondary: solarized::BASE01,
+        highlight_bg: solarized::BASE02,
+        highlight_fg: solarized::BASE1,
+    },
+    ColorScheme {
+        name: Cow::Borrowed("Solarized Light"),
+        background: solarized::BASE3,
+        voices: [
+            solarized::RED,
+            solarized::GREEN,
+            solarized::YELLOW,
+            solarized::BLUE,
+            solarized::MAGENTA,
+            solarized::CYAN,
+            solarized::ORANGE,
+            solarized::VIOLET,
+            solarized::BASE01,
+        ],
+        accent: solarized::CYAN,
+        title: solarized::YELLOW,
+        border_focus: solarized::CYAN,
+        border_dim: solarized::BASE1,
+        text_primary: solarized::BASE00,
+        text_secondary: solarized::BASE1,
+        highlight_bg: solarized::BASE2,
+        highlight_fg: solarized::BASE01,
+    },
+    ColorScheme {
+        name: Cow::Borrowed("One Dark"),
+        background: one_dark::BACKGROUND,
+        voices: [
+            one_dark::RED,
+            one_dark::GREEN,
+            one_dark::BLUE,
+            one_dark::YELLOW,
+            one_dark::PURPLE,
+            one_dark::TEAL,
+            one_dark::ORANGE,
+            one_dark::FOREGROUND,
+            one_dark::GREY,
+        ],
+        accent: one_dark::BLUE,
+        title: one_dark::RED,
+        border_focus: one_dark::BLUE,
+        border_dim: one_dark::GUTTER,
+        text_primary: one_dark::FOREGROUND,
+        text_secondary: one_dark::GREY,
+        highlight_bg: one_dark::GUTTER,
+        highlight_fg: one_dark::YELLOW,
+    },
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn high_contrast_meets_wcag_aa() {
+        let scheme = SCHEMES
+            .iter()
+            .find(|s| s.name == "High Contrast")
+            .expect("high contrast scheme");
+        let others = [
+            scheme.accent,
+            scheme.title,
+            scheme.border_focus,
+            scheme.border_dim,
+            scheme.text_primary,
+            scheme.text_secondary,
+        ];
+        let foregrounds = scheme.voices.iter().chain(&others);
+        for &fg in foregrounds {
+            let ratio = wcag_contrast_ratio(fg, scheme.background);
+            assert!(ratio >= 4.5, "{fg:?} on background: {ratio:.2}");
+        }
+        assert!(wcag_contrast_ratio(scheme.highlight_fg, scheme.highlight_bg) >= 4.5);
+        assert!((wcag_contrast_ratio(Color::White, Color::Black) - 21.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn nord_scheme_has_distinct_voices() {
+        let nord = SCHEMES
+            .iter()
+            .find(|s| s.name == "Nord")
+            .expect("nord scheme");
+        assert_ne!(nord.voices[0], nord.voices[1]);
+        assert_eq!(nord.background, Color::Rgb(0x2e, 0x34, 0x40));
+    }
+
+    #[test]
+    fn one_dark_voices_are_distinct() {
+        let one_dark = SCHEMES
+            .iter()
+            .find(|s| s.name == "One Dark")
+            .expect("one dark scheme");
+        let distinct: std::collections::HashSet<_> = one_dark
+            .voices
+            .iter()
+            .filter_map(|&c| color_to_rgb(c))
+            .collect();
+        assert_eq!(distinct.len(), 9);
+    }
+
+    #[test]
+    fn theme_json_roundtrip() {
+        let dracula = SCHEMES.iter().find(|s| s.name == "Dracula").unwrap();
+        let json = serde_json::to_string(dracula).unwrap();
+        assert!(json.contains("\"background\":\"#282a36\""));
+        let parsed: ColorScheme = serde_json::from_str(&json).unwrap();
+        assert_eq!(&parsed, dracula);
+    }
+
+    #[test]
+    fn theme_json_missing_field_is_rejected() {
+        let dracula = SCHEMES.iter().find(|s| s.name == "Dracula").unwrap();
+        let mut value = serde_json::to_value(dracula).unwrap();
+        value.as_object_mut().unwrap().remove("accent");
+        let error = serde_json::from_value::<ColorScheme>(value).unwrap_err();
+        assert!(error.to_string().contains("accent"));
+        assert_eq!(hex_color::parse("#12345"), None);
+        assert_eq!(hex_color::parse("#gg0000"), None);
+    }
+
+    #[test]
+    fn voice_color_override_roundtrips_through_config() {
+        let mut colors = SCHEMES[0].voice_rgb();
+        colors[0] = [255, 0, 0];
+        let mut config = crate::config::Config::default();
+        config
+            .custom_voice_colors
+            .insert(SCHEMES[0].name.to_string(), colors);
+        let text = toml::to_string_pretty(&config).expect("serialize config");
+        let loaded: crate::config::Config = toml::from_str(&text).expect("parse config");
+
+        let scheme = SCHEMES[0]
+            .clone()
+            .with_voice_colors(loaded.custom_voice_colors.get(SCHEMES[0].name.as_ref()));
+        assert_eq!(scheme.voices[0], Color::Rgb(255, 0, 0));
+        assert_eq!(scheme.voices[1..], SCHEMES[0].voices[1..]);
+    }
+}
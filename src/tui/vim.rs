@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Mikael Lund
+
+//! Vim-style key sequences for the browsers (`gg`, `G`, `5j`, `/pattern`, `n`/`N`).
+
+use crossterm::event::KeyCode;
+
+/// Maximum length of a pending count or `g` sequence (search patterns are not capped)
+pub const MAX_KEY_BUFFER: usize = 10;
+
+/// Largest accepted count prefix (e.g. `999j`)
+const MAX_COUNT: u32 = 9999;
+
+/// A complete browser navigation command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VimCommand {
+    /// `gg`: select the first entry
+    First,
+    /// `G`: select the last entry
+    Last,
+    /// `[count]j`
+    Down(u32),
+    /// `[count]k`
+    Up(u32),
+    /// `/pattern` followed by Enter: selects the next entry starting with the
+    /// pattern, leaving the listing unfiltered
+    Search(String),
+    /// `n`: next match of the last search
+    NextMatch,
+    /// `N`: previous match of the last search
+    PrevMatch,
+}
+
+/// Result of feeding one key into the sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VimInput {
+    /// Part of an unfinished sequence
+    Pending,
+    /// Sequence completed
+    Command(VimCommand),
+    /// Not a vim key; falls through to the regular bindings
+    NotHandled,
+}
+
+/// Count prefix parsed from the pending sequence.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VimState {
+    /// Repeat count typed before a motion (`5` in `5j`)
+    pub pending_count: Option<u32>,
+}
+
+impl VimState {
+    /// Feeds a key into `buffer`, returning the command once the sequence is complete.
+    pub fn feed(&mut self, buffer: &mut String, key: KeyCode) -> VimInput {
+        if buffer.starts_with('/') {
+            return self.feed_search(buffer, key);
+        }
+
+        let KeyCode::Char(c) = key else {
+            self.reset(buffer);
+            return VimInput::NotHandled;
+        };
+        let pending_g = buffer.ends_with('g');
+
+        let command = match c {
+            '0'..='9' if c != '0' || self.pending_count.is_some() => {
+                let digit = c.to_digit(10).unwrap_or(0);
+                let count = self.pending_count.unwrap_or(0) * 10 + digit;
+                self.pending_count = Some(count.min(MAX_COUNT));
+                return self.push(buffer, c);
+            }
+            'g' if pending_g => VimCommand::First,
+            'g' => return self.push(buffer, c),
+            'G' => VimCommand::Last,
+            'j' => VimCommand::Down(self.pending_count.unwrap_or(1)),
+            'k' => VimCommand::Up(self.pending_count.unwrap_or(1)),
+            'n' => VimCommand::NextMatch,
+            'N' => VimCommand::PrevMatch,
+            '/' => {
+                self.reset(buffer);
+                return self.push(buffer, c);
+            }
+            _ => {
+                self.reset(buffer);
+                return VimInput::NotHandled;
+            }
+        };
+        self.reset(buffer);
+        VimInput::Command(command)
+    }
+
+    /// Handles keys while typing a `/pattern`.
+    fn feed_search(&mut self, buffer: &mut String, key: KeyCode) -> VimInput {
+        match key {
+            KeyCode::Enter => {
+                let pattern = buffer[1..].to_string();
+                self.reset(buffer);
+                VimInput::Command(VimCommand::Search(pattern))
+            }
+            KeyCode::Esc => {
+                self.reset(buffer);
+                VimInput::Pending
+            }
+            KeyCode::Backspace => {
+                buffer.pop();
+                VimInput::Pending
+            }
+            KeyCode::Char(c) => {
+                buffer.push(c);
+                VimInput::Pending
+            }
+            _ => VimInput::Pending,
+        }
+    }
+
+    /// Appends a key, dropping the sequence if it grows past `MAX_KEY_BUFFER`.
+    fn push(&mut self, buffer: &mut String, c: char) -> VimInput {
+        if buffer.chars().count() >= MAX_KEY_BUFFER {
+            self.reset(buffer);
+        } else {
+            buffer.push(c);
+        }
+        VimInput::Pending
+    }
+
+    fn reset(&mut self, buffer: &mut String) {
+        buffer.clear();
+        self.pending_count = None;
+    }
+}
+
+/// Returns `current` moved by `delta`, clamped to a list of `len` entries.
+pub fn step_index(current: usize, len: usize, delta: i64) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    let target = i64::try_from(current)
+        .unwrap_or(i64::MAX)
+        .saturating_add(delta);
+    usize::try_from(target.max(0))
+        .unwrap_or(usize::MAX)
+        .min(len - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_all(keys: &str) -> (VimInput, String) {
+        let mut state = VimState::default();
+        let mut buffer = String::new();
+        let mut last = VimInput::NotHandled;
+        for c in keys.chars() {
+            last = state.feed(&mut buffer, KeyCode::Char(c));
+        }
+        (last, buffer)
+    }
+
+    #[test]
+    fn gg_selects_first_entry() {
+        assert_eq!(feed_all("g"), (VimInput::Pending, "g".to_string()));
+        assert_eq!(
+            feed_all("gg"),
+            (VimInput::Command(VimCommand::First), String::new())
+        );
+        assert_eq!(step_index(7, 10, i64::MIN), 0);
+    }
+
+    #[test]
+    fn count_prefix_moves_by_count() {
+        let (input, _) = feed_all("3j");
+        assert_eq!(input, VimInput::Command(VimCommand::Down(3)));
+        assert_eq!(step_index(2, 10, 3), 5);
+        assert_eq!(step_index(8, 10, 3), 9);
+        assert_eq!(feed_all("12k").0, VimInput::Command(VimCommand::Up(12)));
+        assert_eq!(feed_all("q").0, VimInput::NotHandled);
+    }
+
+    #[test]
+    fn search_pattern_completes_on_enter() {
+        let mut state = VimState::default();
+        let mut buffer = String::new();
+        for c in "/hub".chars() {
+            assert_eq!(state.feed(&mut buffer, KeyCode::Char(c)), VimInput::Pending);
+        }
+        assert_eq!(buffer, "/hub");
+        assert_eq!(
+            state.feed(&mut buffer, KeyCode::Enter),
+            VimInput::Command(VimCommand::Search("hub".to_string()))
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn key_buffer_is_capped() {
+        let (_, buffer) = feed_all("123456789012");
+        assert!(buffer.chars().count() <= MAX_KEY_BUFFER);
+    }
+
+    #[test]
+    fn long_search_pattern_is_kept_whole() {
+        let (_, mut buffer) = feed_all("/hubbard rob");
+        assert_eq!(buffer, "/hubbard rob");
+        assert_eq!(
+            VimState::default().feed(&mut buffer, KeyCode::Enter),
+            VimInput::Command(VimCommand::Search("hubbard rob".to_string()))
+        );
+    }
+}
@@ -3,6 +3,8 @@
 
 //! Display state structs for VU meters and oscilloscopes.
 
+use crate::player::VoiceLevels;
+use std::collections::VecDeque;
 use std::time::Instant;
 
 /// Number of samples to display in oscilloscope (downsampled from player buffer)
@@ -11,6 +13,15 @@ pub const SCOPE_DISPLAY_SAMPLES: usize = 256;
 const ATTACK_RATE: f32 = 0.7;
 const DECAY_RATE: f32 = 0.92;
 const PEAK_HOLD_MS: u128 = 500;
+/// Peaks at or above this level count as pinned at maximum
+const PEAK_MAX: f32 = 0.98;
+/// How long a peak must stay pinned before its indicator flashes
+const PEAK_FLASH_MS: u128 = 1000;
+/// Half period of the flashing peak indicator
+const PEAK_BLINK_MS: u128 = 250;
+
+/// Number of frames of peak history kept for export (10 s at 30 fps)
+pub const PEAK_HISTORY_LEN: usize = 300;
 
 /// Blend factor for oscilloscope persistence (0.0 = instant, 1.0 = frozen)
 const SCOPE_PERSISTENCE: f32 = 0.6;
@@ -21,6 +32,12 @@ pub struct VuMeter {
     pub levels: Vec<f32>,
     pub peaks: Vec<f32>,
     peak_hold: Vec<Instant>,
+    /// When each peak reached maximum, if it is still there
+    peak_max_since: Vec<Option<Instant>>,
+    /// Number of SID chips the voices are grouped into
+    sid_count: usize,
+    /// Peaks of the last `PEAK_HISTORY_LEN` updates, oldest first
+    peak_history: VecDeque<Vec<f32>>,
 }
 
 impl VuMeter {
@@ -31,16 +48,20 @@ impl VuMeter {
             levels: vec![0.0; voice_count],
             peaks: vec![0.0; voice_count],
             peak_hold: vec![now; voice_count],
+            peak_max_since: vec![None; voice_count],
+            sid_count: voice_count.div_ceil(3),
+            peak_history: VecDeque::with_capacity(PEAK_HISTORY_LEN),
         }
     }
 
     /// Update meters with new envelope values, applying smoothing.
     /// Resizes internal storage if voice count changes.
-    pub fn update(&mut self, envelope: &[u8]) {
-        self.resize_if_needed(envelope.len());
+    pub fn update(&mut self, envelope: &VoiceLevels) {
+        self.resize_if_needed(envelope.as_slice().len());
+        self.sid_count = envelope.iter_sids().count();
 
         let now = Instant::now();
-        for (i, &env) in envelope.iter().enumerate() {
+        for (i, &env) in envelope.iter_sids().flatten().enumerate() {
             let target = f32::from(env) / 255.0;
 
             // Fast attack, slow decay for classic VU behavior
@@ -57,7 +78,24 @@ impl VuMeter {
             } else if now.duration_since(self.peak_hold[i]).as_millis() > PEAK_HOLD_MS {
                 self.peaks[i] *= 0.95;
             }
+
+            if self.peaks[i] < PEAK_MAX {
+                self.peak_max_since[i] = None;
+            } else if self.peak_max_since[i].is_none() {
+                self.peak_max_since[i] = Some(now);
+            }
+        }
+
+        if self.peak_history.len() == PEAK_HISTORY_LEN {
+            self.peak_history.pop_front();
         }
+        self.peak_history.push_back(self.peaks.clone());
+    }
+
+    /// Returns the peaks of the last `n_frames` updates, oldest first.
+    pub fn peak_history(&self, n_frames: usize) -> Vec<Vec<f32>> {
+        let skip = self.peak_history.len().saturating_sub(n_frames);
+        self.peak_history.iter().skip(skip).cloned().collect()
     }
 
     fn resize_if_needed(&mut self, voice_count: usize) {
@@ -66,13 +104,30 @@ impl VuMeter {
             self.levels.resize(voice_count, 0.0);
             self.peaks.resize(voice_count, 0.0);
             self.peak_hold.resize(voice_count, now);
+            self.peak_max_since.resize(voice_count, None);
         }
     }
 
+    /// True while the peak indicator of `voice` should be drawn red: its peak
+    /// has been at maximum for over a second, blinking on and off.
+    pub fn peak_flash(&self, voice: usize) -> bool {
+        self.peak_max_since
+            .get(voice)
+            .copied()
+            .flatten()
+            .map(|since| since.elapsed().as_millis())
+            .is_some_and(|held| held > PEAK_FLASH_MS && (held / PEAK_BLINK_MS).is_multiple_of(2))
+    }
+
     /// Returns the number of voices being tracked.
     pub fn voice_count(&self) -> usize {
         self.levels.len()
     }
+
+    /// Returns the number of SID chips (groups of three voices).
+    pub fn sid_count(&self) -> usize {
+        self.sid_count
+    }
 }
 
 /// Per-voice envelope scope buffers.
@@ -124,3 +179,30 @@ impl VoiceScopes {
         self.samples.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peak_history_keeps_last_frames() {
+        let mut meter = VuMeter::with_voice_count(3);
+        let levels = VoiceLevels(vec![255, 128, 0]);
+        for _ in 0..60 {
+            meter.update(&levels);
+        }
+        let history = meter.peak_history(PEAK_HISTORY_LEN);
+        assert_eq!(history.len(), 60);
+        assert!(history.iter().all(|peaks| peaks.len() == 3));
+        assert_eq!(meter.peak_history(10).len(), 10);
+
+        // Frames from a second SID push out the three-voice frames
+        let levels = VoiceLevels(vec![0; 6]);
+        for _ in 0..PEAK_HISTORY_LEN {
+            meter.update(&levels);
+        }
+        let history = meter.peak_history(PEAK_HISTORY_LEN + 1);
+        assert_eq!(history.len(), PEAK_HISTORY_LEN);
+        assert!(history.iter().all(|peaks| peaks.len() == 6));
+    }
+}
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: MIT
+// Copyright (c) 2026 Mikael Lund
+
+//! Minimal WAV export for recorded player output.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const BITS_PER_SAMPLE: u16 = 16;
+const CHANNELS: u16 = 1;
+const HEADER_SIZE: usize = 44;
+
+/// Writes mono f32 samples (-1.0..1.0) as a 16-bit PCM WAV file.
+pub fn write_wav(path: &Path, samples: &[f32], sample_rate: u32) -> io::Result<()> {
+    fs::write(path, wav_bytes(samples, sample_rate)?)
+}
+
+fn wav_bytes(samples: &[f32], sample_rate: u32) -> io::Result<Vec<u8>> {
+    let block_align = CHANNELS * BITS_PER_SAMPLE / 8;
+    let data_size = u32::try_from(samples.len() * usize::from(block_align))
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Recording too long for WAV"))?;
+
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + data_size as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_size).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes()); // fmt chunk size
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&CHANNELS.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * u32::from(block_align)).to_le_bytes());
+    bytes.extend_from_slice(&block_align.to_le_bytes());
+    bytes.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_size.to_le_bytes());
+
+    for &sample in samples {
+        #[allow(clippy::cast_possible_truncation)]
+        let pcm = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16;
+        bytes.extend_from_slice(&pcm.to_le_bytes());
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_matches_sample_count() {
+        let bytes = wav_bytes(&[0.0, 1.0, -1.0], 44_100).expect("encode WAV");
+        assert_eq!(bytes.len(), HEADER_SIZE + 6);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(&bytes[24..28], &44_100u32.to_le_bytes());
+        assert_eq!(&bytes[40..44], &6u32.to_le_bytes());
+        assert_eq!(&bytes[46..48], &i16::MAX.to_le_bytes());
+    }
+}